@@ -0,0 +1,46 @@
+//! Alert-pin wakeup: program thresholds, run auto mode and sleep until the ALERT pin fires,
+//! then report which limit was crossed.
+//!
+//! On target, pass the GPIO wired to ALERT; with the async API, `wait_for_alert_async()` lets
+//! the executor sleep on the pin edge.
+
+use core::convert::Infallible;
+
+use embedded_hal::digital::{ErrorType, InputPin};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, SampleRate};
+
+/// ALERT pin that reads high after `low_polls` polls, standing in for a GPIO input
+struct AlertPin {
+    low_polls: u32,
+}
+impl ErrorType for AlertPin {
+    type Error = Infallible;
+}
+impl InputPin for AlertPin {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        self.low_polls = self.low_polls.saturating_sub(1);
+        Ok(self.low_polls == 0)
+    }
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+fn main() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.write_alert_limits(&AlertLimits::FREEZER).unwrap();
+    hdc302x.auto_start(SampleRate::Auto1Hz, LowPowerMode::lowest_power()).unwrap();
+
+    // the freezer door was left open
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.set_environment(2.0, 60.0);
+    sim.advance_auto(1);
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+
+    let mut pin = AlertPin { low_polls: 3 };
+    let status = hdc302x.wait_for_alert(&mut pin).unwrap();
+    println!("alert: {status}");
+    let report = hdc302x.read_alert_report().unwrap();
+    println!("temperature: {:?}, humidity: {:?}", report.temperature, report.humidity);
+}
@@ -0,0 +1,55 @@
+//! Async sampling loop, structured like an Embassy task.
+//!
+//! On target, move `sample_task()` into an `#[embassy_executor::task]`, pass the HAL's async I2C
+//! and `embassy_time::Delay`, and drop `block_on()`.  Here it runs on the host against the
+//! simulated device, so it stays compiling against the real API.
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use embedded_hal_async::delay::DelayNs;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum};
+
+/// Delay that returns immediately, standing in for `embassy_time::Delay`
+struct NoopDelay;
+impl DelayNs for NoopDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+async fn sample_task<I2C, D>(hdc302x: &mut Hdc302x<I2C, D>, samples: u32)
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    I2C::Error: core::fmt::Debug,
+    D: DelayNs,
+{
+    let manufacturer_id = hdc302x.read_manufacturer_id_async().await.unwrap();
+    println!("manufacturer id: {manufacturer_id}");
+    for _ in 0..samples {
+        match hdc302x.one_shot_async(LowPowerMode::lowest_noise()).await {
+            Ok(RawDatum::TempAndRelHumid(sample)) => {
+                println!("{:.1} °C, {:.1} %RH", sample.centigrade(), sample.humidity_percent())
+            }
+            Ok(datum) => println!("unexpected datum: {datum:?}"),
+            Err(error) => println!("one_shot error: {error:?}"),
+        }
+        // on target: Timer::after_secs(10).await;
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(21.5, 45.0);
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay, I2cAddr::Addr00);
+    block_on(sample_task(&mut hdc302x, 3));
+}
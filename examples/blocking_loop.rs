@@ -0,0 +1,32 @@
+//! Blocking super-loop without an RTOS or RTIC: take a sample whenever one is due and do other
+//! work in between.
+//!
+//! On target, pass the HAL's blocking I2C and delay, and a `Clock` backed by a hardware
+//! timer.  Here the simulated device and a `ManualClock` stand in for them.
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, ManualClock, Pacer, RawDatum};
+
+fn main() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(4.0, 85.0);
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 2_000);
+    // 10 s of 500 ms loop iterations
+    for _ in 0..20 {
+        match hdc302x.one_shot_if_due(LowPowerMode::lower_power(), &mut pacer) {
+            Ok(Some(RawDatum::TempAndRelHumid(sample))) => println!(
+                "t={} ms: {:.1} °C, {:.1} %RH",
+                pacer.now_ms(),
+                sample.centigrade(),
+                sample.humidity_percent()
+            ),
+            Ok(_) => {}
+            Err(error) => println!("one_shot error: {error:?}"),
+        }
+        // other work of the loop goes here
+        clock.advance_ms(500);
+    }
+}
@@ -0,0 +1,30 @@
+//! Linux gateway: a background thread samples the sensor while the main thread forwards the
+//! latest reading, e.g. to MQTT or a database.
+//!
+//! On a Linux board, pass `linux_embedded_hal::I2cdev::new("/dev/i2c-1")` and
+//! `linux_embedded_hal::Delay` instead of the simulated device.
+
+use std::thread;
+use std::time::Duration;
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, SyncHdc302x};
+
+fn main() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(23.0, 38.0);
+    let hdc302x = SyncHdc302x::new(Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00));
+
+    println!("serial number: {}", hdc302x.read_serial_number().unwrap());
+    let sampler = hdc302x.spawn_sampler(Duration::from_millis(20), LowPowerMode::lowest_noise());
+    for _ in 0..3 {
+        thread::sleep(Duration::from_millis(50));
+        match hdc302x.latest() {
+            // forward to the backend here
+            Some(sample) => println!("{{\"celsius\":{:.2},\"rh\":{:.2}}}", sample.centigrade(), sample.humidity_percent()),
+            None => println!("no sample yet"),
+        }
+    }
+    sampler.stop();
+    println!("sampling errors: {}", hdc302x.sample_errors());
+}
@@ -0,0 +1,60 @@
+//! Factory provisioning: program calibration offsets, alert thresholds and the power-up mode
+//! into non-volatile memory once, then mirror the result so boots can detect tampering.
+//!
+//! Non-volatile memory has limited write endurance, so every write is confirmed explicitly.
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, ConfigStore, Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, NvMirrorStatus, SampleRate};
+
+/// Configuration store in RAM, standing in for flash or EEPROM
+#[derive(Default)]
+struct RamStore {
+    blob: Option<Vec<u8>>,
+}
+impl ConfigStore for RamStore {
+    type Error = ();
+    fn load(&mut self, buf: &mut [u8]) -> Result<Option<usize>, ()> {
+        let Some(blob) = &self.blob else {
+            return Ok(None);
+        };
+        buf.get_mut(..blob.len()).ok_or(())?.copy_from_slice(blob);
+        Ok(Some(blob.len()))
+    }
+    fn save(&mut self, blob: &[u8]) -> Result<(), ()> {
+        self.blob = Some(blob.to_vec());
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    println!("provisioning {}", hdc302x.read_serial_number().unwrap());
+
+    // offsets measured against a reference in the calibration chamber
+    hdc302x.confirm_nv_write();
+    let rh_offset = hdc302x.write_rh_offset(-1.5).unwrap();
+    hdc302x.confirm_nv_write();
+    let temp_offset = hdc302x.write_temp_offset(0.3).unwrap();
+    println!("programmed offsets: {rh_offset:+.2} %RH, {temp_offset:+.2} °C");
+
+    hdc302x.write_alert_limits(&AlertLimits::GREENHOUSE).unwrap();
+    hdc302x.confirm_nv_write();
+    hdc302x.store_alert_thresholds_nv().unwrap();
+
+    hdc302x.confirm_nv_write();
+    hdc302x.write_power_on_mode(SampleRate::Auto1Hz, LowPowerMode::lowest_power()).unwrap();
+
+    let mut store = RamStore::default();
+    hdc302x.mirror_nv_config(&mut store).unwrap();
+    println!("non-volatile writes: {}", hdc302x.nv_write_count());
+
+    // at every boot
+    match hdc302x.check_nv_config(&mut store).unwrap() {
+        NvMirrorStatus::Match => println!("configuration intact"),
+        status => println!("configuration mismatch: {status:?}"),
+    }
+    match hdc302x.read_power_on_mode().unwrap() {
+        Some((sample_rate, low_power_mode)) => println!("power-up mode: {sample_rate:?}, {low_power_mode:?}"),
+        None => println!("power-up mode register holds an invalid value"),
+    }
+}
@@ -0,0 +1,67 @@
+#![no_main]
+
+use crc::{Crc, CRC_8_NRSC_5};
+use hdc302x::{fuzzing, Error};
+use libfuzzer_sys::fuzz_target;
+
+const CRC: Crc<u8> = Crc::<u8>::new(&CRC_8_NRSC_5);
+
+/// Bytes the bus returns for a byte the device never sent
+const UNRECEIVED: u8 = 0xff;
+
+fuzz_target!(|frame: &[u8]| {
+    fuzzing::decode_all(frame);
+
+    // A frame is a short read if it has fewer than 3 bytes per word, or if it ends in unreceived
+    // bytes covering a whole word or leaving the last word with a bad CRC.  Otherwise it decodes
+    // if and only if every word carries a valid CRC, and the decoded words are exactly the
+    // big-endian data bytes.
+    for num_words in 0..=2 {
+        let mut words = [0u16; 2];
+        let result = fuzzing::decode_response(frame, &mut words[..num_words]);
+        let expected = 3 * num_words;
+        let short_read = if frame.len() < expected {
+            Some(frame.len())
+        } else {
+            let unreceived = frame[..expected].iter().rev().take_while(|byte| **byte == UNRECEIVED).count();
+            let last_word_unreceived = unreceived > 0 && {
+                let last = &frame[expected - 3..expected];
+                CRC.checksum(&last[0..2]) != last[2]
+            };
+            (unreceived >= 3 || last_word_unreceived).then_some(expected - unreceived)
+        };
+        let chunks = frame.chunks_exact(3).take(num_words);
+        let crc_ok = chunks.clone().all(|chunk| CRC.checksum(&chunk[0..2]) == chunk[2]);
+        match result {
+            Ok(()) => {
+                assert!(short_read.is_none() && crc_ok);
+                for (word, chunk) in words.iter().zip(chunks) {
+                    assert_eq!(*word, u16::from_be_bytes([chunk[0], chunk[1]]));
+                }
+            }
+            Err(Error::ShortRead { expected: reported, got }) => {
+                assert_eq!(reported, expected);
+                assert_eq!(short_read, Some(got));
+            }
+            Err(Error::CrcMismatch) => assert!(short_read.is_none() && !crc_ok),
+            Err(
+                error @ (Error::I2c(_)
+                | Error::InvalidInputData
+                | Error::ConfigStore
+                | Error::AutoModeRequired
+                | Error::AutoModeActive
+                | Error::HeaterActiveDuringMeasure
+                | Error::NvWriteFailed
+                | Error::AlertPin
+                | Error::SupplyTooLow
+                | Error::NvWriteRefused
+                | Error::DeviceWasReset
+                | Error::WrongMode(_)
+                | Error::Timeout
+                | Error::DeviceRejectedWrite
+                | Error::DeviceNotFound
+                | Error::InvalidAlertLimits(_)),
+            ) => panic!("decoding a frame failed with {error:?}"),
+        }
+    }
+});
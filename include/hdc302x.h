@@ -0,0 +1,54 @@
+/*
+ * C interface to the hdc302x Rust driver, built with the `ffi` feature.
+ *
+ * Functions return 0 on success, the negated driver error code (see `Error::code()`) on a
+ * driver error, or HDC302X_FFI_INVALID_ARGUMENT.
+ */
+#ifndef HDC302X_H
+#define HDC302X_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define HDC302X_FFI_INVALID_ARGUMENT (-100)
+
+/* Bytes reserved for the driver inside an hdc302x_t */
+#define HDC302X_FFI_STORAGE_SIZE 256
+
+/* I2C bus and delay callbacks; the bus callbacks return 0 on success */
+typedef struct {
+    void *ctx;
+    int32_t (*write)(void *ctx, uint8_t addr, const uint8_t *data, size_t len);
+    int32_t (*read)(void *ctx, uint8_t addr, uint8_t *data, size_t len);
+    void (*delay_us)(void *ctx, uint32_t us);
+} hdc302x_bus_t;
+
+/* Device handle holding the driver state, filled in by hdc302x_init(); opaque, do not copy */
+typedef struct {
+    _Alignas(8) uint8_t driver[HDC302X_FFI_STORAGE_SIZE];
+    uint32_t magic;
+} hdc302x_t;
+
+/* addr is the 7-bit address, 0x44 to 0x47 */
+int32_t hdc302x_init(hdc302x_t *dev, hdc302x_bus_t bus, uint8_t addr);
+
+int32_t hdc302x_measure(hdc302x_t *dev, float *centigrade, float *humidity_percent);
+
+int32_t hdc302x_read_status(hdc302x_t *dev, uint16_t *status);
+
+int32_t hdc302x_set_alerts(
+    hdc302x_t *dev,
+    float set_low_centigrade, float set_low_humidity_percent,
+    float set_high_centigrade, float set_high_humidity_percent,
+    float clear_low_centigrade, float clear_low_humidity_percent,
+    float clear_high_centigrade, float clear_high_humidity_percent);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* HDC302X_H */
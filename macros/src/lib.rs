@@ -0,0 +1,131 @@
+//! Internal procedural macros of the `hdc302x` driver.  Not a public API.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_macro_input, Expr, Ident, ImplItem, ItemImpl, LitStr, Signature};
+
+/// Generate the blocking and async flavor of an `impl` block written as async code
+///
+/// The async flavor is the block as written, gated on the `async` feature.  The blocking flavor,
+/// gated on the `blocking` feature, drops every `.await` and `async` and strips the `_async`
+/// suffix from all identifiers (so `embedded_hal_async` becomes `embedded_hal` and
+/// `read_status_async` becomes `read_status`) and from words in string literals, including doc
+/// comments and log messages.  Items marked `#[only_blocking]` or `#[only_async]` appear in one
+/// flavor only, for the few methods whose bodies differ.
+#[proc_macro_attribute]
+pub fn blocking_and_async(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(proc_macro2::Span::call_site(), "blocking_and_async takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let input = parse_macro_input!(item as ItemImpl);
+
+    let mut async_impl = input.clone();
+    keep_flavor(&mut async_impl, "only_async", "only_blocking");
+
+    let mut blocking_impl = input;
+    keep_flavor(&mut blocking_impl, "only_blocking", "only_async");
+    ToBlocking.visit_item_impl_mut(&mut blocking_impl);
+
+    quote! {
+        #[cfg(feature = "blocking")]
+        #blocking_impl
+        #[cfg(feature = "async")]
+        #async_impl
+    }
+    .into()
+}
+
+/// Drop the items marked `other` and the `keep` markers
+fn keep_flavor(item_impl: &mut ItemImpl, keep: &str, other: &str) {
+    item_impl.items.retain(|item| !item_attrs(item).iter().any(|attr| attr.path().is_ident(other)));
+    for item in &mut item_impl.items {
+        if let ImplItem::Fn(item_fn) = item {
+            item_fn.attrs.retain(|attr| !attr.path().is_ident(keep));
+        }
+    }
+}
+
+fn item_attrs(item: &ImplItem) -> &[syn::Attribute] {
+    match item {
+        ImplItem::Fn(item_fn) => &item_fn.attrs,
+        ImplItem::Const(item_const) => &item_const.attrs,
+        ImplItem::Type(item_type) => &item_type.attrs,
+        ImplItem::Macro(item_macro) => &item_macro.attrs,
+        _ => &[],
+    }
+}
+
+/// Strip `_async` from the end of every word that has it
+fn strip_async_suffix(text: &str) -> String {
+    const SUFFIX: &str = "_async";
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(SUFFIX) {
+        let (before, after) = rest.split_at(pos);
+        let after = &after[SUFFIX.len()..];
+        out.push_str(before);
+        let ends_word = !after.starts_with(is_word);
+        let has_stem = before.ends_with(is_word);
+        if !(ends_word && has_stem) {
+            out.push_str(SUFFIX);
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Rewrites async code into the equivalent blocking code
+struct ToBlocking;
+impl VisitMut for ToBlocking {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        while let Expr::Await(awaited) = expr {
+            *expr = (*awaited.base).clone();
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_signature_mut(&mut self, signature: &mut Signature) {
+        signature.asyncness = None;
+        visit_mut::visit_signature_mut(self, signature);
+    }
+
+    fn visit_ident_mut(&mut self, ident: &mut Ident) {
+        let name = ident.to_string();
+        let stripped = strip_async_suffix(&name);
+        if stripped != name {
+            *ident = Ident::new(&stripped, ident.span());
+        }
+    }
+
+    fn visit_lit_str_mut(&mut self, lit: &mut LitStr) {
+        let value = lit.value();
+        let stripped = strip_async_suffix(&value);
+        if stripped != value {
+            *lit = LitStr::new(&stripped, lit.span());
+        }
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        // log macros: rewrite the format string, keep everything else
+        let tokens = core::mem::take(&mut mac.tokens);
+        mac.tokens = tokens
+            .into_iter()
+            .map(|token| match token {
+                proc_macro2::TokenTree::Literal(literal) => match syn::parse2::<LitStr>(proc_macro2::TokenTree::Literal(literal.clone()).into()) {
+                    Ok(mut lit) => {
+                        self.visit_lit_str_mut(&mut lit);
+                        proc_macro2::TokenTree::Literal(lit.token())
+                    }
+                    Err(_) => proc_macro2::TokenTree::Literal(literal),
+                },
+                other => other,
+            })
+            .collect();
+        visit_mut::visit_macro_mut(self, mac);
+    }
+}
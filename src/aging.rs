@@ -0,0 +1,167 @@
+use crate::alert::*;
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+const MS_PER_HOUR: f32 = 3_600_000.0;
+const HOURS_PER_YEAR: f32 = 8766.0;
+
+/// Drift model used by [`ExposureDose::estimated_drift()`]
+///
+/// The defaults use the datasheet typical long-term drift of 0.19 %RH per year under normal
+/// conditions, plus an extra allowance for time spent at high humidity, which shifts the RH offset
+/// faster.  Tune the allowance to the application's qualification data where available.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriftModel {
+    /// drift per year of operation in %RH
+    pub base_percent_per_year: f32,
+    /// relative humidity in % above which exposure counts as high humidity
+    pub high_humidity_threshold_percent: f32,
+    /// additional drift per 1000 hours at high humidity in %RH
+    pub high_humidity_percent_per_1000h: f32,
+    /// estimated drift in %RH at which recalibration or reconditioning is recommended
+    pub recalibrate_at_percent: f32,
+}
+impl Default for DriftModel {
+    fn default() -> Self {
+        Self {
+            base_percent_per_year: 0.19,
+            high_humidity_threshold_percent: 80.0,
+            high_humidity_percent_per_1000h: 0.5,
+            recalibrate_at_percent: 1.0,
+        }
+    }
+}
+
+/// Result of [`ExposureDose::estimated_drift()`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriftEstimate {
+    /// expected accumulated RH drift magnitude in %RH
+    pub drift_percent: f32,
+    /// true once the drift reaches [`DriftModel::recalibrate_at_percent`]: program a new RH
+    /// offset or recondition the sensor
+    pub recalibrate: bool,
+}
+
+/// Accumulated operating time and high humidity exposure of one sensor, kept across power cycles
+/// (see [`ExposureDose::to_bytes()`]) and reset after recalibration
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExposureDose {
+    total_ms: u64,
+    high_humidity_ms: u64,
+}
+impl ExposureDose {
+    /// Version byte at the start of the blob
+    pub const BLOB_VERSION: u8 = 1;
+    /// Length of the blob produced by [`ExposureDose::to_bytes()`]
+    pub const BLOB_LEN: usize = 17;
+
+    /// Start a new dose, e.g. for a new or freshly recalibrated sensor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account for `elapsed_ms` spent at the humidity of `sample`
+    pub fn record(&mut self, sample: &RawTempAndRelHumid, elapsed_ms: u64, model: &DriftModel) {
+        self.total_ms = self.total_ms.saturating_add(elapsed_ms);
+        if sample.humidity_percent() > model.high_humidity_threshold_percent {
+            self.high_humidity_ms = self.high_humidity_ms.saturating_add(elapsed_ms);
+        }
+    }
+
+    /// Total accounted operating time in hours
+    pub fn total_hours(&self) -> f32 {
+        self.total_ms as f32 / MS_PER_HOUR
+    }
+
+    /// Accounted time at high humidity in hours
+    pub fn high_humidity_hours(&self) -> f32 {
+        self.high_humidity_ms as f32 / MS_PER_HOUR
+    }
+
+    /// Expected accumulated RH drift since the dose was started
+    pub fn estimated_drift(&self, model: &DriftModel) -> DriftEstimate {
+        let drift_percent = self.total_hours() / HOURS_PER_YEAR * model.base_percent_per_year
+            + self.high_humidity_hours() / 1000.0 * model.high_humidity_percent_per_1000h;
+        DriftEstimate {
+            drift_percent,
+            recalibrate: drift_percent >= model.recalibrate_at_percent,
+        }
+    }
+
+    /// Serialize for non-volatile storage
+    pub fn to_bytes(&self) -> [u8; Self::BLOB_LEN] {
+        let mut blob = [0u8; Self::BLOB_LEN];
+        blob[0] = Self::BLOB_VERSION;
+        blob[1..9].copy_from_slice(&self.total_ms.to_be_bytes());
+        blob[9..17].copy_from_slice(&self.high_humidity_ms.to_be_bytes());
+        blob
+    }
+
+    /// Deserialize a blob produced by [`ExposureDose::to_bytes()`], or `None` if it is not one
+    pub fn from_bytes(blob: &[u8]) -> Option<Self> {
+        if blob.len() != Self::BLOB_LEN || blob[0] != Self::BLOB_VERSION {
+            return None;
+        }
+        let mut total_ms = [0u8; 8];
+        let mut high_humidity_ms = [0u8; 8];
+        total_ms.copy_from_slice(&blob[1..9]);
+        high_humidity_ms.copy_from_slice(&blob[9..17]);
+        Some(Self {
+            total_ms: u64::from_be_bytes(total_ms),
+            high_humidity_ms: u64::from_be_bytes(high_humidity_ms),
+        })
+    }
+}
+
+/// Relative humidity tracking alert used as an offset drift detector.
+///
+/// Where the sensor sits in a known humidity (a sealed enclosure with a humidity control pack, a
+/// reference chamber or a salt reference), program [`DriftWatch::alert_limits()`] and the device
+/// raises a relative humidity tracking alert once its reading leaves the tolerance band around the
+/// reference.  Only humidity alerts: the temperature thresholds span the device's range.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriftWatch {
+    /// known relative humidity at the sensor in %
+    pub reference_percent: f32,
+    /// accepted deviation from the reference in %RH before the alert sets
+    pub tolerance_percent: f32,
+    /// how far back inside the band in %RH the reading must come before the alert clears
+    pub hysteresis_percent: f32,
+}
+impl DriftWatch {
+    /// Alert thresholds for the tolerance band, to program with
+    /// [`Hdc302x::write_alert_limits()`](crate::Hdc302x::write_alert_limits)
+    pub fn alert_limits(&self) -> Result<AlertLimits, AlertLimitsError> {
+        let set = self.tolerance_percent;
+        let clear = self.tolerance_percent - self.hysteresis_percent;
+        AlertLimits::new(
+            (-40.0, self.reference_percent - set),
+            (125.0, self.reference_percent + set),
+            (-39.0, self.reference_percent - clear),
+            (124.0, self.reference_percent + clear),
+        )
+    }
+
+    /// Direction the sensor drifted in, if `status` shows a relative humidity tracking alert
+    pub fn check(&self, status: &StatusBits) -> Option<AlertDirection> {
+        if status.rh_high_tracking_alert {
+            Some(AlertDirection::High)
+        } else if status.rh_low_tracking_alert {
+            Some(AlertDirection::Low)
+        } else {
+            None
+        }
+    }
+
+    /// Relative humidity offset in %RH that brings a reading of `measured_percent` back to the
+    /// reference, for [`Hdc302x::write_rh_offset()`](crate::Hdc302x::write_rh_offset)
+    pub fn suggested_offset_percent(&self, measured_percent: f32) -> f32 {
+        self.reference_percent - measured_percent
+    }
+}
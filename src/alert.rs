@@ -0,0 +1,365 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+use core::fmt;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Whether alert status bits are cleared once they are delivered to the application
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AlertClearPolicy {
+    /// bits stay set until the application acknowledges them, for strict alarm management
+    #[default]
+    Acknowledge,
+    /// bits are cleared as soon as they are delivered, e.g. for a simple dashboard
+    OnDelivery,
+}
+
+/// Alert bits accumulated across successive status reads.
+///
+/// A single status read only shows the alerts that are set at that instant, so an alert that sets
+/// and clears between two polls is easy to miss.  `LatchedAlerts` keeps the most recent alert bits
+/// alongside every alert bit seen since the application last acknowledged them.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LatchedAlerts {
+    current: u16,
+    history: u16,
+    policy: AlertClearPolicy,
+}
+impl LatchedAlerts {
+    /// Create an empty alert latch
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Create an empty alert latch whose [`LatchedAlerts::deliver()`] follows `policy`
+    pub fn with_policy(policy: AlertClearPolicy) -> Self {
+        Self { policy, ..Self::default() }
+    }
+    /// Merge a freshly read status into the latch
+    pub fn update(&mut self, status: StatusBits) {
+        self.current = status.raw() & STATUS_ALERT_MASK;
+        self.history |= self.current;
+    }
+    /// Alert bits from the most recent status read
+    pub fn current(&self) -> StatusBits {
+        StatusBits::from(self.current)
+    }
+    /// Alert bits seen at least once since the last acknowledge, including the current ones
+    pub fn history(&self) -> StatusBits {
+        StatusBits::from(self.history)
+    }
+    /// Alert bits seen since the last acknowledge that are no longer active
+    pub fn cleared_since_acknowledge(&self) -> StatusBits {
+        StatusBits::from(self.history & !self.current)
+    }
+    /// true if any alert was seen since the last acknowledge
+    pub fn pending(&self) -> bool {
+        self.history != 0
+    }
+    /// Acknowledge the history, keeping only the alerts that are still active
+    pub fn acknowledge(&mut self) {
+        self.history = self.current;
+    }
+    /// Hand the history to the consumer; with [`AlertClearPolicy::OnDelivery`] this also
+    /// acknowledges it
+    pub fn deliver(&mut self) -> StatusBits {
+        let history = self.history();
+        if self.policy == AlertClearPolicy::OnDelivery {
+            self.acknowledge();
+        }
+        history
+    }
+}
+
+/// One alert threshold as stored in the device: the packed register word and its decoded
+/// engineering units, i.e. the exact limits the device compares against
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertThreshold {
+    /// packed register word (7 MSBs of relative humidity, 9 MSBs of temperature)
+    pub raw: u16,
+    /// temperature threshold in °C
+    pub centigrade: f32,
+    /// relative humidity threshold in %
+    pub humidity_percent: f32,
+}
+
+/// All four alert thresholds as stored in the device
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertThresholds {
+    /// alert sets below this
+    pub set_low: AlertThreshold,
+    /// alert sets above this
+    pub set_high: AlertThreshold,
+    /// low alert clears above this
+    pub clear_low: AlertThreshold,
+    /// high alert clears below this
+    pub clear_high: AlertThreshold,
+}
+
+/// Which side of its thresholds a quantity crossed
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AlertDirection {
+    /// rose above the set high threshold
+    High,
+    /// fell below the set low threshold
+    Low,
+}
+
+/// One quantity's active alert, with the set threshold it crossed
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertCrossing {
+    /// high or low alert
+    pub direction: AlertDirection,
+    /// crossed set threshold, in °C or %RH
+    pub limit: f32,
+}
+
+/// Active alerts decoded against the programmed thresholds: which quantity crossed which limit
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertReport {
+    /// temperature alert, if active
+    pub temperature: Option<AlertCrossing>,
+    /// relative humidity alert, if active
+    pub humidity: Option<AlertCrossing>,
+}
+impl AlertReport {
+    /// Correlate the tracking alert bits of `status` with `thresholds`
+    pub fn new(status: &StatusBits, thresholds: &AlertThresholds) -> Self {
+        let crossing = |high: bool, low: bool, high_limit: f32, low_limit: f32| {
+            if high {
+                Some(AlertCrossing { direction: AlertDirection::High, limit: high_limit })
+            } else if low {
+                Some(AlertCrossing { direction: AlertDirection::Low, limit: low_limit })
+            } else {
+                None
+            }
+        };
+        Self {
+            temperature: crossing(
+                status.t_high_tracking_alert,
+                status.t_low_tracking_alert,
+                thresholds.set_high.centigrade,
+                thresholds.set_low.centigrade,
+            ),
+            humidity: crossing(
+                status.rh_high_tracking_alert,
+                status.rh_low_tracking_alert,
+                thresholds.set_high.humidity_percent,
+                thresholds.set_low.humidity_percent,
+            ),
+        }
+    }
+
+    /// true if any alert is active
+    pub fn any(&self) -> bool {
+        self.temperature.is_some() || self.humidity.is_some()
+    }
+}
+
+/// Why a set of alert limits was rejected by [`AlertLimits::new()`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AlertLimitsError {
+    /// a threshold is outside -45..=130 °C or 0..=100 %RH
+    OutOfRange,
+    /// the set low threshold is not below the set high threshold
+    LowNotBelowHigh,
+    /// the clear low threshold is below the set low threshold or above the clear high threshold
+    ClearLowOutsideSet,
+    /// the clear high threshold is above the set high threshold
+    ClearHighOutsideSet,
+}
+impl fmt::Display for AlertLimitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OutOfRange => "alert threshold out of range",
+            Self::LowNotBelowHigh => "alert set low threshold not below set high threshold",
+            Self::ClearLowOutsideSet => "alert clear low threshold outside the set thresholds",
+            Self::ClearHighOutsideSet => "alert clear high threshold above the set high threshold",
+        })
+    }
+}
+impl<E> From<AlertLimitsError> for Error<E> {
+    fn from(reason: AlertLimitsError) -> Self {
+        Error::InvalidAlertLimits(reason)
+    }
+}
+
+/// Consistent set of the four alert thresholds, ready to program with
+/// [`Hdc302x::write_alert_limits()`](crate::Hdc302x::write_alert_limits)
+///
+/// The invariants are checked on the quantized values the device stores: set low below set high
+/// in both temperature and relative humidity, and each clear threshold at or inside its set
+/// threshold, with clear low not above clear high.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "AlertLimitsWords"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AlertLimits {
+    set_low: u16,
+    set_high: u16,
+    clear_low: u16,
+    clear_high: u16,
+}
+// register words as serialized, validated on deserialization
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct AlertLimitsWords {
+    set_low: u16,
+    set_high: u16,
+    clear_low: u16,
+    clear_high: u16,
+}
+#[cfg(feature = "serde")]
+impl TryFrom<AlertLimitsWords> for AlertLimits {
+    type Error = AlertLimitsError;
+    fn try_from(words: AlertLimitsWords) -> Result<Self, AlertLimitsError> {
+        let limits = Self { set_low: words.set_low, set_high: words.set_high, clear_low: words.clear_low, clear_high: words.clear_high };
+        limits.validate()?;
+        Ok(limits)
+    }
+}
+impl AlertLimits {
+    /// Indoor comfort zone: alert outside 18..28 °C or 25..65 %RH, clearing once back inside
+    /// 20..26 °C and 30..60 %RH
+    pub const COMFORT_ZONE: Self = Self::preset((18.0, 25.0), (28.0, 65.0), (20.0, 30.0), (26.0, 60.0));
+    /// Freezer monitoring: alert below -30 °C or above -15 °C, clearing inside -28..-17 °C.  The
+    /// relative humidity limits span the full range, so only a saturated sensor (above about
+    /// 99 %RH, e.g. frost) alerts on humidity.
+    pub const FREEZER: Self = Self::preset((-30.0, 0.0), (-15.0, 100.0), (-28.0, 0.0), (-17.0, 100.0));
+    /// Greenhouse: alert outside 12..32 °C or 50..90 %RH, clearing once back inside 14..30 °C and
+    /// 55..85 %RH
+    pub const GREENHOUSE: Self = Self::preset((12.0, 50.0), (32.0, 90.0), (14.0, 55.0), (30.0, 85.0));
+
+    const fn preset(set_low: (f32, f32), set_high: (f32, f32), clear_low: (f32, f32), clear_high: (f32, f32)) -> Self {
+        let (Some(set_low), Some(set_high), Some(clear_low), Some(clear_high)) = (
+            encode_alert_threshold(set_low.0, set_low.1),
+            encode_alert_threshold(set_high.0, set_high.1),
+            encode_alert_threshold(clear_low.0, clear_low.1),
+            encode_alert_threshold(clear_high.0, clear_high.1),
+        ) else {
+            panic!("alert preset out of range");
+        };
+        let limits = Self { set_low, set_high, clear_low, clear_high };
+        assert!(limits.validate().is_ok(), "inconsistent alert preset");
+        limits
+    }
+
+    /// Validate and quantize four (°C, %RH) thresholds
+    pub fn new(
+        set_low: (f32, f32),
+        set_high: (f32, f32),
+        clear_low: (f32, f32),
+        clear_high: (f32, f32),
+    ) -> Result<Self, AlertLimitsError> {
+        let encode = |(centigrade, humidity_percent)| {
+            encode_alert_threshold(centigrade, humidity_percent).ok_or(AlertLimitsError::OutOfRange)
+        };
+        let limits = Self {
+            set_low: encode(set_low)?,
+            set_high: encode(set_high)?,
+            clear_low: encode(clear_low)?,
+            clear_high: encode(clear_high)?,
+        };
+        limits.validate()?;
+        Ok(limits)
+    }
+
+    const fn validate(&self) -> Result<(), AlertLimitsError> {
+        let (low, high) = (self.set_low, self.set_high);
+        if !(low & ALERT_RH_MASK < high & ALERT_RH_MASK && low & !ALERT_RH_MASK < high & !ALERT_RH_MASK) {
+            return Err(AlertLimitsError::LowNotBelowHigh);
+        }
+        if !clear_inside_set(self.clear_high, self.set_high, true) {
+            return Err(AlertLimitsError::ClearHighOutsideSet);
+        }
+        if !clear_inside_set(self.clear_low, self.set_low, false)
+            || !clear_inside_set(self.clear_low, self.clear_high, true)
+        {
+            return Err(AlertLimitsError::ClearLowOutsideSet);
+        }
+        Ok(())
+    }
+
+    /// Effective set low threshold
+    pub fn set_low(&self) -> AlertThreshold {
+        decode_alert_threshold(self.set_low)
+    }
+
+    /// Effective set high threshold
+    pub fn set_high(&self) -> AlertThreshold {
+        decode_alert_threshold(self.set_high)
+    }
+
+    /// Effective clear low threshold
+    pub fn clear_low(&self) -> AlertThreshold {
+        decode_alert_threshold(self.clear_low)
+    }
+
+    /// Effective clear high threshold
+    pub fn clear_high(&self) -> AlertThreshold {
+        decode_alert_threshold(self.clear_high)
+    }
+
+    /// Register words in programming order: set low, set high, clear low, clear high
+    pub(crate) fn words(&self) -> [u16; 4] {
+        [self.set_low, self.set_high, self.clear_low, self.clear_high]
+    }
+}
+
+/// Encode a (°C, %RH) pair into the packed 16-bit alert threshold format: the top 7 bits of the
+/// raw relative humidity followed by the top 9 bits of the raw temperature.  The device keeps only
+/// these bits, so the threshold is truncated; see [`decode_alert_threshold()`] for the effective
+/// value.  Returns `None` outside -45..=130 °C or 0..=100 %RH.
+pub const fn encode_alert_threshold(centigrade: f32, humidity_percent: f32) -> Option<u16> {
+    let in_range = centigrade >= -45.0 && centigrade <= 130.0 && humidity_percent >= 0.0 && humidity_percent <= 100.0;
+    if !in_range {
+        return None;
+    }
+    let rh = percent_to_raw_rel_humid(humidity_percent) & ALERT_RH_MASK;
+    let t = centigrade_to_raw_temp(centigrade) >> ALERT_T_SHIFT;
+    Some(rh | t)
+}
+
+/// Check that an encoded clear threshold lies inside (below for high, above for low) the encoded
+/// set threshold in both temperature and relative humidity
+pub(crate) const fn clear_inside_set(clear: u16, set: u16, high: bool) -> bool {
+    let (clear_rh, clear_t) = (clear & ALERT_RH_MASK, clear & !ALERT_RH_MASK);
+    let (set_rh, set_t) = (set & ALERT_RH_MASK, set & !ALERT_RH_MASK);
+    if high {
+        clear_rh <= set_rh && clear_t <= set_t
+    } else {
+        clear_rh >= set_rh && clear_t >= set_t
+    }
+}
+
+/// Decode a packed 16-bit alert threshold into the (°C, %RH) pair the device compares against
+pub const fn decode_alert_threshold(word: u16) -> AlertThreshold {
+    AlertThreshold {
+        raw: word,
+        centigrade: raw_temp_to_centigrade((word & !ALERT_RH_MASK) << ALERT_T_SHIFT),
+        humidity_percent: raw_rel_humid_to_percent(word & ALERT_RH_MASK),
+    }
+}
+
+/// Effective threshold the device applies when programmed with (°C, %RH), or `None` if the
+/// values are out of range
+pub const fn quantize_alert_threshold(centigrade: f32, humidity_percent: f32) -> Option<AlertThreshold> {
+    match encode_alert_threshold(centigrade, humidity_percent) {
+        Some(word) => Some(decode_alert_threshold(word)),
+        None => None,
+    }
+}
@@ -0,0 +1,110 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+use hdc302x_macros::blocking_and_async;
+
+/// Up to four HDC302x on one bus, one per I2C address, with fan-out operations
+///
+/// Each sensor is a separate driver with its own bus handle, e.g. an `embedded-hal-bus`
+/// `RefCellDevice`, and its own delay.  Fan-out operations address every sensor in turn and
+/// report each one's result in an array in sensor order, so one failing sensor does not hide
+/// the others' readings.
+#[derive(Debug)]
+pub struct Hdc302xArray<I2C, Delay, const N: usize> {
+    sensors: [Hdc302x<I2C, Delay>; N],
+}
+impl<I2C, Delay, const N: usize> Hdc302xArray<I2C, Delay, N> {
+    /// Manage the given sensors; `None` if two of them use the same I2C address
+    pub fn new(sensors: [Hdc302x<I2C, Delay>; N]) -> Option<Self> {
+        for (i, sensor) in sensors.iter().enumerate() {
+            if sensors[..i].iter().any(|other| other.i2c_addr == sensor.i2c_addr) {
+                return None;
+            }
+        }
+        Some(Self { sensors })
+    }
+
+    /// Give the drivers back
+    pub fn release(self) -> [Hdc302x<I2C, Delay>; N] {
+        self.sensors
+    }
+
+    /// I2C addresses of the sensors, in sensor order
+    pub fn addresses(&self) -> [I2cAddr; N] {
+        core::array::from_fn(|i| self.sensors[i].i2c_addr)
+    }
+
+    /// All drivers, in sensor order
+    pub fn sensors_mut(&mut self) -> &mut [Hdc302x<I2C, Delay>; N] {
+        &mut self.sensors
+    }
+
+    /// Driver of the sensor at `i2c_addr`, if the array has one
+    pub fn sensor_mut(&mut self, i2c_addr: I2cAddr) -> Option<&mut Hdc302x<I2C, Delay>> {
+        self.sensors.iter_mut().find(|sensor| sensor.i2c_addr == i2c_addr)
+    }
+}
+
+// Written once as async code; the blocking flavor is generated from it
+#[blocking_and_async]
+impl<I2C, Delay, E, const N: usize> Hdc302xArray<I2C, Delay, N>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Start auto mode on every sensor
+    pub async fn auto_start_all_async(&mut self, sample_rate: SampleRate, low_power_mode: LowPowerMode) -> [Result<(), Error<E>>; N] {
+        let mut results = core::array::from_fn(|_| Ok(()));
+        for (sensor, result) in self.sensors.iter_mut().zip(results.iter_mut()) {
+            *result = sensor.auto_start_async(sample_rate, low_power_mode).await;
+        }
+        results
+    }
+
+    /// Stop auto mode on every sensor
+    pub async fn auto_stop_all_async(&mut self) -> [Result<(), Error<E>>; N] {
+        let mut results = core::array::from_fn(|_| Ok(()));
+        for (sensor, result) in self.sensors.iter_mut().zip(results.iter_mut()) {
+            *result = sensor.auto_stop_async().await;
+        }
+        results
+    }
+
+    /// Read the latest auto-mode sample (or minimum/maximum) of every sensor
+    pub async fn auto_read_all_async(&mut self, target: AutoReadTarget) -> [Result<RawDatum, Error<E>>; N] {
+        // every slot is overwritten below
+        let mut results = core::array::from_fn(|_| Err(Error::InvalidInputData));
+        for (sensor, result) in self.sensors.iter_mut().zip(results.iter_mut()) {
+            *result = sensor.auto_read_async(target).await;
+        }
+        results
+    }
+
+    /// Take a one-shot measurement on every sensor.  All sensors are triggered before the first
+    /// result is read, so they convert in parallel.
+    pub async fn one_shot_all_async(&mut self, low_power_mode: LowPowerMode) -> [Result<RawDatum, Error<E>>; N] {
+        let mut results = core::array::from_fn(|_| Err(Error::InvalidInputData));
+        let mut started = [false; N];
+        for ((sensor, result), started) in self.sensors.iter_mut().zip(results.iter_mut()).zip(started.iter_mut()) {
+            match sensor.start_one_shot_async(low_power_mode).await {
+                Ok(()) => *started = true,
+                Err(error) => *result = Err(error),
+            }
+        }
+        for ((sensor, result), started) in self.sensors.iter_mut().zip(results.iter_mut()).zip(started) {
+            if started {
+                *result = sensor.read_one_shot_result_async().await;
+            }
+        }
+        results
+    }
+
+    /// Read the status of every sensor
+    pub async fn read_status_all_async(&mut self, clear: bool) -> [Result<StatusBits, Error<E>>; N] {
+        let mut results = core::array::from_fn(|_| Err(Error::InvalidInputData));
+        for (sensor, result) in self.sensors.iter_mut().zip(results.iter_mut()) {
+            *result = sensor.read_status_async(clear).await;
+        }
+        results
+    }
+}
@@ -0,0 +1,109 @@
+use heapless::mpmc::MpMcQueue;
+use hdc302x_macros::blocking_and_async;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// What to do when a sample is pushed into a full queue
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// discard the oldest queued sample to make room for the new one
+    DropOldest,
+    /// discard the new sample
+    DropNewest,
+    /// wait until the consumer makes room, see [`SampleBridge::push()`]
+    Block,
+}
+
+/// Result of pushing one sample into the bridge
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PushOutcome {
+    /// the sample was queued
+    Queued,
+    /// the sample was queued after discarding the oldest one
+    DroppedOldest,
+    /// the queue was full and the sample was discarded
+    DroppedNewest,
+    /// the queue is full and the policy is [`OverflowPolicy::Block`]: [`SampleBridge::try_push()`]
+    /// does not wait, and [`SampleBridge::push()`] gave up after its timeout
+    Full,
+}
+
+/// Bridge pushing samples into a bounded lock-free queue shared with a (possibly slow) consumer.
+///
+/// The queue is a `heapless::mpmc::MpMcQueue`, typically a `static`, so the consumer can dequeue
+/// from another task or interrupt while the sampling task keeps its own cadence.  `N` must be a
+/// power of two.
+pub struct SampleBridge<'q, T, const N: usize> {
+    queue: &'q MpMcQueue<T, N>,
+    policy: OverflowPolicy,
+    dropped: u32,
+}
+impl<'q, T, const N: usize> SampleBridge<'q, T, N> {
+    /// Create a bridge feeding `queue` with the given overflow policy
+    pub fn new(queue: &'q MpMcQueue<T, N>, policy: OverflowPolicy) -> Self {
+        Self { queue, policy, dropped: 0 }
+    }
+
+    /// Overflow policy of this bridge
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Number of samples discarded because the queue was full
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Push a sample according to the overflow policy.  With [`OverflowPolicy::Block`] the sample
+    /// is handed back together with [`PushOutcome::Full`] when the queue is full.
+    pub fn try_push(&mut self, sample: T) -> Result<PushOutcome, (PushOutcome, T)> {
+        let sample = match self.queue.enqueue(sample) {
+            Ok(()) => return Ok(PushOutcome::Queued),
+            Err(sample) => sample,
+        };
+        match self.policy {
+            OverflowPolicy::DropNewest => {
+                self.dropped = self.dropped.saturating_add(1);
+                Ok(PushOutcome::DroppedNewest)
+            }
+            OverflowPolicy::DropOldest => {
+                let _ = self.queue.dequeue();
+                self.dropped = self.dropped.saturating_add(1);
+                match self.queue.enqueue(sample) {
+                    Ok(()) => Ok(PushOutcome::DroppedOldest),
+                    // another producer took the freed slot
+                    Err(_) => Ok(PushOutcome::DroppedNewest),
+                }
+            }
+            OverflowPolicy::Block => Err((PushOutcome::Full, sample)),
+        }
+    }
+}
+
+#[blocking_and_async]
+impl<T, const N: usize> SampleBridge<'_, T, N> {
+    /// Push a sample according to the overflow policy.  With [`OverflowPolicy::Block`] this waits
+    /// on `delay` in 1 ms steps until the consumer makes room, or for at most `timeout_ms`, after
+    /// which the sample is handed back together with [`PushOutcome::Full`].
+    pub async fn push_async<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        mut sample: T,
+        delay: &mut D,
+        timeout_ms: Option<u32>,
+    ) -> Result<PushOutcome, (PushOutcome, T)> {
+        let mut waited_ms = 0u32;
+        loop {
+            match self.try_push(sample) {
+                Err((_, returned)) if timeout_ms.is_none_or(|timeout_ms| waited_ms < timeout_ms) => {
+                    sample = returned;
+                    delay.delay_ms(1).await;
+                    waited_ms = waited_ms.saturating_add(1);
+                }
+                result => return result,
+            }
+        }
+    }
+}
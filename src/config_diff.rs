@@ -0,0 +1,132 @@
+use crate::config_store::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Register backed by non-volatile memory, one field of [`NvConfig`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NvField {
+    /// alert set low threshold register
+    AlertSetLow,
+    /// alert set high threshold register
+    AlertSetHigh,
+    /// alert clear low threshold register
+    AlertClearLow,
+    /// alert clear high threshold register
+    AlertClearHigh,
+    /// temperature and relative humidity offset register
+    Offsets,
+    /// power-on/reset state register
+    ResetState,
+}
+impl NvField {
+    /// All fields, in [`NvConfig`] order
+    pub const ALL: [Self; 6] = [
+        Self::AlertSetLow,
+        Self::AlertSetHigh,
+        Self::AlertClearLow,
+        Self::AlertClearHigh,
+        Self::Offsets,
+        Self::ResetState,
+    ];
+
+    /// Bit of this field in [`ConfigDiff::mask()`]
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// One field that differs between two configurations
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FieldMismatch {
+    /// the field
+    pub field: NvField,
+    /// value in the expected (golden) configuration
+    pub expected: u16,
+    /// value read from the device
+    pub actual: u16,
+}
+
+/// Fields of a device's non-volatile configuration that drifted from the expected (golden)
+/// profile, e.g. for a fleet-management backend.  Only the mismatched fields are kept, and
+/// [`ConfigDiff::to_bytes()`] serializes them compactly.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ConfigDiff {
+    mask: u8,
+    expected: [u16; 6],
+    actual: [u16; 6],
+}
+impl ConfigDiff {
+    /// Longest blob produced by [`ConfigDiff::to_bytes()`]: the mask byte and every field
+    pub const MAX_BLOB_LEN: usize = 1 + 4 * NvField::ALL.len();
+
+    /// Compare the configuration read from a device against the expected one
+    pub fn between(expected: &NvConfig, actual: &NvConfig) -> Self {
+        let mut diff = Self::default();
+        let fields = NvField::ALL.iter().zip(expected.words()).zip(actual.words());
+        for (i, ((field, expected), actual)) in fields.enumerate() {
+            if expected != actual {
+                diff.mask |= field.bit();
+                diff.expected[i] = expected;
+                diff.actual[i] = actual;
+            }
+        }
+        diff
+    }
+
+    /// true if the configurations match
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0
+    }
+
+    /// Number of mismatched fields
+    pub fn len(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Mismatched fields as a bit mask, see [`NvField::bit()`]
+    pub fn mask(&self) -> u8 {
+        self.mask
+    }
+
+    /// Mismatched fields, in [`NvConfig`] order
+    pub fn iter(&self) -> impl Iterator<Item = FieldMismatch> + '_ {
+        NvField::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| self.mask & field.bit() != 0)
+            .map(|(i, &field)| FieldMismatch { field, expected: self.expected[i], actual: self.actual[i] })
+    }
+
+    /// Serialize into `buf` as the mask byte followed by the expected and actual big-endian words
+    /// of each mismatched field.  Returns the length written, or `None` if `buf` is too short
+    /// (it never is at [`ConfigDiff::MAX_BLOB_LEN`]).
+    pub fn to_bytes(&self, buf: &mut [u8]) -> Option<usize> {
+        let len = 1 + 4 * self.len();
+        let blob = buf.get_mut(..len)?;
+        blob[0] = self.mask;
+        for (chunk, mismatch) in blob[1..].chunks_exact_mut(4).zip(self.iter()) {
+            chunk[..2].copy_from_slice(&mismatch.expected.to_be_bytes());
+            chunk[2..].copy_from_slice(&mismatch.actual.to_be_bytes());
+        }
+        Some(len)
+    }
+
+    /// Deserialize a blob produced by [`ConfigDiff::to_bytes()`], or `None` if it is not one
+    pub fn from_bytes(blob: &[u8]) -> Option<Self> {
+        let (&mask, words) = blob.split_first()?;
+        if mask >> NvField::ALL.len() != 0 || words.len() != 4 * mask.count_ones() as usize {
+            return None;
+        }
+        let mut diff = Self { mask, ..Self::default() };
+        let set = (0..NvField::ALL.len()).filter(|i| mask & (1 << i) != 0);
+        for (i, chunk) in set.zip(words.chunks_exact(4)) {
+            diff.expected[i] = u16::from_be_bytes([chunk[0], chunk[1]]);
+            diff.actual[i] = u16::from_be_bytes([chunk[2], chunk[3]]);
+        }
+        Some(diff)
+    }
+}
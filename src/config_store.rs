@@ -0,0 +1,132 @@
+use crate::hw_def::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Storage for a small configuration blob, typically a page of the MCU's own flash.
+///
+/// Used to mirror the device's non-volatile configuration so that a mismatch between the expected
+/// and the actual device state can be detected at boot.
+pub trait ConfigStore {
+    /// Storage-specific error
+    type Error;
+
+    /// Copy the saved blob into `buf` and return its length, or `None` if nothing was saved yet
+    fn load(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Self::Error>;
+
+    /// Replace the saved blob with `blob`
+    fn save(&mut self, blob: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Raw contents of the device registers that are backed by non-volatile memory
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NvConfig {
+    /// alert set low threshold register
+    pub alert_set_low: u16,
+    /// alert set high threshold register
+    pub alert_set_high: u16,
+    /// alert clear low threshold register
+    pub alert_clear_low: u16,
+    /// alert clear high threshold register
+    pub alert_clear_high: u16,
+    /// temperature and relative humidity offset register
+    pub offsets: u16,
+    /// power-on/reset state register
+    pub reset_state: u16,
+}
+
+/// Result of comparing the device's non-volatile configuration against the mirrored copy
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NvMirrorStatus {
+    /// nothing has been mirrored yet
+    NoMirror,
+    /// the device matches the mirrored configuration
+    Match,
+    /// the device differs from the mirrored configuration
+    Mismatch {
+        /// mirrored configuration
+        expected: NvConfig,
+        /// configuration read from the device
+        actual: NvConfig,
+    },
+}
+
+impl NvConfig {
+    /// Format version stored in the first byte of the blob
+    pub const BLOB_VERSION: u8 = 1;
+    /// Length of the blob produced by [`NvConfig::to_bytes()`]
+    pub const BLOB_LEN: usize = 13;
+
+    /// Serialize into the blob stored by [`ConfigStore::save()`]
+    pub fn to_bytes(&self) -> [u8; Self::BLOB_LEN] {
+        let mut blob = [0u8; Self::BLOB_LEN];
+        blob[0] = Self::BLOB_VERSION;
+        for (chunk, word) in blob[1..].chunks_exact_mut(2).zip(self.words()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        blob
+    }
+
+    /// Deserialize a blob produced by [`NvConfig::to_bytes()`], or `None` if it is not one
+    pub fn from_bytes(blob: &[u8]) -> Option<Self> {
+        if blob.len() != Self::BLOB_LEN || blob[0] != Self::BLOB_VERSION {
+            return None;
+        }
+        let mut words = [0u16; 6];
+        for (word, chunk) in words.iter_mut().zip(blob[1..].chunks_exact(2)) {
+            *word = u16::from_be_bytes([chunk[0], chunk[1]]);
+        }
+        Some(Self::from_words(words))
+    }
+
+    pub(crate) fn words(&self) -> [u16; 6] {
+        [
+            self.alert_set_low,
+            self.alert_set_high,
+            self.alert_clear_low,
+            self.alert_clear_high,
+            self.offsets,
+            self.reset_state,
+        ]
+    }
+
+    fn from_words(words: [u16; 6]) -> Self {
+        Self {
+            alert_set_low: words[0],
+            alert_set_high: words[1],
+            alert_clear_low: words[2],
+            alert_clear_high: words[3],
+            offsets: words[4],
+            reset_state: words[5],
+        }
+    }
+}
+
+/// Device configuration that a reset loses or may change, captured by
+/// [`Hdc302x::save_config()`](crate::Hdc302x::save_config) and pushed back by
+/// [`Hdc302x::apply_config()`](crate::Hdc302x::apply_config), e.g. when
+/// [`StatusBits::reset_since_clear`](crate::StatusBits::reset_since_clear) is observed
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SavedConfig {
+    /// alert set low threshold register
+    pub alert_set_low: u16,
+    /// alert set high threshold register
+    pub alert_set_high: u16,
+    /// alert clear low threshold register
+    pub alert_clear_low: u16,
+    /// alert clear high threshold register
+    pub alert_clear_high: u16,
+    /// NV offset register (RH byte, then temperature byte)
+    pub offsets: u16,
+    /// heater setting, `None` if the driver did not know it
+    pub heater: Option<HeaterLevel>,
+    /// auto mode sample rate and low power mode, `None` for sleep (one-shot sampling)
+    pub auto_mode: Option<(SampleRate, LowPowerMode)>,
+}
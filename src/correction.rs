@@ -0,0 +1,63 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Per-unit linear correction applied by the driver on top of the nominal conversion, see
+/// [`Hdc302x::set_conversion_coefficients()`](crate::Hdc302x::set_conversion_coefficients).
+///
+/// Corrected values are `slope * nominal + offset`.  Unlike the device's non-volatile offset
+/// register, this can express a gain error and offsets of any size, but the device's alert
+/// comparison still uses the uncorrected values.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConversionCoefficients {
+    /// temperature gain
+    pub temp_slope: f32,
+    /// temperature offset in °C
+    pub temp_offset_centigrade: f32,
+    /// relative humidity gain
+    pub rel_humid_slope: f32,
+    /// relative humidity offset in %RH
+    pub rel_humid_offset_percent: f32,
+}
+impl ConversionCoefficients {
+    /// The nominal datasheet conversion, no correction
+    pub const NOMINAL: Self = Self {
+        temp_slope: 1.0,
+        temp_offset_centigrade: 0.0,
+        rel_humid_slope: 1.0,
+        rel_humid_offset_percent: 0.0,
+    };
+
+    /// Apply the temperature correction to a raw temperature, saturating at the raw range
+    pub const fn correct_raw_temp(&self, raw: u16) -> u16 {
+        centigrade_to_raw_temp(self.temp_slope * raw_temp_to_centigrade(raw) + self.temp_offset_centigrade)
+    }
+
+    /// Apply the relative humidity correction to a raw relative humidity, saturating at the raw
+    /// range
+    pub const fn correct_raw_rel_humid(&self, raw: u16) -> u16 {
+        percent_to_raw_rel_humid(self.rel_humid_slope * raw_rel_humid_to_percent(raw) + self.rel_humid_offset_percent)
+    }
+
+    /// Apply the correction to every value in `datum`
+    pub const fn correct(&self, datum: RawDatum) -> RawDatum {
+        match datum {
+            RawDatum::TempAndRelHumid(sample) => RawDatum::TempAndRelHumid(RawTempAndRelHumid {
+                temperature: self.correct_raw_temp(sample.temperature),
+                humidity: self.correct_raw_rel_humid(sample.humidity),
+            }),
+            RawDatum::MinTemp(raw) => RawDatum::MinTemp(self.correct_raw_temp(raw)),
+            RawDatum::MaxTemp(raw) => RawDatum::MaxTemp(self.correct_raw_temp(raw)),
+            RawDatum::MinRelHumid(raw) => RawDatum::MinRelHumid(self.correct_raw_rel_humid(raw)),
+            RawDatum::MaxRelHumid(raw) => RawDatum::MaxRelHumid(self.correct_raw_rel_humid(raw)),
+        }
+    }
+}
+impl Default for ConversionCoefficients {
+    fn default() -> Self {
+        Self::NOMINAL
+    }
+}
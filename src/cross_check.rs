@@ -0,0 +1,135 @@
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Temperature and relative humidity reading that can be cross-checked, implemented for the
+/// driver's samples and by applications for a reference sensor
+pub trait ClimateSample {
+    /// temperature in degrees centigrade
+    fn centigrade(&self) -> f32;
+    /// relative humidity in percent
+    fn humidity_percent(&self) -> f32;
+}
+impl ClimateSample for RawTempAndRelHumid {
+    fn centigrade(&self) -> f32 {
+        RawTempAndRelHumid::centigrade(self)
+    }
+    fn humidity_percent(&self) -> f32 {
+        RawTempAndRelHumid::humidity_percent(self)
+    }
+}
+impl ClimateSample for TempAndRelHumid {
+    fn centigrade(&self) -> f32 {
+        self.centigrade
+    }
+    fn humidity_percent(&self) -> f32 {
+        self.humidity_percent
+    }
+}
+
+/// Accuracy of one sensor, as a ± bound around the true value
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccuracyBounds {
+    /// temperature accuracy in °C
+    pub centigrade: f32,
+    /// relative humidity accuracy in %RH
+    pub humidity_percent: f32,
+}
+impl AccuracyBounds {
+    /// HDC302x accuracy over the central operating range; widen it for readings near the ends of
+    /// the temperature or humidity range
+    pub const HDC302X: Self = Self { centigrade: 0.3, humidity_percent: 2.5 };
+}
+
+/// Two sensors disagreeing beyond their combined accuracy, from [`CrossCheck::compare()`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Divergence {
+    /// primary minus secondary temperature in °C
+    pub centigrade_delta: f32,
+    /// primary minus secondary relative humidity in %RH
+    pub humidity_delta_percent: f32,
+    /// the temperatures disagree beyond the combined accuracy
+    pub temperature: bool,
+    /// the relative humidities disagree beyond the combined accuracy
+    pub humidity: bool,
+}
+
+/// Divergence statistics over all comparisons since creation or [`CrossCheck::reset()`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DivergenceStats {
+    /// number of compared sample pairs
+    pub comparisons: u32,
+    /// number of pairs that disagreed beyond the combined accuracy
+    pub divergences: u32,
+    /// mean primary minus secondary temperature in °C (the systematic offset)
+    pub mean_centigrade_delta: f32,
+    /// largest absolute temperature difference in °C
+    pub max_abs_centigrade_delta: f32,
+    /// mean primary minus secondary relative humidity in %RH
+    pub mean_humidity_delta_percent: f32,
+    /// largest absolute relative humidity difference in %RH
+    pub max_abs_humidity_delta_percent: f32,
+}
+
+/// Redundancy check between a primary HDC302x and a second HDC302x or a reference sensor
+///
+/// Feed it samples taken at about the same time and place.  A pair disagrees when the difference
+/// exceeds the sum of both sensors' accuracy bounds, which a healthy pair never does.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CrossCheck {
+    primary: AccuracyBounds,
+    secondary: AccuracyBounds,
+    stats: DivergenceStats,
+}
+impl CrossCheck {
+    /// Compare two sensors with the given accuracy bounds
+    pub fn new(primary: AccuracyBounds, secondary: AccuracyBounds) -> Self {
+        Self { primary, secondary, stats: DivergenceStats::default() }
+    }
+
+    /// Compare two HDC302x units
+    pub fn hdc302x_pair() -> Self {
+        Self::new(AccuracyBounds::HDC302X, AccuracyBounds::HDC302X)
+    }
+
+    /// Account for one sample pair; returns the divergence if they disagree
+    pub fn compare(&mut self, primary: &impl ClimateSample, secondary: &impl ClimateSample) -> Option<Divergence> {
+        let centigrade_delta = primary.centigrade() - secondary.centigrade();
+        let humidity_delta_percent = primary.humidity_percent() - secondary.humidity_percent();
+        let divergence = Divergence {
+            centigrade_delta,
+            humidity_delta_percent,
+            temperature: centigrade_delta.abs() > self.primary.centigrade + self.secondary.centigrade,
+            humidity: humidity_delta_percent.abs() > self.primary.humidity_percent + self.secondary.humidity_percent,
+        };
+
+        let stats = &mut self.stats;
+        stats.comparisons = stats.comparisons.saturating_add(1);
+        let count = stats.comparisons as f32;
+        stats.mean_centigrade_delta += (centigrade_delta - stats.mean_centigrade_delta) / count;
+        stats.mean_humidity_delta_percent += (humidity_delta_percent - stats.mean_humidity_delta_percent) / count;
+        stats.max_abs_centigrade_delta = stats.max_abs_centigrade_delta.max(centigrade_delta.abs());
+        stats.max_abs_humidity_delta_percent = stats.max_abs_humidity_delta_percent.max(humidity_delta_percent.abs());
+
+        if !divergence.temperature && !divergence.humidity {
+            return None;
+        }
+        stats.divergences = stats.divergences.saturating_add(1);
+        Some(divergence)
+    }
+
+    /// Statistics over all comparisons so far
+    pub fn stats(&self) -> DivergenceStats {
+        self.stats
+    }
+
+    /// Forget the statistics, e.g. after recalibrating either sensor
+    pub fn reset(&mut self) {
+        self.stats = DivergenceStats::default();
+    }
+}
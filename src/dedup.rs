@@ -0,0 +1,90 @@
+use crate::quality::*;
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Last sample seen before the application went to sleep, kept in retained RAM or flash so the
+/// first sample after wake-up can be checked with [`SampleDedup`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ResumeState {
+    /// last sample passed to [`SampleDedup::check()`]
+    pub last_sample: RawTempAndRelHumid,
+    /// application time at which it was fetched, in milliseconds
+    pub fetched_at_ms: u64,
+}
+impl ResumeState {
+    /// Version byte at the start of the blob
+    pub const BLOB_VERSION: u8 = 1;
+    /// Length of the blob produced by [`ResumeState::to_bytes()`]
+    pub const BLOB_LEN: usize = 13;
+
+    /// Serialize for storage across sleep
+    pub fn to_bytes(&self) -> [u8; Self::BLOB_LEN] {
+        let mut blob = [0u8; Self::BLOB_LEN];
+        blob[0] = Self::BLOB_VERSION;
+        blob[1..3].copy_from_slice(&self.last_sample.temperature.to_be_bytes());
+        blob[3..5].copy_from_slice(&self.last_sample.humidity.to_be_bytes());
+        blob[5..13].copy_from_slice(&self.fetched_at_ms.to_be_bytes());
+        blob
+    }
+
+    /// Deserialize a blob produced by [`ResumeState::to_bytes()`], or `None` if it is not one
+    pub fn from_bytes(blob: &[u8]) -> Option<Self> {
+        if blob.len() != Self::BLOB_LEN || blob[0] != Self::BLOB_VERSION {
+            return None;
+        }
+        let mut fetched_at_ms = [0u8; 8];
+        fetched_at_ms.copy_from_slice(&blob[5..13]);
+        Some(Self {
+            last_sample: RawTempAndRelHumid {
+                temperature: u16::from_be_bytes([blob[1], blob[2]]),
+                humidity: u16::from_be_bytes([blob[3], blob[4]]),
+            },
+            fetched_at_ms: u64::from_be_bytes(fetched_at_ms),
+        })
+    }
+}
+
+/// Flags auto-mode samples fetched after a restart that the device measured before it.
+///
+/// In auto mode the device keeps measuring while the MCU sleeps, but the result register still
+/// holds the last measurement until the next one completes.  The first sample fetched after
+/// wake-up is flagged [`MeasurementFlags::stale`] when it is identical to the last sample fetched
+/// before sleep and less than two sample periods have passed, i.e. the device has most likely not
+/// produced a new measurement yet.  Timestamps must come from a clock that keeps running across
+/// sleep (e.g. an RTC).
+#[derive(Clone, Copy, Debug)]
+pub struct SampleDedup {
+    period_ms: u32,
+    resumed: Option<ResumeState>,
+    last: Option<ResumeState>,
+}
+impl SampleDedup {
+    /// Start without history, for a cold boot; `period_ms` is the auto-mode sample period
+    pub fn new(period_ms: u32) -> Self {
+        Self { period_ms, resumed: None, last: None }
+    }
+
+    /// Start from the state saved before sleep
+    pub fn resume(period_ms: u32, state: ResumeState) -> Self {
+        Self { period_ms, resumed: Some(state), last: Some(state) }
+    }
+
+    /// Flag `sample` if it predates the restart, and remember it for the next save
+    pub fn check(&mut self, sample: &mut FlaggedSample, now_ms: u64) {
+        if let Some(resumed) = self.resumed.take() {
+            let elapsed_ms = now_ms.saturating_sub(resumed.fetched_at_ms);
+            if sample.sample == resumed.last_sample && elapsed_ms < 2 * self.period_ms as u64 {
+                sample.flags.stale = true;
+            }
+        }
+        self.last = Some(ResumeState { last_sample: sample.sample, fetched_at_ms: now_ms });
+    }
+
+    /// State to keep across sleep, `None` if no sample has been checked yet
+    pub fn save(&self) -> Option<ResumeState> {
+        self.last
+    }
+}
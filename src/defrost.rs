@@ -0,0 +1,158 @@
+use crate::hw_def::*;
+use crate::pacing::*;
+use crate::types::*;
+
+use hdc302x_macros::blocking_and_async;
+
+/// Timing of a [`DefrostCycle`]
+#[derive(Clone, Copy, Debug)]
+pub struct DefrostSchedule {
+    /// length of each measurement window between heater bursts
+    pub measure_ms: u32,
+    /// length of each heater burst
+    pub heater_ms: u32,
+    /// heater power during a burst
+    pub heater_level: HeaterLevel,
+    /// samples taken within this time after a burst are flagged invalid, since the die is still
+    /// warmer than its surroundings
+    pub settle_ms: u32,
+    /// valid samples to collect in each window before the next burst may start; the window is
+    /// extended until they are collected
+    pub min_valid_samples: u32,
+}
+impl DefrostSchedule {
+    /// Cold-storage door sensor: 10 s heater bursts at 50% every 5 min, 30 s settle time and at
+    /// least 4 valid samples per window
+    pub const COLD_STORAGE: Self = Self {
+        measure_ms: 300_000,
+        heater_ms: 10_000,
+        heater_level: HeaterLevel::On50Percent,
+        settle_ms: 30_000,
+        min_valid_samples: 4,
+    };
+}
+
+/// What a [`DefrostCycle`] step did
+#[derive(Clone, Copy, Debug)]
+pub enum DefrostStep {
+    /// took a heater-off sample; `valid` is false while the die settles after a burst
+    Sample {
+        /// the sample
+        sample: RawTempAndRelHumid,
+        /// true if the sample is unbiased by the heater
+        valid: bool,
+    },
+    /// turned the heater on to start a burst
+    HeaterOn,
+    /// turned the heater off at the end of a burst
+    HeaterOff,
+    /// a burst is in progress, nothing to do
+    Heating,
+}
+
+/// Interleaves measurement windows and heater bursts to keep condensation and frost off the sensor,
+/// e.g. on a cold-storage door.
+///
+/// Call `step()` at the sampling period.  Samples are only taken with the heater off, each window
+/// collects at least [`DefrostSchedule::min_valid_samples`] valid samples before the next burst,
+/// and samples taken while the die settles after a burst are flagged invalid.
+#[derive(Debug)]
+pub struct DefrostCycle<C> {
+    clock: C,
+    schedule: DefrostSchedule,
+    low_power_mode: LowPowerMode,
+    heating: bool,
+    after_burst: bool,
+    phase_start_ms: Option<u64>,
+    window_valid: u32,
+    valid_samples: u32,
+    total_samples: u32,
+}
+impl<C: Clock> DefrostCycle<C> {
+    /// Create a cycle starting with a measurement window
+    pub fn new(clock: C, schedule: DefrostSchedule, low_power_mode: LowPowerMode) -> Self {
+        Self {
+            clock,
+            schedule,
+            low_power_mode,
+            heating: false,
+            after_burst: false,
+            phase_start_ms: None,
+            window_valid: 0,
+            valid_samples: 0,
+            total_samples: 0,
+        }
+    }
+
+    /// true during a heater burst
+    pub fn is_heating(&self) -> bool {
+        self.heating
+    }
+
+    /// Valid samples taken since the cycle was created
+    pub fn valid_samples(&self) -> u32 {
+        self.valid_samples
+    }
+
+    /// Samples taken since the cycle was created, valid or not
+    pub fn total_samples(&self) -> u32 {
+        self.total_samples
+    }
+
+    /// Fraction of the samples taken so far that are valid, 1.0 before the first sample
+    pub fn valid_fraction(&self) -> f32 {
+        match self.total_samples {
+            0 => 1.0,
+            total => self.valid_samples as f32 / total as f32,
+        }
+    }
+
+    fn start_phase(&mut self, now: u64, heating: bool) {
+        self.after_burst |= heating;
+        self.heating = heating;
+        self.phase_start_ms = Some(now);
+        self.window_valid = 0;
+    }
+
+    fn record_sample(&mut self, sample: RawTempAndRelHumid, elapsed_ms: u64) -> DefrostStep {
+        // the first window follows no burst, so it needs no settle time
+        let valid = !self.after_burst || elapsed_ms >= self.schedule.settle_ms as u64;
+        self.total_samples = self.total_samples.saturating_add(1);
+        if valid {
+            self.valid_samples = self.valid_samples.saturating_add(1);
+            self.window_valid = self.window_valid.saturating_add(1);
+        }
+        DefrostStep::Sample { sample, valid }
+    }
+}
+
+#[blocking_and_async]
+impl<C: Clock> DefrostCycle<C> {
+    /// Advance the cycle: take a sample, start or end a heater burst, or do nothing while heating
+    pub async fn step_async<I2C, Delay, E>(&mut self, hdc302x: &mut Hdc302x<I2C, Delay>) -> Result<DefrostStep, Error<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+        Delay: embedded_hal_async::delay::DelayNs,
+    {
+        let now = self.clock.now_ms();
+        let phase_start = *self.phase_start_ms.get_or_insert(now);
+        let elapsed_ms = now.saturating_sub(phase_start);
+        if self.heating {
+            if elapsed_ms < self.schedule.heater_ms as u64 {
+                return Ok(DefrostStep::Heating);
+            }
+            hdc302x.heater_async(HeaterLevel::Off).await?;
+            self.start_phase(now, false);
+            return Ok(DefrostStep::HeaterOff);
+        }
+        if elapsed_ms >= self.schedule.measure_ms as u64 && self.window_valid >= self.schedule.min_valid_samples {
+            hdc302x.heater_async(self.schedule.heater_level).await?;
+            self.start_phase(now, true);
+            return Ok(DefrostStep::HeaterOn);
+        }
+        match hdc302x.one_shot_async(self.low_power_mode).await? {
+            RawDatum::TempAndRelHumid(sample) => Ok(self.record_sample(sample, elapsed_ms)),
+            _ => Err(Error::InvalidInputData),
+        }
+    }
+}
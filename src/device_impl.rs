@@ -1,11 +1,27 @@
+use crate::alert::*;
+#[cfg(feature = "heapless")]
+use crate::bridge::*;
+use crate::config_store::*;
+use crate::correction::*;
 use crate::hw_def::*;
+use crate::integrity::*;
+use crate::observer::*;
+use crate::offset::*;
+use crate::pacing::*;
+use crate::quality::*;
+use crate::supply::*;
 use crate::types::*;
 
 use cfg_if::cfg_if;
+use hdc302x_macros::blocking_and_async;
+use embedded_hal::i2c::ErrorKind;
 
 #[cfg(feature = "crc")]
 use crc::{Crc, CRC_8_NRSC_5};
 
+#[cfg(feature = "uom")]
+use uom::si::{f32::{Ratio, TemperatureInterval}, ratio, temperature_interval};
+
 #[cfg(feature = "defmt")]
 use defmt::{trace, warn};
 #[cfg(feature = "log")]
@@ -22,275 +38,638 @@ macro_rules! warn {
 #[cfg(feature = "crc")]
 const CRC: crc::Crc<u8> = Crc::<u8>::new(&CRC_8_NRSC_5);
 
+/// Times a command the device NACKs is written before giving up
+const CMD_WRITE_ATTEMPTS: u8 = 3;
+/// Times a command is issued before a read that never succeeds is reported
+const CMD_ISSUES: u8 = 2;
+
+/// Value the read buffer is filled with before each read, as the idle (pulled-up) bus would read
+const UNRECEIVED: u8 = 0xff;
+
+/// Decode big-endian data words from a device response, checking the CRC that follows each word
+/// as `policy` says.  Returns the number of CRC mismatches accepted under
+/// [`CrcPolicy::WarnOnly`].
+///
+/// The read buffer is filled with [`UNRECEIVED`] before each read.  A word and CRC that are all
+/// [`UNRECEIVED`] bytes can not come from the device (the CRC of 0xffff is 0xac), so a response
+/// ending in one was cut short by the bus and is reported as [`Error::ShortRead`], as is a CRC
+/// mismatch of a last word that ends in [`UNRECEIVED`] bytes (whatever the policy).  A response
+/// cut inside its last word still passes if the CRC happens to match the bytes left in the
+/// buffer (1 in 256).
+pub(crate) fn decode_words<E>(frame: &[u8], words: &mut [u16], policy: CrcPolicy, label: &str) -> Result<u32, Error<E>> {
+    #[cfg(not(any(feature = "defmt", feature = "log")))]
+    let _ = label;
+    let expected = 3 * words.len();
+    if frame.len() < expected {
+        return Err(Error::ShortRead { expected, got: frame.len() });
+    }
+    let unreceived = frame[..expected].iter().rev().take_while(|byte| **byte == UNRECEIVED).count();
+    #[cfg(feature = "crc")]
+    let last_word_unreceived = unreceived > 0 && {
+        let last = &frame[expected - 3..expected];
+        CRC.checksum(&last[0..2]) != last[2]
+    };
+    #[cfg(not(feature = "crc"))]
+    let last_word_unreceived = false;
+    if unreceived >= 3 || last_word_unreceived {
+        warn!("hdc302x[{}]::decode_words(): short read: {} of {} bytes", label, expected - unreceived, expected);
+        return Err(Error::ShortRead { expected, got: expected - unreceived });
+    }
+    #[cfg_attr(not(feature = "crc"), allow(unused_mut))]
+    let mut accepted_mismatches = 0;
+    for (word, chunk) in words.iter_mut().zip(frame.chunks_exact(3)) {
+        let read_word = &chunk[0..2];
+        cfg_if! {
+            if #[cfg(feature = "crc")] {
+                let read_crc = chunk[2];
+                let crc_expect = CRC.checksum(read_word);
+                if policy != CrcPolicy::Ignore && read_crc != crc_expect {
+                    warn!("hdc302x[{}]::decode_words(): crc mismatch: frame={:?}, read_word={:?}, read_crc={}, crc_expect={}",
+                        label,
+                        frame,
+                        read_word,
+                        read_crc,
+                        crc_expect);
+                    if policy == CrcPolicy::Fail {
+                        return Err(Error::CrcMismatch);
+                    }
+                    accepted_mismatches += 1;
+                }
+            } else {
+                let _ = policy;
+            }
+        }
+        *word = u16::from_be_bytes([read_word[0], read_word[1]]);
+    }
+    Ok(accepted_mismatches)
+}
+
 impl<I2C, Delay> Hdc302x<I2C, Delay> {
     /// Create a new HDC302x driver instance
     pub fn new(i2c: I2C, delay: Delay, i2c_addr: I2cAddr) -> Self {
-        Self { i2c, delay, i2c_addr }
+        Self {
+            i2c,
+            delay,
+            i2c_addr,
+            crc_errors: 0,
+            crc_policy: CrcPolicy::default(),
+            verify_writes: false,
+            post_reset_policy: PostResetPolicy::default(),
+            post_reset_remaining: 0,
+            mode: DeviceMode::Unknown,
+            heater_level: None,
+            startup_grace_ms: STARTUP_TIME_MS,
+            startup_pending_ms: STARTUP_TIME_MS,
+            read_timeout_ms: DEFAULT_READ_TIMEOUT_MS,
+            label: i2c_addr.label(),
+            bus_speed: BusSpeed::Standard,
+            min_gap_us: 0,
+            strict_auto: false,
+            coefficients: ConversionCoefficients::NOMINAL,
+            nv_write_confirmed: false,
+            nv_writes: 0,
+            nv_write_limit: NV_WRITE_SESSION_LIMIT,
+            saturation_policy: SaturationPolicy::AsIs,
+            auto_mode: None,
+            reset_recovery: None,
+            observer: None,
+            alert_clear_policy: AlertClearPolicy::Acknowledge,
+        }
     }
-}
 
-#[cfg(feature = "blocking")]
-impl<I2C, Delay, E> Hdc302x<I2C, Delay>
-where
-    I2C: embedded_hal::i2c::I2c<Error = E>,
-    Delay: embedded_hal::delay::DelayNs,
-{
-    fn cmd_and_read(&mut self, cmd_bytes: &[u8; 2], read_vals: &mut [u16]) -> Result<(), Error<E>> {
-        let num_vals = read_vals.len();
-        // We are heapless, so have to have an upper bound
-        assert!(num_vals <= 2);
-
-        if read_vals.is_empty() {
-            if let Err(i2c_err) = self.i2c.write(self.i2c_addr.as_u8(), cmd_bytes) {
-                return Err(Error::I2c(i2c_err));
-            }
-        } else {
-            let mut read_buf = [0u8; 6];
-            let read_buf_slice = &mut read_buf[0..(3 * num_vals)];
-            trace!("hdc302x::cmd_and_read(): read_buf_slice.len()={}", read_buf_slice.len());
-            if let Err(_) = self.i2c.write_read(self.i2c_addr.as_u8(), cmd_bytes, read_buf_slice) {
-                // TODO: consider a timeout and/or retry limit
-                while let Err(_) = self.i2c.read(self.i2c_addr.as_u8(), read_buf_slice) {
-                    self.delay.delay_ms(1);
-                };
-            };
-            // TODO: consider whether to retry around this failure
-            for ii in 0..num_vals {
-                let read_word = &read_buf[ii*3+0..=ii*3+1];
-                cfg_if! {
-                    if #[cfg(feature = "crc")] {
-                        let read_crc = &read_buf[ii*3+2];
-                        let crc_expect = CRC.checksum(read_word);
-                        if *read_crc != crc_expect {
-                            warn!("hdc302x::cmd_and_read(): crc mismatch word {}/{}: read_buf={:?}, read_word={:?}, read_crc={}, crc_expect={}",
-                                ii,
-                                num_vals,
-                                read_buf,
-                                read_word,
-                                read_crc,
-                                crc_expect);
-                            return Err(Error::CrcMismatch);
-                        }
-                    }
-                }
-                read_vals[ii] = (read_word[0] as u16) << 8 | read_word[1] as u16;
-            }
+    /// Create a new HDC302x driver instance on a bus clocked at `bus_hz`.  At fast-mode-plus
+    /// speeds the driver adds settle time between a command and its response (see
+    /// [`BusSpeed::settle_us()`]); frequencies above [`MAX_BUS_HZ`] are out of spec and logged.
+    /// The minimum gap between transactions defaults to the bus free time of the speed class (see
+    /// [`BusSpeed::bus_free_us()`]).
+    pub fn new_with_bus_hz(i2c: I2C, delay: Delay, i2c_addr: I2cAddr, bus_hz: u32) -> Self {
+        let mut hdc302x = Self::new(i2c, delay, i2c_addr);
+        if bus_hz > MAX_BUS_HZ {
+            warn!("hdc302x[{}]: bus clock {} Hz exceeds the device maximum of {} Hz", hdc302x.label, bus_hz, MAX_BUS_HZ);
         }
-        Ok(())
+        hdc302x.bus_speed = BusSpeed::from_hz(bus_hz);
+        hdc302x.min_gap_us = hdc302x.bus_speed.bus_free_us();
+        hdc302x
     }
 
-    /// Trigger a one-shot measurement and return the raw sample pair
-    pub fn one_shot(&mut self, low_power_mode: LowPowerMode) -> Result<RawDatum, Error<E>> {
-        let cmd_bytes = start_sampling_command(SampleRate::OneShot, low_power_mode).to_be_bytes();
-        let mut read_buf = [0u16; 2];
-        self.cmd_and_read(&cmd_bytes, &mut read_buf)?;
-        Ok(RawDatum::TempAndRelHumid(RawTempAndRelHumid {
-            temperature: read_buf[0],
-            humidity: read_buf[1],
-        }))
+    /// Create a new HDC302x driver instance, selecting the I2C address by reading the board's
+    /// ADDR1 and ADDR strapping pins
+    pub fn new_with_strapping_pins<Addr1, Addr0, PinE>(
+        i2c: I2C,
+        delay: Delay,
+        addr1: &mut Addr1,
+        addr0: &mut Addr0,
+    ) -> Result<Self, PinE>
+    where
+        Addr1: embedded_hal::digital::InputPin<Error = PinE>,
+        Addr0: embedded_hal::digital::InputPin<Error = PinE>,
+    {
+        Ok(Self::new(i2c, delay, I2cAddr::from_strapping_pins(addr1, addr0)?))
     }
 
-    /// Enter auto mode (continuous self-timed sampling)
-    pub fn auto_start(&mut self, sample_rate: SampleRate, low_power_mode: LowPowerMode) -> Result<(), Error<E>> {
-        let cmd_bytes = start_sampling_command(sample_rate, low_power_mode).to_be_bytes();
-        self.cmd_and_read(&cmd_bytes, &mut [0u16; 0])?;
-        Ok(())
+    /// Name this instance, e.g. "intake", to tell several sensors apart in the log output of the
+    /// `defmt` and `log` features and in errors returned through [`Hdc302x::labelled()`];
+    /// defaults to the I2C address, e.g. "0x44".
+    pub fn set_label(&mut self, label: &'static str) {
+        self.label = label;
     }
 
-    /// exit auto mode and return to sleep
-    pub fn auto_stop(&mut self) -> Result<(), Error<E>> {
-        self.cmd_and_read(&Command::AutoExit.to_be_bytes(), &mut [0u16; 0])?;
-        Ok(())
+    /// Name of this instance, see [`Hdc302x::set_label()`]
+    pub fn label(&self) -> &'static str {
+        self.label
     }
 
-    /// read most recent temperature and relative humidity from auto mode
-    pub fn auto_read(&mut self, target: AutoReadTarget) -> Result<RawDatum, Error<E>> {
-        let cmd_bytes = match target {
-            AutoReadTarget::LastTempAndRelHumid => Command::AutoReadTempAndRelHumid,
-            AutoReadTarget::MinTemp => Command::AutoReadMinTemp,
-            AutoReadTarget::MaxTemp => Command::AutoReadMaxTemp,
-            AutoReadTarget::MinRelHumid => Command::AutoReadMinRelHumid,
-            AutoReadTarget::MaxRelHumid => Command::AutoReadMaxRelHumid,
-        }.to_be_bytes();
+    /// Run `op` on this instance and attach its label to any error, e.g.
+    /// `hdc302x.labelled(|hdc302x| hdc302x.read_status(false))?`.  For async calls, attach it
+    /// with [`Error::labelled()`] instead.
+    pub fn labelled<T, E>(&mut self, op: impl FnOnce(&mut Self) -> Result<T, Error<E>>) -> Result<T, LabelledError<E>> {
+        let label = self.label;
+        op(self).map_err(|error| error.labelled(label))
+    }
 
-        let mut read_buf = [0u16; 2];
-        let read_buf_slice = match target {
-            AutoReadTarget::LastTempAndRelHumid => &mut read_buf[..2],
-            AutoReadTarget::MinTemp => &mut read_buf[..1],
-            AutoReadTarget::MaxTemp => &mut read_buf[..1],
-            AutoReadTarget::MinRelHumid => &mut read_buf[..1],
-            AutoReadTarget::MaxRelHumid => &mut read_buf[..1],
-        };
+    /// Destroy the driver instance, returning the I2C bus and delay
+    pub fn destroy(self) -> (I2C, Delay) {
+        (self.i2c, self.delay)
+    }
 
-        self.cmd_and_read(&cmd_bytes, read_buf_slice)?;
+    /// Number of CRC mismatches detected on responses from the device since creation or the last
+    /// [`Hdc302x::reset_crc_error_count()`]
+    pub fn crc_error_count(&self) -> u32 {
+        self.crc_errors
+    }
 
-        Ok(match target {
-            AutoReadTarget::LastTempAndRelHumid => RawDatum::TempAndRelHumid(RawTempAndRelHumid {
-                temperature: read_buf[0],
-                humidity: read_buf[1],
-            }),
-            AutoReadTarget::MinTemp => RawDatum::MinTemp(read_buf[0]),
-            AutoReadTarget::MaxTemp => RawDatum::MaxTemp(read_buf[0]),
-            AutoReadTarget::MinRelHumid => RawDatum::MinRelHumid(read_buf[0]),
-            AutoReadTarget::MaxRelHumid => RawDatum::MaxRelHumid(read_buf[0]),
-        })
+    /// Reset the CRC mismatch counter
+    pub fn reset_crc_error_count(&mut self) {
+        self.crc_errors = 0;
     }
 
-    /// Condensation heater
-    pub fn heater(&mut self, heater_level: HeaterLevel) -> Result<(), Error<E>> {
-        self.cmd_and_read(&Command::HeaterDisable.to_be_bytes(), &mut [0u16; 0])?;
+    /// Choose what a CRC mismatch on a response does; defaults to [`CrcPolicy::Fail`].  Has no
+    /// effect without the `crc` feature, which compiles the checks in.
+    pub fn set_crc_policy(&mut self, policy: CrcPolicy) {
+        self.crc_policy = policy;
+    }
 
-        if let Some(setting) = heater_level.setting() {
-            let mut cmd_bytes = [0u8; 4];
-            cmd_bytes[0..2].copy_from_slice(&Command::HeaterConfig.to_be_bytes());
-            cmd_bytes[2..4].copy_from_slice(&setting.to_be_bytes());
-            if let Err(i2c_err) = self.i2c.write(self.i2c_addr.as_u8(), &cmd_bytes) {
-                return Err(Error::I2c(i2c_err));
-            }
-            self.cmd_and_read(&Command::HeaterEnable.to_be_bytes(), &mut [0u16; 0])?;
+    /// Choose how [`Hdc302x::one_shot_flagged()`] treats the first samples after a device reset
+    pub fn set_post_reset_policy(&mut self, policy: PostResetPolicy) {
+        self.post_reset_policy = policy;
+    }
+
+    /// Set how long the driver waits before its first command after [`Hdc302x::new()`] or a
+    /// software reset; defaults to [`STARTUP_TIME_MS`].  Use 0 if the application already waits
+    /// for the device to power up.  Replaces any wait still pending.
+    pub fn set_startup_grace_ms(&mut self, grace_ms: u32) {
+        self.startup_grace_ms = grace_ms;
+        self.startup_pending_ms = grace_ms;
+    }
+
+    /// Milliseconds the driver will still wait before sending its next command, 0 once the
+    /// device is ready
+    pub fn ready_after(&self) -> u32 {
+        self.startup_pending_ms
+    }
+
+    /// Set how long a read is polled, 1 ms apart, before the command is issued again or, after the
+    /// last attempt, [`Error::Timeout`] is returned; defaults to [`DEFAULT_READ_TIMEOUT_MS`].  A
+    /// command whose response never arrives takes at most twice this long.
+    pub fn set_read_timeout_ms(&mut self, timeout_ms: u32) {
+        self.read_timeout_ms = timeout_ms;
+    }
+
+    /// Set a minimum time between the end of one I2C transaction and the start of the next, for
+    /// I2C peripherals that issue back-to-back transactions faster than the device tolerates
+    /// (mostly at 1 MHz).  Defaults to 0 with [`Hdc302x::new()`], and to the bus free time of the
+    /// declared speed with [`Hdc302x::new_with_bus_hz()`].
+    pub fn set_min_transaction_gap_us(&mut self, gap_us: u32) {
+        self.min_gap_us = gap_us;
+    }
+
+    /// Minimum time between I2C transactions, see [`Hdc302x::set_min_transaction_gap_us()`]
+    pub fn min_transaction_gap_us(&self) -> u32 {
+        self.min_gap_us
+    }
+
+    /// In strict auto mode, commands that would disturb auto-mode sampling (one-shot triggers,
+    /// threshold, offset and non-volatile writes) fail with [`Error::AutoModeActive`] while the
+    /// driver knows the device is in auto mode.  Result, status and ID reads and heater control
+    /// remain available.
+    pub fn set_strict_auto_mode(&mut self, strict: bool) {
+        self.strict_auto = strict;
+    }
+
+    /// Apply a per-unit linear correction to every sample and auto-mode min/max value the driver
+    /// returns from now on; [`ConversionCoefficients::NOMINAL`] removes it
+    pub fn set_conversion_coefficients(&mut self, coefficients: ConversionCoefficients) {
+        self.coefficients = coefficients;
+    }
+
+    /// Correction currently applied to samples, see [`Hdc302x::set_conversion_coefficients()`]
+    pub fn conversion_coefficients(&self) -> ConversionCoefficients {
+        self.coefficients
+    }
+
+    /// Allow the next non-volatile memory write (alert thresholds or offsets).  Non-volatile
+    /// memory has limited write endurance, so every write must be confirmed separately.
+    pub fn confirm_nv_write(&mut self) {
+        self.nv_write_confirmed = true;
+    }
+
+    /// Number of non-volatile memory writes issued since the driver was created
+    pub fn nv_write_count(&self) -> u32 {
+        self.nv_writes
+    }
+
+    /// Set how many non-volatile memory writes the driver allows in this session, guarding
+    /// against writes repeated in a loop; defaults to [`NV_WRITE_SESSION_LIMIT`]
+    pub fn set_nv_write_limit(&mut self, limit: u32) {
+        self.nv_write_limit = limit;
+    }
+
+    fn take_nv_write_permit<E>(&mut self) -> Result<(), Error<E>> {
+        if !self.nv_write_confirmed {
+            warn!("hdc302x[{}]: non-volatile write refused, not confirmed", self.label);
+            return Err(Error::NvWriteRefused);
+        }
+        if self.nv_writes >= self.nv_write_limit {
+            warn!("hdc302x[{}]: non-volatile write refused, session limit of {} reached", self.label, self.nv_write_limit);
+            return Err(Error::NvWriteRefused);
         }
+        self.nv_write_confirmed = false;
+        self.nv_writes += 1;
         Ok(())
     }
 
-    /// Read and optionally clear status bits
-    pub fn read_status(&mut self, clear: bool) -> Result<StatusBits, Error<E>> {
-        let mut read_buf = [0u16; 1];
-        self.cmd_and_read(&Command::StatusRead.to_be_bytes(), &mut read_buf)?;
-        if clear {
-            self.cmd_and_read(&Command::StatusClear.to_be_bytes(), &mut [0u16; 0])?;
-        }
+    /// Read the status after every data write (heater configuration, alert thresholds, offsets,
+    /// power-on mode) and return [`Error::DeviceRejectedWrite`] if the device flagged a checksum
+    /// failure, i.e. the write did not take effect.  Off by default; costs one status read per
+    /// write.
+    pub fn set_write_verification(&mut self, verify: bool) {
+        self.verify_writes = verify;
+    }
 
-        Ok(StatusBits::from(read_buf[0]))
+    /// Choose how saturated readings (raw 0x0000 or 0xffff) are returned
+    pub fn set_saturation_policy(&mut self, policy: SaturationPolicy) {
+        self.saturation_policy = policy;
     }
 
-    /// Read the NIST-tracable serial number
-    pub fn read_serial_number(&mut self) -> Result<SerialNumber, Error<E>> {
-        let mut temp_u16 = [0u16; 1];
-        let mut bytes= [0u8; 6];
-        self.cmd_and_read(&Command::SerialID54.to_be_bytes(), &mut temp_u16)?;
-        bytes[5] = (temp_u16[0] >> 8) as u8;
-        bytes[4] = temp_u16[0] as u8;
-        self.cmd_and_read(&Command::SerialID32.to_be_bytes(), &mut temp_u16)?;
-        bytes[3] = (temp_u16[0] >> 8) as u8;
-        bytes[2] = temp_u16[0] as u8;
-        self.cmd_and_read(&Command::SerialID10.to_be_bytes(), &mut temp_u16)?;
-        bytes[1] = (temp_u16[0] >> 8) as u8;
-        bytes[0] = temp_u16[0] as u8;
-        Ok(SerialNumber(bytes))
+    /// Opt in to automatic recovery from device resets: when a status read (including the one
+    /// [`Hdc302x::auto_read()`] then does first) sees [`StatusBits::reset_since_clear`], the
+    /// driver clears the status, applies `config` again (see [`Hdc302x::apply_config()`]) and
+    /// returns [`Error::DeviceWasReset`] instead of stale results.  `None` turns it off.
+    pub fn set_reset_recovery(&mut self, config: Option<SavedConfig>) {
+        self.reset_recovery = config;
     }
 
-    /// Read the NIST-tracable manufacturer ID
-    pub fn read_manufacturer_id(&mut self) -> Result<ManufacturerId, Error<E>> {
-        let mut read_buf = [0u16; 1];
-        self.cmd_and_read(&Command::ManufacturerID.to_be_bytes(), &mut read_buf)?;
-        Ok(ManufacturerId::from(read_buf[0]))
+    /// Choose whether [`Hdc302x::wait_for_alert()`] and [`Hdc302x::read_alert_report()`] clear
+    /// the status after reading it, or leave the alert bits set until
+    /// [`Hdc302x::acknowledge_alerts()`]; defaults to [`AlertClearPolicy::Acknowledge`].  Clearing
+    /// the status also clears [`StatusBits::reset_since_clear`].
+    pub fn set_alert_clear_policy(&mut self, policy: AlertClearPolicy) {
+        self.alert_clear_policy = policy;
     }
 
-    /// software reset
-    pub fn software_reset(&mut self) -> Result<(), Error<E>> {
-        self.cmd_and_read(&Command::SoftReset.to_be_bytes(), &mut [0u16; 0])?;
-        Ok(())
+    /// Set a hook called with every driver state transition (mode, heater, resets, recovery and
+    /// non-volatile writes), e.g. to log them or assert on them in tests.  `None` removes it.
+    pub fn set_observer(&mut self, observer: Option<StateObserver>) {
+        self.observer = observer;
     }
 
-    // TODO: Support Alerting
-    // Command::WriteSetLowAlert,
-    // Command::WriteSetHighAlert,
-    // Command::WriteClearLowAlert,
-    // Command::WriteClearHighAlert,
-    // Command::AlertToNV,
+    /// Measurement mode the driver last put the device in
+    pub fn mode(&self) -> DeviceMode {
+        self.mode
+    }
 
-    // Command::ReadSetLowAlert,
-    // Command::ReadSetHighAlert,
-    // Command::ReadClearLowAlert,
-    // Command::ReadClearHighAlert,
+    fn notify(&self, event: StateEvent) {
+        if let Some(observer) = self.observer {
+            observer(&event);
+        }
+    }
 
-    // TODO: Support non-volatile offset
-    // Command::NVOffset,
+    fn finish_datum(&self, datum: RawDatum) -> RawDatum {
+        self.saturation_policy.apply(self.coefficients.correct(datum))
+    }
+
+    fn decode_one_shot<E>(&mut self, read_buf: &[u8]) -> Result<RawDatum, Error<E>> {
+        let mut words = [0u16; 2];
+        self.decode_response(read_buf, &mut words)?;
+        Ok(self.finish_datum(RawDatum::TempAndRelHumid(RawTempAndRelHumid {
+            temperature: words[0],
+            humidity: words[1],
+        })))
+    }
 
-    // TODO: Support reset state
-    // Command::ResetState,
+    fn check_safe_during_auto<E>(&self, command: u16) -> Result<(), Error<E>> {
+        if self.mode == DeviceMode::MeasurementPending && command != Command::SoftReset.as_u16() {
+            warn!("hdc302x[{}]: command {:?} refused, one-shot result pending", self.label, command);
+            return Err(Error::WrongMode(self.mode));
+        }
+        if self.strict_auto && self.mode == DeviceMode::Auto && !is_safe_during_auto(command) {
+            warn!("hdc302x[{}]: command {:?} refused in strict auto mode", self.label, command);
+            return Err(Error::AutoModeActive);
+        }
+        Ok(())
+    }
+
+    fn decode_response<E>(&mut self, frame: &[u8], words: &mut [u16]) -> Result<(), Error<E>> {
+        match decode_words(frame, words, self.crc_policy, self.label) {
+            Ok(accepted_mismatches) => {
+                self.crc_errors = self.crc_errors.saturating_add(accepted_mismatches);
+                Ok(())
+            }
+            Err(error) => {
+                #[cfg(feature = "crc")]
+                if let Error::CrcMismatch = error {
+                    self.crc_errors = self.crc_errors.saturating_add(1);
+                }
+                Err(error)
+            }
+        }
+    }
 }
 
-// TODO: consider adding type state pattern around the state of the device.  When we start a
-// one-shot, don't do things other than read the result until that happens.  When in auto mode,
-// don't do one-shot samples.  When sleeping (not in one-shot or auto mode), don't read auto mode
-// results.
-#[cfg(feature = "async")]
+// Written once as async code; the blocking flavor is generated from it
+#[blocking_and_async]
 impl<I2C, Delay, E> Hdc302x<I2C, Delay>
 where
     I2C: embedded_hal_async::i2c::I2c<Error = E>,
     Delay: embedded_hal_async::delay::DelayNs,
 {
+    async fn wait_ready_async(&mut self) {
+        if self.startup_pending_ms > 0 {
+            trace!("hdc302x[{}]: waiting {} ms for device startup", self.label, self.startup_pending_ms);
+            self.delay.delay_ms(self.startup_pending_ms).await;
+            self.startup_pending_ms = 0;
+        }
+    }
+
+    /// Leave the configured minimum gap before the next I2C transaction
+    async fn transaction_gap_async(&mut self) {
+        if self.min_gap_us > 0 {
+            self.delay.delay_us(self.min_gap_us).await;
+        }
+    }
+
     async fn cmd_and_read_async(&mut self, cmd_bytes: &[u8; 2], read_vals: &mut [u16]) -> Result<(), Error<E>> {
-        let num_vals = read_vals.len();
-        // We are heapless, so have to have an upper bound
-        assert!(num_vals <= 2);
+        self.check_safe_during_auto(u16::from_be_bytes(*cmd_bytes))?;
+        self.wait_ready_async().await;
+        // We are heapless, so responses are bounded by the largest one (two words)
+        let mut read_buf = [0u8; 6];
+        let Some(read_buf_slice) = read_buf.get_mut(..3 * read_vals.len()) else {
+            return Err(Error::InvalidInputData);
+        };
 
-        if read_vals.is_empty() {
-            if let Err(i2c_err) = self.i2c.write(self.i2c_addr.as_u8(), cmd_bytes).await {
-                return Err(Error::I2c(i2c_err));
-            }
-        } else {
-            let mut read_buf = [0u8; 6];
-            let read_buf_slice = &mut read_buf[0..(3 * num_vals)];
-            trace!("hdc302x::cmd_and_read_async(): read_buf_slice.len()={}", read_buf_slice.len());
-            if let Err(_) = self.i2c.write_read(self.i2c_addr.as_u8(), cmd_bytes, read_buf_slice).await {
-                // TODO: consider a timeout and/or retry limit
-                while let Err(_) = self.i2c.read(self.i2c_addr.as_u8(), read_buf_slice).await {
-                    self.delay.delay_ms(1).await;
+        let addr = self.i2c_addr.as_u8();
+        let mut issues = 0;
+        loop {
+            // A NACKed command was not accepted, so writing it again is safe
+            let mut attempts = 0;
+            loop {
+                self.transaction_gap_async().await;
+                let Err(i2c_err) = self.i2c.write(addr, cmd_bytes).await else {
+                    break;
                 };
+                attempts += 1;
+                if attempts >= CMD_WRITE_ATTEMPTS {
+                    return Err(Error::I2c(i2c_err));
+                }
+                self.delay.delay_ms(1).await;
+            }
+            if read_vals.is_empty() {
+                return Ok(());
+            }
+
+            let settle_us = self.bus_speed.settle_us();
+            if settle_us > 0 {
+                self.delay.delay_us(settle_us).await;
+            }
+
+            // The device NACKs reads until the result is ready.  If it never becomes ready, the
+            // command was probably lost and has to be issued again.
+            trace!("hdc302x[{}]::cmd_and_read_async(): read_buf_slice.len()={}", self.label, read_buf_slice.len());
+            let Err(error) = self.poll_read_async(read_buf_slice).await else {
+                break;
             };
-            // TODO: consider whether to retry around this failure
-            for ii in 0..num_vals {
-                let read_word = &read_buf[ii*3+0..=ii*3+1];
-                cfg_if! {
-                    if #[cfg(feature = "crc")] {
-                        let read_crc = &read_buf[ii*3+2];
-                        let crc_expect = CRC.checksum(read_word);
-                        if *read_crc != crc_expect {
-                            warn!("hdc302x::cmd_and_read_async(): crc mismatch word {}/{}: read_buf={:?}, read_word={:?}, read_crc={}, crc_expect={}",
-                                ii,
-                                num_vals,
-                                read_buf,
-                                read_word,
-                                read_crc,
-                                crc_expect);
-                            return Err(Error::CrcMismatch);
-                        }
+            issues += 1;
+            if issues >= CMD_ISSUES {
+                return Err(error);
+            }
+            warn!("hdc302x[{}]::cmd_and_read_async(): no response after {} ms, issuing command again", self.label, self.read_timeout_ms);
+        }
+        self.decode_response(read_buf_slice, read_vals)
+    }
+
+    async fn poll_read_async(&mut self, read_buf: &mut [u8]) -> Result<(), Error<E>> {
+        let addr = self.i2c_addr.as_u8();
+        let mut polls = 0;
+        loop {
+            read_buf.fill(UNRECEIVED);
+            self.transaction_gap_async().await;
+            match self.i2c.read(addr, read_buf).await {
+                Ok(()) => return Ok(()),
+                Err(_) => {
+                    polls += 1;
+                    if polls >= self.read_timeout_ms {
+                        return Err(Error::Timeout);
                     }
+                    self.delay.delay_ms(1).await;
                 }
-                read_vals[ii] = (read_word[0] as u16) << 8 | read_word[1] as u16;
             }
         }
+    }
+
+    /// Trigger a one-shot measurement without waiting for it.  The MCU can sleep or do other work
+    /// for [`LowPowerMode::conversion_time_us()`], then fetch the result with
+    /// [`Hdc302x::try_read_one_shot_async()`].  Until then, other commands (except a software reset)
+    /// fail with [`Error::WrongMode`].
+    ///
+    /// Returns [`Error::AutoModeActive`] if the driver started auto mode and has not stopped it.
+    pub async fn start_one_shot_async(&mut self, low_power_mode: LowPowerMode) -> Result<(), Error<E>> {
+        if self.mode == DeviceMode::Auto {
+            return Err(Error::AutoModeActive);
+        }
+        let cmd_bytes = start_sampling_command(SampleRate::OneShot, low_power_mode).to_be_bytes();
+        self.cmd_and_read_async(&cmd_bytes, &mut [0u16; 0]).await?;
+        self.mode = DeviceMode::MeasurementPending;
+        Ok(())
+    }
+
+    /// Fetch the result of a one-shot started with [`Hdc302x::start_one_shot_async()`] if the
+    /// conversion has finished, or `None` (with the result still pending) if the device is still
+    /// converting.  Returns [`Error::WrongMode`] if no result is pending, which the device would
+    /// only NACK.
+    pub async fn try_read_one_shot_async(&mut self) -> Result<Option<RawDatum>, Error<E>>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        if self.mode != DeviceMode::MeasurementPending {
+            return Err(Error::WrongMode(self.mode));
+        }
+        let mut read_buf = [UNRECEIVED; 6];
+        self.transaction_gap_async().await;
+        match self.i2c.read(self.i2c_addr.as_u8(), &mut read_buf).await {
+            Ok(()) => {}
+            Err(i2c_err) if matches!(i2c_err.kind(), ErrorKind::NoAcknowledge(_)) => return Ok(None),
+            Err(i2c_err) => return Err(Error::I2c(i2c_err)),
+        }
+        self.mode = DeviceMode::Sleep;
+        self.decode_one_shot(&read_buf).map(Some)
+    }
+
+    /// Take a one-shot measurement with the lowest latency: trigger it, wait exactly
+    /// [`LowPowerMode::conversion_time_us()`] and read the result once, instead of polling every
+    /// 1 ms as [`Hdc302x::one_shot_async()`] does.  Falls back to polling if the device is still
+    /// converting.
+    pub async fn one_shot_fast_async(&mut self, low_power_mode: LowPowerMode) -> Result<RawDatum, Error<E>>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        self.start_one_shot_async(low_power_mode).await?;
+        self.delay.delay_us(low_power_mode.conversion_time_us()).await;
+        match self.try_read_one_shot_async().await? {
+            Some(datum) => Ok(datum),
+            None => self.read_one_shot_result_async().await,
+        }
+    }
+
+    /// Fetch the result of a one-shot started with [`Hdc302x::start_one_shot_async()`], polling
+    /// while the device is still converting
+    pub(crate) async fn read_one_shot_result_async(&mut self) -> Result<RawDatum, Error<E>> {
+        if self.mode != DeviceMode::MeasurementPending {
+            return Err(Error::WrongMode(self.mode));
+        }
+        // the device returns to sleep once the result is read or the conversion is abandoned
+        self.mode = DeviceMode::Sleep;
+        let mut read_buf = [0u8; 6];
+        self.poll_read_async(&mut read_buf).await?;
+        self.decode_one_shot(&read_buf)
+    }
+
+    /// Write a command followed by a data word and its CRC.  The device ignores data words without
+    /// a valid CRC (and flags [`StatusBits::checksum_failure`]), so every data write goes through
+    /// here.  With [`Hdc302x::set_write_verification()`], the status is read back and
+    /// [`Error::DeviceRejectedWrite`] returned if the device flagged the write.
+    async fn write_cmd_word_async(&mut self, command: Command, word: u16) -> Result<(), Error<E>> {
+        self.check_safe_during_auto(command.as_u16())?;
+        self.wait_ready_async().await;
+        let mut write_buf = [0u8; 5];
+        write_buf[0..2].copy_from_slice(&command.to_be_bytes());
+        write_buf[2..4].copy_from_slice(&word.to_be_bytes());
+        write_buf[4] = crc8(&write_buf[2..4]);
+        self.transaction_gap_async().await;
+        self.i2c.write(self.i2c_addr.as_u8(), &write_buf).await.map_err(Error::I2c)?;
+        if !self.verify_writes {
+            return Ok(());
+        }
+        // reading the status does not clear the flag, which reflects only the last data write
+        let mut status = [0u16; 1];
+        self.cmd_and_read_async(&Command::StatusRead.to_be_bytes(), &mut status).await?;
+        if StatusBits::from(status[0]).checksum_failure {
+            warn!("hdc302x[{}]::write_cmd_word_async(): device rejected data for command {:?}", self.label, command.as_u16());
+            return Err(Error::DeviceRejectedWrite);
+        }
         Ok(())
     }
 
     /// Trigger a one-shot measurement and return the raw sample pair
+    ///
+    /// Returns [`Error::AutoModeActive`] if the driver started auto mode and has not stopped it.
     pub async fn one_shot_async(&mut self, low_power_mode: LowPowerMode) -> Result<RawDatum, Error<E>> {
+        if self.mode == DeviceMode::Auto {
+            return Err(Error::AutoModeActive);
+        }
         let cmd_bytes = start_sampling_command(SampleRate::OneShot, low_power_mode).to_be_bytes();
         let mut read_buf = [0u16; 2];
         self.cmd_and_read_async(&cmd_bytes, &mut read_buf).await?;
-        Ok(RawDatum::TempAndRelHumid(RawTempAndRelHumid {
+        Ok(self.finish_datum(RawDatum::TempAndRelHumid(RawTempAndRelHumid {
             temperature: read_buf[0],
             humidity: read_buf[1],
-        }))
+        })))
+    }
+
+    /// Trigger a one-shot measurement and return it with quality flags.
+    ///
+    /// The status register is checked first; if it reports a reset, the status is cleared and
+    /// the post-reset policy (see [`Hdc302x::set_post_reset_policy()`]) is applied to the
+    /// following samples.  Saturated samples are flagged if the saturation policy (see
+    /// [`Hdc302x::set_saturation_policy()`]) says so, and samples taken with the heater on are
+    /// flagged from the same status read.  A sample failing its CRC check is measured once more
+    /// and flagged [`MeasurementFlags::crc_retried`].
+    pub async fn one_shot_flagged_async(&mut self, low_power_mode: LowPowerMode) -> Result<FlaggedSample, Error<E>> {
+        let status = self.read_status_async(false).await?;
+        if status.reset_since_clear {
+            self.cmd_and_read_async(&Command::StatusClear.to_be_bytes(), &mut [0u16; 0]).await?;
+            self.post_reset_remaining = self.post_reset_policy.count();
+        }
+        loop {
+            #[cfg_attr(not(feature = "crc"), allow(unused_mut))]
+            let mut crc_retried = false;
+            let datum = match self.one_shot_async(low_power_mode).await {
+                #[cfg(feature = "crc")]
+                Err(Error::CrcMismatch) => {
+                    crc_retried = true;
+                    self.one_shot_async(low_power_mode).await?
+                }
+                result => result?,
+            };
+            let sample = match datum {
+                RawDatum::TempAndRelHumid(sample) => sample,
+                _ => return Err(Error::InvalidInputData),
+            };
+            let post_reset = self.post_reset_remaining > 0;
+            self.post_reset_remaining = self.post_reset_remaining.saturating_sub(1);
+            if post_reset && matches!(self.post_reset_policy, PostResetPolicy::Discard(_)) {
+                continue;
+            }
+            return Ok(FlaggedSample {
+                sample,
+                flags: MeasurementFlags {
+                    post_reset,
+                    saturated: self.saturation_policy == SaturationPolicy::Flag && sample.is_saturated(),
+                    heater_on: status.heater_enabled,
+                    crc_retried,
+                    ..Default::default()
+                },
+            });
+        }
     }
 
     /// Enter auto mode (continuous self-timed sampling)
     pub async fn auto_start_async(&mut self, sample_rate: SampleRate, low_power_mode: LowPowerMode) -> Result<(), Error<E>> {
         let cmd_bytes = start_sampling_command(sample_rate, low_power_mode).to_be_bytes();
         self.cmd_and_read_async(&cmd_bytes, &mut [0u16; 0]).await?;
+        self.mode = match sample_rate {
+            SampleRate::OneShot => DeviceMode::Sleep,
+            _ => DeviceMode::Auto,
+        };
+        self.auto_mode = match sample_rate {
+            SampleRate::OneShot => None,
+            _ => Some((sample_rate, low_power_mode)),
+        };
+        if sample_rate != SampleRate::OneShot {
+            self.notify(StateEvent::AutoStarted { sample_rate, low_power_mode });
+        }
         Ok(())
     }
 
     /// exit auto mode and return to sleep
     pub async fn auto_stop_async(&mut self) -> Result<(), Error<E>> {
         self.cmd_and_read_async(&Command::AutoExit.to_be_bytes(), &mut [0u16; 0]).await?;
+        self.mode = DeviceMode::Sleep;
+        self.auto_mode = None;
+        self.notify(StateEvent::AutoStopped);
         Ok(())
     }
 
     /// read most recent temperature and relative humidity from auto mode
+    ///
+    /// Returns [`Error::AutoModeRequired`] if the driver knows the device is not in auto mode.  With
+    /// reset recovery on (see [`Hdc302x::set_reset_recovery()`]), checks the status first.
     pub async fn auto_read_async(&mut self, target: AutoReadTarget) -> Result<RawDatum, Error<E>> {
+        if self.reset_recovery.is_some() {
+            self.read_status_async(false).await?;
+        }
+        if self.mode == DeviceMode::Sleep {
+            return Err(Error::AutoModeRequired);
+        }
         let cmd_bytes = match target {
             AutoReadTarget::LastTempAndRelHumid => Command::AutoReadTempAndRelHumid,
             AutoReadTarget::MinTemp => Command::AutoReadMinTemp,
@@ -310,7 +689,7 @@ where
 
         self.cmd_and_read_async(&cmd_bytes, read_buf_slice).await?;
 
-        Ok(match target {
+        Ok(self.finish_datum(match target {
             AutoReadTarget::LastTempAndRelHumid => RawDatum::TempAndRelHumid(RawTempAndRelHumid {
                 temperature: read_buf[0],
                 humidity: read_buf[1],
@@ -319,25 +698,111 @@ where
             AutoReadTarget::MaxTemp => RawDatum::MaxTemp(read_buf[0]),
             AutoReadTarget::MinRelHumid => RawDatum::MinRelHumid(read_buf[0]),
             AutoReadTarget::MaxRelHumid => RawDatum::MaxRelHumid(read_buf[0]),
-        })
+        }))
     }
 
     /// Condensation heater
     pub async fn heater_async(&mut self, heater_level: HeaterLevel) -> Result<(), Error<E>> {
+        let previous = self.heater_level;
+        self.heater_level = None;
         self.cmd_and_read_async(&Command::HeaterDisable.to_be_bytes(), &mut [0u16; 0]).await?;
+        self.heater_level = Some(HeaterLevel::Off);
 
         if let Some(setting) = heater_level.setting() {
-            let mut cmd_bytes = [0u8; 4];
-            cmd_bytes[0..2].copy_from_slice(&Command::HeaterConfig.to_be_bytes());
-            cmd_bytes[2..4].copy_from_slice(&setting.to_be_bytes());
-            if let Err(i2c_err) = self.i2c.write(self.i2c_addr.as_u8(), &cmd_bytes).await {
-                return Err(Error::I2c(i2c_err));
-            }
+            self.write_cmd_word_async(Command::HeaterConfig, setting).await?;
             self.cmd_and_read_async(&Command::HeaterEnable.to_be_bytes(), &mut [0u16; 0]).await?;
+            self.heater_level = Some(heater_level);
+        }
+        if previous != Some(heater_level) {
+            self.notify(StateEvent::HeaterChanged { from: previous, to: heater_level });
         }
         Ok(())
     }
 
+    /// Read back the heater configuration register and whether the heater is enabled, e.g. to
+    /// diagnose unexpected current draw; [`HeaterState::is_consistent()`] cross-checks both with
+    /// the setting the driver last applied
+    pub async fn read_heater_state_async(&mut self) -> Result<HeaterState, Error<E>> {
+        let mut config = [0u16; 1];
+        self.cmd_and_read_async(&Command::HeaterConfig.to_be_bytes(), &mut config).await?;
+        let mut status = [0u16; 1];
+        self.cmd_and_read_async(&Command::StatusRead.to_be_bytes(), &mut status).await?;
+        Ok(HeaterState {
+            config: config[0],
+            enabled: StatusBits::from(status[0]).heater_enabled,
+            expected: self.heater_level,
+        })
+    }
+
+    /// Set the heater like [`Hdc302x::heater_async()`], checking the supply voltage against
+    /// `lockout` first: returns [`Error::SupplyTooLow`] below the minimum and derates to 25% power
+    /// in the derating band.  Returns the level actually applied.
+    pub async fn heater_with_supply_async<S: SupplyVoltage>(
+        &mut self,
+        heater_level: HeaterLevel,
+        lockout: &HeaterLockout,
+        supply: &mut S,
+    ) -> Result<HeaterLevel, Error<E>> {
+        let millivolts = supply.millivolts();
+        let Some(allowed) = lockout.allowed_level(heater_level, millivolts) else {
+            warn!("hdc302x[{}]: heater refused at {} mV", self.label, millivolts);
+            return Err(Error::SupplyTooLow);
+        };
+        if allowed != heater_level {
+            warn!("hdc302x[{}]: heater derated at {} mV", self.label, millivolts);
+        }
+        self.heater_async(allowed).await?;
+        Ok(allowed)
+    }
+
+    /// Check that the heater works: take a one-shot sample, run the heater at `heater_level` for
+    /// `dwell_ms`, take another and turn the heater off again (also on error).  A working heater
+    /// raises the temperature and lowers the relative humidity; how much depends on the level,
+    /// the dwell and the enclosure, so compare against limits measured on a known-good unit.
+    pub async fn measure_heater_delta_async(
+        &mut self,
+        heater_level: HeaterLevel,
+        dwell_ms: u32,
+        low_power_mode: LowPowerMode,
+    ) -> Result<HeaterDelta, Error<E>> {
+        let before = match self.one_shot_async(low_power_mode).await? {
+            RawDatum::TempAndRelHumid(sample) => sample,
+            _ => return Err(Error::InvalidInputData),
+        };
+        self.heater_async(heater_level).await?;
+        self.delay.delay_ms(dwell_ms).await;
+        let during = self.one_shot_async(low_power_mode).await;
+        self.heater_async(HeaterLevel::Off).await?;
+        let during = match during? {
+            RawDatum::TempAndRelHumid(sample) => sample,
+            _ => return Err(Error::InvalidInputData),
+        };
+        Ok(HeaterDelta { before, during })
+    }
+
+    /// Drive condensation off the sensor: run the heater at `heater_level`, sampling every
+    /// [`DRYING_SAMPLE_INTERVAL_MS`], until the relative humidity drops below
+    /// `target_humidity_percent` or `max_duration_ms` elapses, then turn the heater off (also on
+    /// error).  The heater warms the die, so the samples read lower than the surroundings; let it
+    /// cool before trusting measurements again.  See [`Hdc302x::begin_recondition()`] to run it
+    /// step by step.
+    pub async fn dry_sensor_async(
+        &mut self,
+        heater_level: HeaterLevel,
+        target_humidity_percent: f32,
+        max_duration_ms: u32,
+        low_power_mode: LowPowerMode,
+    ) -> Result<DryingReport, Error<E>> {
+        self.begin_recondition(heater_level, target_humidity_percent, max_duration_ms, low_power_mode).finish_async().await
+    }
+
+    pub(crate) async fn sample_async(&mut self, low_power_mode: LowPowerMode) -> Result<RawTempAndRelHumid, Error<E>> {
+        match self.one_shot_async(low_power_mode).await? {
+            RawDatum::TempAndRelHumid(sample) => Ok(sample),
+            _ => Err(Error::InvalidInputData),
+        }
+    }
+
     /// Read and optionally clear status bits
     pub async fn read_status_async(&mut self, clear: bool) -> Result<StatusBits, Error<E>> {
         let mut read_buf = [0u16; 1];
@@ -346,23 +811,34 @@ where
             self.cmd_and_read_async(&Command::StatusClear.to_be_bytes(), &mut [0u16; 0]).await?;
         }
 
-        Ok(StatusBits::from(read_buf[0]))
+        let status = StatusBits::from(read_buf[0]);
+        if let (true, Some(config)) = (status.reset_since_clear, self.reset_recovery) {
+            self.recover_from_reset_async(&config).await?;
+            return Err(Error::DeviceWasReset);
+        }
+        Ok(status)
+    }
+
+    async fn recover_from_reset_async(&mut self, config: &SavedConfig) -> Result<(), Error<E>> {
+        warn!("hdc302x[{}]: device was reset, applying the saved configuration again", self.label);
+        self.cmd_and_read_async(&Command::StatusClear.to_be_bytes(), &mut [0u16; 0]).await?;
+        self.mode = DeviceMode::Unknown;
+        self.auto_mode = None;
+        self.heater_level = Some(HeaterLevel::Off);
+        self.post_reset_remaining = self.post_reset_policy.count();
+        self.notify(StateEvent::Recovering);
+        self.apply_config_async(config).await?;
+        self.notify(StateEvent::Recovered);
+        Ok(())
     }
 
     /// Read the NIST-tracable serial number
     pub async fn read_serial_number_async(&mut self) -> Result<SerialNumber, Error<E>> {
-        let mut temp_u16 = [0u16; 1];
-        let mut bytes= [0u8; 6];
-        self.cmd_and_read_async(&Command::SerialID54.to_be_bytes(), &mut temp_u16).await?;
-        bytes[5] = (temp_u16[0] >> 8) as u8;
-        bytes[4] = temp_u16[0] as u8;
-        self.cmd_and_read_async(&Command::SerialID32.to_be_bytes(), &mut temp_u16).await?;
-        bytes[3] = (temp_u16[0] >> 8) as u8;
-        bytes[2] = temp_u16[0] as u8;
-        self.cmd_and_read_async(&Command::SerialID10.to_be_bytes(), &mut temp_u16).await?;
-        bytes[1] = (temp_u16[0] >> 8) as u8;
-        bytes[0] = temp_u16[0] as u8;
-        Ok(SerialNumber(bytes))
+        let mut words = [0u16; 3];
+        self.cmd_and_read_async(&Command::SerialID54.to_be_bytes(), &mut words[0..1]).await?;
+        self.cmd_and_read_async(&Command::SerialID32.to_be_bytes(), &mut words[1..2]).await?;
+        self.cmd_and_read_async(&Command::SerialID10.to_be_bytes(), &mut words[2..3]).await?;
+        Ok(SerialNumber::from_words(words))
     }
 
     /// Read the NIST-tracable manufacturer ID
@@ -372,27 +848,479 @@ where
         Ok(ManufacturerId::from(read_buf[0]))
     }
 
+    /// Create a driver after confirming an HDC302x answers at `i2c_addr`: reads the manufacturer
+    /// ID and returns [`Error::DeviceNotFound`] if nothing answers or the ID is not Texas
+    /// Instruments', e.g. because the address straps do not match `i2c_addr`
+    pub async fn probe_async(i2c: I2C, delay: Delay, i2c_addr: I2cAddr) -> Result<Self, Error<E>>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        let mut hdc302x = Self::new(i2c, delay, i2c_addr);
+        let manufacturer_id = match hdc302x.read_manufacturer_id_async().await {
+            Ok(manufacturer_id) => manufacturer_id,
+            Err(Error::I2c(i2c_err)) if matches!(i2c_err.kind(), ErrorKind::NoAcknowledge(_)) => {
+                warn!("hdc302x[{}]::probe_async(): no device at {:?}", hdc302x.label, i2c_addr.as_u8());
+                return Err(Error::DeviceNotFound);
+            }
+            Err(error) => return Err(error),
+        };
+        if !matches!(manufacturer_id, ManufacturerId::TexasInstruments) {
+            warn!("hdc302x[{}]::probe_async(): unexpected manufacturer ID {:?}", hdc302x.label, u16::from(manufacturer_id));
+            return Err(Error::DeviceNotFound);
+        }
+        Ok(hdc302x)
+    }
+
+    /// Like [`Hdc302x::probe_async()`], also reading the serial number, e.g. for the bring-up log
+    pub async fn probe_with_serial_async(i2c: I2C, delay: Delay, i2c_addr: I2cAddr) -> Result<(Self, SerialNumber), Error<E>>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        let mut hdc302x = Self::probe_async(i2c, delay, i2c_addr).await?;
+        let serial_number = hdc302x.read_serial_number_async().await?;
+        Ok((hdc302x, serial_number))
+    }
+
+    /// Probe all four addresses (see [`Hdc302x::probe_async()`]) and report which an HDC302x
+    /// answers at, e.g. for provisioning tools or boards whose address straps vary.  Bus errors
+    /// other than a NACK abort the scan.
+    pub async fn detect_async(i2c: &mut I2C, delay: &mut Delay) -> Result<DetectedAddrs, Error<E>>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        let mut detected = DetectedAddrs::default();
+        for i2c_addr in I2cAddr::ALL {
+            match Hdc302x::probe_async(&mut *i2c, &mut *delay, i2c_addr).await {
+                Ok(_) => detected.insert(i2c_addr),
+                Err(Error::I2c(i2c_err)) => return Err(Error::I2c(i2c_err)),
+                Err(_) => {}
+            }
+        }
+        Ok(detected)
+    }
+
     /// software reset
     pub async fn software_reset_async(&mut self) -> Result<(), Error<E>> {
         self.cmd_and_read_async(&Command::SoftReset.to_be_bytes(), &mut [0u16; 0]).await?;
+        self.startup_pending_ms = self.startup_grace_ms;
+        // the programmed power-on/reset state may have started auto mode
+        self.mode = DeviceMode::Unknown;
+        self.auto_mode = None;
+        self.heater_level = Some(HeaterLevel::Off);
+        self.notify(StateEvent::SoftwareReset);
+        Ok(())
+    }
+
+    /// Take a one-shot sample and push it into `bridge`, applying the bridge's overflow policy.
+    /// With [`OverflowPolicy::Block`] this waits in 1 ms steps until the consumer makes room.
+    #[cfg(feature = "heapless")]
+    pub async fn one_shot_into_async<const N: usize>(
+        &mut self,
+        low_power_mode: LowPowerMode,
+        bridge: &mut SampleBridge<'_, RawTempAndRelHumid, N>,
+    ) -> Result<PushOutcome, Error<E>> {
+        let sample = match self.one_shot_async(low_power_mode).await? {
+            RawDatum::TempAndRelHumid(sample) => sample,
+            _ => return Err(Error::InvalidInputData),
+        };
+        match bridge.push_async(sample, &mut self.delay, None).await {
+            Ok(outcome) | Err((outcome, _)) => Ok(outcome),
+        }
+    }
+
+    /// Take a one-shot sample if `pacer` says one is due, otherwise return `None` immediately
+    pub async fn one_shot_if_due_async<C: Clock>(
+        &mut self,
+        low_power_mode: LowPowerMode,
+        pacer: &mut Pacer<C>,
+    ) -> Result<Option<RawDatum>, Error<E>> {
+        if !pacer.is_due() {
+            return Ok(None);
+        }
+        let datum = self.one_shot_async(low_power_mode).await?;
+        pacer.mark_sampled();
+        Ok(Some(datum))
+    }
+
+    /// Wait until `pacer` says a sample is due, then take a one-shot sample
+    pub async fn one_shot_paced_async<C: Clock>(
+        &mut self,
+        low_power_mode: LowPowerMode,
+        pacer: &mut Pacer<C>,
+    ) -> Result<RawDatum, Error<E>> {
+        let wait_ms = pacer.until_due_ms();
+        if wait_ms > 0 {
+            self.delay.delay_ms(wait_ms).await;
+        }
+        let datum = self.one_shot_async(low_power_mode).await?;
+        pacer.mark_sampled();
+        Ok(datum)
+    }
+
+    /// Program the alert set high threshold: the alert sets when the temperature or relative
+    /// humidity rises above these values.  The device keeps 9 bits of temperature and 7 bits of
+    /// relative humidity, so the programmed threshold is quantized.
+    pub async fn write_alert_set_high_async(&mut self, centigrade: f32, humidity_percent: f32) -> Result<(), Error<E>> {
+        let word = encode_alert_threshold(centigrade, humidity_percent).ok_or(AlertLimitsError::OutOfRange)?;
+        self.write_cmd_word_async(Command::WriteSetHighAlert, word).await
+    }
+
+    /// Program the alert set low threshold: the alert sets when the temperature or relative
+    /// humidity falls below these values.  The device keeps 9 bits of temperature and 7 bits of
+    /// relative humidity, so the programmed threshold is quantized.
+    pub async fn write_alert_set_low_async(&mut self, centigrade: f32, humidity_percent: f32) -> Result<(), Error<E>> {
+        let word = encode_alert_threshold(centigrade, humidity_percent).ok_or(AlertLimitsError::OutOfRange)?;
+        self.write_cmd_word_async(Command::WriteSetLowAlert, word).await
+    }
+
+    /// Program the alert clear high threshold: an active high alert clears when the temperature
+    /// and relative humidity fall below these values.  Returns [`Error::InvalidAlertLimits`] unless
+    /// the clear threshold is at or below the set high threshold currently programmed.
+    pub async fn write_alert_clear_high_async(&mut self, centigrade: f32, humidity_percent: f32) -> Result<(), Error<E>> {
+        self.write_alert_clear_async(Command::ReadSetHighAlert, Command::WriteClearHighAlert, centigrade, humidity_percent, true).await
+    }
+
+    /// Program the alert clear low threshold: an active low alert clears when the temperature
+    /// and relative humidity rise above these values.  Returns [`Error::InvalidAlertLimits`] unless
+    /// the clear threshold is at or above the set low threshold currently programmed.
+    pub async fn write_alert_clear_low_async(&mut self, centigrade: f32, humidity_percent: f32) -> Result<(), Error<E>> {
+        self.write_alert_clear_async(Command::ReadSetLowAlert, Command::WriteClearLowAlert, centigrade, humidity_percent, false).await
+    }
+
+    async fn write_alert_clear_async(
+        &mut self,
+        read_set: Command,
+        write_clear: Command,
+        centigrade: f32,
+        humidity_percent: f32,
+        high: bool,
+    ) -> Result<(), Error<E>> {
+        let clear = encode_alert_threshold(centigrade, humidity_percent).ok_or(AlertLimitsError::OutOfRange)?;
+        let mut set = [0u16; 1];
+        self.cmd_and_read_async(&read_set.to_be_bytes(), &mut set).await?;
+        if !clear_inside_set(clear, set[0], high) {
+            let reason = if high { AlertLimitsError::ClearHighOutsideSet } else { AlertLimitsError::ClearLowOutsideSet };
+            return Err(reason.into());
+        }
+        self.write_cmd_word_async(write_clear, clear).await
+    }
+
+    /// Program all four alert thresholds from a validated [`AlertLimits`]
+    pub async fn write_alert_limits_async(&mut self, limits: &AlertLimits) -> Result<(), Error<E>> {
+        let commands = [
+            Command::WriteSetLowAlert,
+            Command::WriteSetHighAlert,
+            Command::WriteClearLowAlert,
+            Command::WriteClearHighAlert,
+        ];
+        for (command, word) in commands.into_iter().zip(limits.words()) {
+            self.write_cmd_word_async(command, word).await?;
+        }
+        Ok(())
+    }
+
+    /// Wait for the ALERT pin to go high, then read the status register to report which alert
+    /// fired.  Lets the application sleep in auto mode until a threshold is crossed.
+    ///
+    /// The pin is polled every millisecond; the async API sleeps until the pin changes instead.
+    #[only_blocking]
+    pub fn wait_for_alert<P>(&mut self, pin: &mut P) -> Result<StatusBits, Error<E>>
+    where
+        P: embedded_hal::digital::InputPin,
+    {
+        while !pin.is_high().map_err(|_| Error::AlertPin)? {
+            self.delay.delay_ms(1);
+        }
+        let status = self.read_status(self.alert_clear_policy == AlertClearPolicy::OnDelivery)?;
+        trace!("hdc302x[{}]: alert pin raised, status={:?}", self.label, status.raw());
+        Ok(status)
+    }
+
+    /// Wait for the ALERT pin to go high, then read the status register to report which alert
+    /// fired.  Lets the application sleep in auto mode until a threshold is crossed.
+    #[only_async]
+    pub async fn wait_for_alert_async<P>(&mut self, pin: &mut P) -> Result<StatusBits, Error<E>>
+    where
+        P: embedded_hal_async::digital::Wait,
+    {
+        pin.wait_for_high().await.map_err(|_| Error::AlertPin)?;
+        let status = self.read_status_async(self.alert_clear_policy == AlertClearPolicy::OnDelivery).await?;
+        trace!("hdc302x[{}]: alert pin raised, status={:?}", self.label, status.raw());
+        Ok(status)
+    }
+
+    /// Read back the four programmed alert thresholds, as raw register words and decoded into °C
+    /// and %RH
+    pub async fn read_alert_thresholds_async(&mut self) -> Result<AlertThresholds, Error<E>> {
+        let words = self.read_alert_words_async().await?;
+        Ok(AlertThresholds {
+            set_low: decode_alert_threshold(words[0]),
+            set_high: decode_alert_threshold(words[1]),
+            clear_low: decode_alert_threshold(words[2]),
+            clear_high: decode_alert_threshold(words[3]),
+        })
+    }
+
+    /// Read the status and the programmed thresholds and report which quantity crossed which
+    /// limit
+    pub async fn read_alert_report_async(&mut self) -> Result<AlertReport, Error<E>> {
+        let status = self.read_status_async(self.alert_clear_policy == AlertClearPolicy::OnDelivery).await?;
+        let thresholds = self.read_alert_thresholds_async().await?;
+        Ok(AlertReport::new(&status, &thresholds))
+    }
+
+    /// Clear the alert bits (and the rest of the status) once the application has handled them,
+    /// see [`Hdc302x::set_alert_clear_policy()`]
+    pub async fn acknowledge_alerts_async(&mut self) -> Result<(), Error<E>> {
+        self.cmd_and_read_async(&Command::StatusClear.to_be_bytes(), &mut [0u16; 0]).await
+    }
+
+    /// Store the four alert thresholds currently programmed in non-volatile memory, so the device
+    /// loads them at power-up.  Waits [`NV_WRITE_TIME_MS`] for the write to complete, then checks
+    /// the status and reads the thresholds back; returns [`Error::NvWriteFailed`] if the device
+    /// reports a checksum failure or the thresholds changed.  Requires
+    /// [`Hdc302x::confirm_nv_write()`] first.
+    pub async fn store_alert_thresholds_nv_async(&mut self) -> Result<(), Error<E>> {
+        self.take_nv_write_permit()?;
+        let expected = self.read_alert_words_async().await?;
+        self.cmd_and_read_async(&Command::AlertToNV.to_be_bytes(), &mut [0u16; 0]).await?;
+        self.delay.delay_ms(NV_WRITE_TIME_MS).await;
+        if self.read_status_async(false).await?.checksum_failure {
+            warn!("hdc302x[{}]: checksum failure after storing alert thresholds", self.label);
+            return Err(Error::NvWriteFailed);
+        }
+        if self.read_alert_words_async().await? != expected {
+            warn!("hdc302x[{}]: alert thresholds changed while storing them", self.label);
+            return Err(Error::NvWriteFailed);
+        }
+        self.notify(StateEvent::Provisioned(NvItem::AlertThresholds));
         Ok(())
     }
 
-    // TODO: Support Alerting
-    // Command::WriteSetLowAlert,
-    // Command::WriteSetHighAlert,
-    // Command::WriteClearLowAlert,
-    // Command::WriteClearHighAlert,
-    // Command::AlertToNV,
+    async fn read_alert_words_async(&mut self) -> Result<[u16; 4], Error<E>> {
+        let mut words = [0u16; 4];
+        let commands = [
+            Command::ReadSetLowAlert,
+            Command::ReadSetHighAlert,
+            Command::ReadClearLowAlert,
+            Command::ReadClearHighAlert,
+        ];
+        for (word, command) in words.chunks_exact_mut(1).zip(commands) {
+            self.cmd_and_read_async(&command.to_be_bytes(), word).await?;
+        }
+        Ok(words)
+    }
 
-    // Command::ReadSetLowAlert,
-    // Command::ReadSetHighAlert,
-    // Command::ReadClearLowAlert,
-    // Command::ReadClearHighAlert,
+    /// Read the raw contents of the registers backed by non-volatile memory
+    pub async fn read_nv_config_async(&mut self) -> Result<NvConfig, Error<E>> {
+        let mut words = [0u16; 6];
+        let commands = [
+            Command::ReadSetLowAlert,
+            Command::ReadSetHighAlert,
+            Command::ReadClearLowAlert,
+            Command::ReadClearHighAlert,
+            Command::NVOffset,
+            Command::ResetState,
+        ];
+        for (word, command) in words.chunks_exact_mut(1).zip(commands) {
+            self.cmd_and_read_async(&command.to_be_bytes(), word).await?;
+        }
+        Ok(NvConfig {
+            alert_set_low: words[0],
+            alert_set_high: words[1],
+            alert_clear_low: words[2],
+            alert_clear_high: words[3],
+            offsets: words[4],
+            reset_state: words[5],
+        })
+    }
 
-    // TODO: Support non-volatile offset
-    // Command::NVOffset,
+    /// Read the device's non-volatile configuration and save it to `store` as the expected state
+    pub async fn mirror_nv_config_async<S: ConfigStore>(&mut self, store: &mut S) -> Result<NvConfig, Error<E>> {
+        let config = self.read_nv_config_async().await?;
+        store.save(&config.to_bytes()).map_err(|_| Error::ConfigStore)?;
+        Ok(config)
+    }
 
-    // TODO: Support reset state
-    // Command::ResetState,
+    /// Compare the device's non-volatile configuration against the copy saved in `store`
+    pub async fn check_nv_config_async<S: ConfigStore>(&mut self, store: &mut S) -> Result<NvMirrorStatus, Error<E>> {
+        let mut blob = [0u8; NvConfig::BLOB_LEN];
+        let expected = match store.load(&mut blob).map_err(|_| Error::ConfigStore)? {
+            None => return Ok(NvMirrorStatus::NoMirror),
+            Some(len) => NvConfig::from_bytes(blob.get(..len).ok_or(Error::ConfigStore)?).ok_or(Error::ConfigStore)?,
+        };
+        let actual = self.read_nv_config_async().await?;
+        if actual == expected {
+            Ok(NvMirrorStatus::Match)
+        } else {
+            Ok(NvMirrorStatus::Mismatch { expected, actual })
+        }
+    }
+
+    /// Check the device is still healthy, intended to be run periodically (e.g. daily)
+    ///
+    /// Reads the serial number and status, takes a one-shot sample and compares them and the CRC
+    /// mismatch counter against `limits`.  The device must not be in auto mode and the heater
+    /// must be off, since it would bias the sample.  See [`Hdc302x::begin_self_test()`] to run
+    /// the check step by step.
+    pub async fn integrity_check_async(&mut self, limits: &IntegrityLimits) -> Result<IntegrityReport, Error<E>> {
+        self.begin_self_test(*limits).finish_async().await
+    }
+
+    /// Program the relative humidity offset in non-volatile memory, e.g. to correct drift after
+    /// field aging.  The device adds the offset to every RH result.  `percent` is rounded to the
+    /// nearest [`RH_OFFSET_STEP_PERCENT`] and must be within about ±24.8 %RH; the temperature
+    /// offset is kept.  Requires [`Hdc302x::confirm_nv_write()`] first.  Waits
+    /// [`NV_WRITE_TIME_MS`] for the write to complete and returns the offset actually programmed.
+    pub async fn write_rh_offset_async(&mut self, percent: f32) -> Result<f32, Error<E>> {
+        let rh_byte = encode_rh_offset(percent).ok_or(Error::InvalidInputData)?;
+        self.update_nv_offset_async(0xff00, u16::from(rh_byte) << 8).await?;
+        Ok(decode_rh_offset(rh_byte))
+    }
+
+    /// Program the temperature offset in non-volatile memory.  The device adds the offset to
+    /// every temperature result.  `centigrade` is rounded to the nearest
+    /// [`TEMP_OFFSET_STEP_CENTIGRADE`] and must be within about ±21.7 °C; the relative humidity
+    /// offset is kept.  Requires [`Hdc302x::confirm_nv_write()`] first.  Waits
+    /// [`NV_WRITE_TIME_MS`] for the write to complete and returns the offset actually programmed.
+    pub async fn write_temp_offset_async(&mut self, centigrade: f32) -> Result<f32, Error<E>> {
+        let temp_byte = encode_temp_offset(centigrade).ok_or(Error::InvalidInputData)?;
+        self.update_nv_offset_async(0x00ff, u16::from(temp_byte)).await?;
+        Ok(decode_temp_offset(temp_byte))
+    }
+
+    /// Program the relative humidity offset given as a `uom` ratio, see
+    /// [`Hdc302x::write_rh_offset()`]
+    #[cfg(feature = "uom")]
+    pub async fn write_rh_offset_uom_async(&mut self, offset: Ratio) -> Result<Ratio, Error<E>> {
+        let programmed = self.write_rh_offset_async(offset.get::<ratio::percent>()).await?;
+        Ok(Ratio::new::<ratio::percent>(programmed))
+    }
+
+    /// Program the temperature offset given as a `uom` interval, see
+    /// [`Hdc302x::write_temp_offset()`]
+    #[cfg(feature = "uom")]
+    pub async fn write_temp_offset_uom_async(&mut self, offset: TemperatureInterval) -> Result<TemperatureInterval, Error<E>> {
+        let programmed = self.write_temp_offset_async(offset.get::<temperature_interval::degree_celsius>()).await?;
+        Ok(TemperatureInterval::new::<temperature_interval::degree_celsius>(programmed))
+    }
+
+    /// Read the temperature and relative humidity offsets programmed in non-volatile memory
+    pub async fn read_offsets_async(&mut self) -> Result<Offsets, Error<E>> {
+        let mut word = [0u16; 1];
+        self.cmd_and_read_async(&Command::NVOffset.to_be_bytes(), &mut word).await?;
+        Ok(Offsets::from_word(word[0]))
+    }
+
+    /// Capture the alert thresholds, offsets, heater setting and measurement mode, to restore
+    /// them with [`Hdc302x::apply_config_async()`] after a reset.  The heater setting and
+    /// measurement mode are the ones the driver last set.  In auto mode, auto mode is stopped to
+    /// read the offsets and restarted, which also restarts the min/max tracking.
+    pub async fn save_config_async(&mut self) -> Result<SavedConfig, Error<E>> {
+        let alert_words = self.read_alert_words_async().await?;
+        let auto_mode = self.auto_mode;
+        // reading the offset register is not one of the commands auto mode tolerates
+        let restart = auto_mode.filter(|_| self.mode == DeviceMode::Auto);
+        if restart.is_some() {
+            self.auto_stop_async().await?;
+        }
+        let mut offsets = [0u16; 1];
+        self.cmd_and_read_async(&Command::NVOffset.to_be_bytes(), &mut offsets).await?;
+        if let Some((sample_rate, low_power_mode)) = restart {
+            self.auto_start_async(sample_rate, low_power_mode).await?;
+        }
+        Ok(SavedConfig {
+            alert_set_low: alert_words[0],
+            alert_set_high: alert_words[1],
+            alert_clear_low: alert_words[2],
+            alert_clear_high: alert_words[3],
+            offsets: offsets[0],
+            heater: self.heater_level,
+            auto_mode,
+        })
+    }
+
+    /// Push a configuration captured by [`Hdc302x::save_config_async()`] back to the device in
+    /// one call: alert thresholds, then offsets, heater and auto mode.  The offsets are only
+    /// written if they differ, which needs [`Hdc302x::confirm_nv_write()`] first.
+    pub async fn apply_config_async(&mut self, config: &SavedConfig) -> Result<(), Error<E>> {
+        let thresholds = [
+            (Command::WriteSetLowAlert, config.alert_set_low),
+            (Command::WriteSetHighAlert, config.alert_set_high),
+            (Command::WriteClearLowAlert, config.alert_clear_low),
+            (Command::WriteClearHighAlert, config.alert_clear_high),
+        ];
+        for (command, word) in thresholds {
+            self.write_cmd_word_async(command, word).await?;
+        }
+        let mut offsets = [0u16; 1];
+        self.cmd_and_read_async(&Command::NVOffset.to_be_bytes(), &mut offsets).await?;
+        if offsets[0] != config.offsets {
+            self.update_nv_offset_async(0xffff, config.offsets).await?;
+        }
+        if let Some(heater_level) = config.heater {
+            self.heater_async(heater_level).await?;
+        }
+        if let Some((sample_rate, low_power_mode)) = config.auto_mode {
+            self.auto_start_async(sample_rate, low_power_mode).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace the `mask` bits of the NV offset register with `bits` and wait for the write
+    async fn update_nv_offset_async(&mut self, mask: u16, bits: u16) -> Result<(), Error<E>> {
+        self.take_nv_write_permit()?;
+        let mut word = [0u16; 1];
+        self.cmd_and_read_async(&Command::NVOffset.to_be_bytes(), &mut word).await?;
+        self.write_cmd_word_async(Command::NVOffset, (word[0] & !mask) | bits).await?;
+        self.delay.delay_ms(NV_WRITE_TIME_MS).await;
+        self.notify(StateEvent::Provisioned(NvItem::Offsets));
+        Ok(())
+    }
+
+    /// Read the programmed power-up and reset measurement mode, `None` if the register holds a
+    /// value that is not a valid mode (a mis-provisioned device).  Sleep reads back as
+    /// [`SampleRate::OneShot`] with [`LowPowerMode::LPM0`].
+    pub async fn read_power_on_mode_async(&mut self) -> Result<Option<(SampleRate, LowPowerMode)>, Error<E>> {
+        let mut word = [0u16; 1];
+        self.cmd_and_read_async(&Command::ResetState.to_be_bytes(), &mut word).await?;
+        Ok(decode_reset_state(word[0]))
+    }
+
+    /// Bring the tracked mode in line with the device after power-up or an MCU restart, instead of
+    /// assuming sleep.  First probes with an auto mode read, which a device in auto mode answers
+    /// without leaving it, so its min/max history and alerts are kept.  Only if that is NACKed does
+    /// it read the programmed power-up mode (see [`Hdc302x::write_power_on_mode()`]), and if that is
+    /// an auto mode (whose first result was not ready yet) restarts it, since reading the register
+    /// is not one of the commands auto mode tolerates.  Returns the resulting [`Hdc302x::mode()`],
+    /// [`DeviceMode::Unknown`] for a mis-provisioned register.
+    pub async fn init_async(&mut self) -> Result<DeviceMode, Error<E>> {
+        let mut probe = [0u16; 2];
+        if self.cmd_and_read_async(&Command::AutoReadTempAndRelHumid.to_be_bytes(), &mut probe).await.is_ok() {
+            self.mode = DeviceMode::Auto;
+            return Ok(self.mode);
+        }
+        match self.read_power_on_mode_async().await? {
+            Some((SampleRate::OneShot, _)) => self.mode = DeviceMode::Sleep,
+            Some((sample_rate, low_power_mode)) => self.auto_start_async(sample_rate, low_power_mode).await?,
+            None => {
+                warn!("hdc302x[{}]::init_async(): invalid power-on mode register", self.label);
+                self.mode = DeviceMode::Unknown;
+            }
+        }
+        Ok(self.mode)
+    }
+
+    /// Program the measurement mode the device enters at power-up and after a reset, e.g. auto
+    /// mode so it samples (and the ALERT pin is armed) right after brown-out recovery.
+    /// [`SampleRate::OneShot`] selects sleep, the factory default.  Requires
+    /// [`Hdc302x::confirm_nv_write()`] first.  Waits [`NV_WRITE_TIME_MS`] for the write to
+    /// complete.
+    pub async fn write_power_on_mode_async(&mut self, sample_rate: SampleRate, low_power_mode: LowPowerMode) -> Result<(), Error<E>> {
+        self.take_nv_write_permit()?;
+        self.write_cmd_word_async(Command::ResetState, reset_state_value(sample_rate, low_power_mode)).await?;
+        self.delay.delay_ms(NV_WRITE_TIME_MS).await;
+        self.notify(StateEvent::Provisioned(NvItem::PowerOnMode));
+        Ok(())
+    }
 }
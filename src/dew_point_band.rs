@@ -0,0 +1,105 @@
+use crate::hw_def::*;
+use crate::psychrometrics::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Dew point band to keep the relative humidity alert thresholds at
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DewPointBand {
+    /// alert when the dew point drops below this, in degrees centigrade
+    pub low_centigrade: f32,
+    /// alert when the dew point rises above this, in degrees centigrade
+    pub high_centigrade: f32,
+    /// dew point hysteresis between the set and clear thresholds, in degrees centigrade
+    pub hysteresis_centigrade: f32,
+}
+
+/// Relative humidity alert thresholds in percent, as programmed into the device
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RhAlertThresholds {
+    /// set low threshold
+    pub set_low: f32,
+    /// clear low threshold
+    pub clear_low: f32,
+    /// clear high threshold
+    pub clear_high: f32,
+    /// set high threshold
+    pub set_high: f32,
+}
+
+/// Keeps the device's relative humidity alert thresholds tracking a constant dew point band as
+/// the ambient temperature drifts.
+///
+/// The device compares relative humidity, not dew point, so the thresholds have to be recomputed
+/// as the temperature changes.  The controller only asks for a rewrite when the recomputed
+/// thresholds differ from the programmed ones at the device's alert resolution (7 bits of
+/// relative humidity), which keeps threshold writes to a minimum.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug)]
+pub struct DewPointBandController {
+    band: DewPointBand,
+    programmed: Option<[u16; 4]>,
+}
+impl DewPointBandController {
+    /// Create a controller for `band`; the first [`DewPointBandController::update()`] always
+    /// asks for a write
+    pub fn new(band: DewPointBand) -> Self {
+        Self { band, programmed: None }
+    }
+
+    /// The band being maintained
+    pub fn band(&self) -> DewPointBand {
+        self.band
+    }
+
+    /// Change the band, forcing a rewrite on the next update
+    pub fn set_band(&mut self, band: DewPointBand) {
+        self.band = band;
+        self.programmed = None;
+    }
+
+    /// Forget what was programmed, e.g. after a device reset, forcing a rewrite on the next update
+    pub fn invalidate(&mut self) {
+        self.programmed = None;
+    }
+
+    /// Thresholds for the band at the given ambient temperature, quantized to the device's alert
+    /// resolution
+    pub fn thresholds_at(&self, centigrade: f32) -> RhAlertThresholds {
+        let [set_low, clear_low, clear_high, set_high] = self.quantized_at(centigrade);
+        RhAlertThresholds {
+            set_low: raw_rel_humid_to_percent(set_low),
+            clear_low: raw_rel_humid_to_percent(clear_low),
+            clear_high: raw_rel_humid_to_percent(clear_high),
+            set_high: raw_rel_humid_to_percent(set_high),
+        }
+    }
+
+    /// Feed the current ambient temperature.  Returns the thresholds to program if they differ
+    /// from the ones last returned, `None` if the device is already up to date.
+    pub fn update(&mut self, centigrade: f32) -> Option<RhAlertThresholds> {
+        let quantized = self.quantized_at(centigrade);
+        if self.programmed == Some(quantized) {
+            return None;
+        }
+        self.programmed = Some(quantized);
+        Some(self.thresholds_at(centigrade))
+    }
+
+    fn quantized_at(&self, centigrade: f32) -> [u16; 4] {
+        let band = &self.band;
+        let rh = |dew_point: f32| {
+            let percent = humidity_percent_for_dew_point(centigrade, dew_point).clamp(0.0, 100.0);
+            percent_to_raw_rel_humid(percent) & ALERT_RH_MASK
+        };
+        [
+            rh(band.low_centigrade),
+            rh(band.low_centigrade + band.hysteresis_centigrade),
+            rh(band.high_centigrade - band.hysteresis_centigrade),
+            rh(band.high_centigrade),
+        ]
+    }
+}
@@ -0,0 +1,106 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+use hdc302x_macros::blocking_and_async;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Heater pulsed at a fixed duty cycle for anti-condensation within a power budget
+///
+/// Each cycle runs the heater for `on_ms` and leaves it off for `off_ms`, using the driver's
+/// delay.  The temperature is sampled at the end of every pulse; if it rose more than
+/// `max_rise_centigrade` above the temperature before the first pulse, the next cycle is skipped
+/// to let the die cool.
+#[derive(Clone, Copy, Debug)]
+pub struct HeaterDutyCycle {
+    /// heater power during a pulse
+    pub heater_level: HeaterLevel,
+    /// pulse length
+    pub on_ms: u32,
+    /// pause after each pulse
+    pub off_ms: u32,
+    /// largest allowed temperature rise over the starting temperature in °C
+    pub max_rise_centigrade: f32,
+}
+impl HeaterDutyCycle {
+    /// 25% heater power at a 20% duty cycle (2 s on, 8 s off), at most 5 °C above the start
+    pub const ANTI_CONDENSATION: Self = Self {
+        heater_level: HeaterLevel::On25Percent,
+        on_ms: 2_000,
+        off_ms: 8_000,
+        max_rise_centigrade: 5.0,
+    };
+
+    /// Fraction of the time the heater is on, 0 to 1
+    pub fn duty(&self) -> f32 {
+        match self.on_ms.saturating_add(self.off_ms) {
+            0 => 0.0,
+            period_ms => self.on_ms as f32 / period_ms as f32,
+        }
+    }
+}
+
+/// What [`HeaterDutyCycle`] did in a run
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DutyCycleReport {
+    /// cycles run, including skipped ones
+    pub cycles: u32,
+    /// cycles skipped because the temperature rose too far
+    pub skipped_cycles: u32,
+    /// total heater on time in milliseconds
+    pub heated_ms: u32,
+    /// largest temperature rise over the starting temperature seen at the end of a pulse, in °C
+    pub max_rise_centigrade: f32,
+    /// the run was cancelled before all cycles ran
+    pub cancelled: bool,
+}
+
+#[blocking_and_async]
+impl HeaterDutyCycle {
+    /// Run `cycles` heater cycles, or until `cancel` returns true; it is checked before every
+    /// cycle.  The heater is off when this returns, also on error.  Dropping the async future
+    /// during a pulse leaves the heater on, so cancel through `cancel` instead.
+    pub async fn run_async<I2C, Delay, E, F>(
+        &self,
+        hdc302x: &mut Hdc302x<I2C, Delay>,
+        cycles: u32,
+        low_power_mode: LowPowerMode,
+        mut cancel: F,
+    ) -> Result<DutyCycleReport, Error<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+        Delay: embedded_hal_async::delay::DelayNs,
+        F: FnMut() -> bool,
+    {
+        let start_centigrade = hdc302x.sample_async(low_power_mode).await?.centigrade();
+        let mut report = DutyCycleReport::default();
+        let mut cool_down = false;
+        while report.cycles < cycles {
+            if cancel() {
+                report.cancelled = true;
+                break;
+            }
+            report.cycles += 1;
+            if cool_down {
+                cool_down = false;
+                report.skipped_cycles += 1;
+                hdc302x.delay.delay_ms(self.on_ms.saturating_add(self.off_ms)).await;
+                continue;
+            }
+
+            hdc302x.heater_async(self.heater_level).await?;
+            hdc302x.delay.delay_ms(self.on_ms).await;
+            let sample = hdc302x.sample_async(low_power_mode).await;
+            hdc302x.heater_async(HeaterLevel::Off).await?;
+            report.heated_ms = report.heated_ms.saturating_add(self.on_ms);
+
+            let rise = sample?.centigrade() - start_centigrade;
+            report.max_rise_centigrade = report.max_rise_centigrade.max(rise);
+            cool_down = rise > self.max_rise_centigrade;
+            hdc302x.delay.delay_ms(self.off_ms).await;
+        }
+        Ok(report)
+    }
+}
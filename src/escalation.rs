@@ -0,0 +1,248 @@
+use crate::hw_def::*;
+use crate::pacing::*;
+use crate::types::*;
+
+use hdc302x_macros::blocking_and_async;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+#[cfg(feature = "defmt")]
+use defmt::warn;
+#[cfg(feature = "log")]
+use log::warn;
+#[cfg(not(any(feature = "defmt", feature = "log")))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Stage of an [`Escalation`], in increasing severity
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum EscalationStage {
+    /// the condition is not active
+    Idle,
+    /// the condition is active and was logged
+    Log,
+    /// the application was notified through the hook set with [`Escalation::set_hook()`]
+    Notify,
+    /// the heater is on to drive off condensation
+    Heater,
+    /// maintenance is requested; latched until [`Escalation::acknowledge()`]
+    Maintenance,
+}
+impl EscalationStage {
+    fn next(self) -> Self {
+        match self {
+            Self::Idle => Self::Log,
+            Self::Log => Self::Notify,
+            Self::Notify => Self::Heater,
+            Self::Heater | Self::Maintenance => Self::Maintenance,
+        }
+    }
+}
+
+/// When an [`Escalation`] enters a stage: once the condition has been active without a break
+/// for `active_ms`, or has become active `occurrences` times since the last
+/// [`Escalation::acknowledge()`], whichever comes first
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EscalationTrigger {
+    /// time the condition must persist, `None` to ignore persistence
+    pub active_ms: Option<u64>,
+    /// number of times the condition must occur, `None` to ignore repetition
+    pub occurrences: Option<u32>,
+}
+impl EscalationTrigger {
+    /// Never enter the stage
+    pub const NEVER: Self = Self { active_ms: None, occurrences: None };
+    /// Enter the stage as soon as the condition is active
+    pub const IMMEDIATELY: Self = Self { active_ms: Some(0), occurrences: None };
+
+    /// Enter the stage once the condition persists for `active_ms`
+    pub const fn after_ms(active_ms: u64) -> Self {
+        Self { active_ms: Some(active_ms), occurrences: None }
+    }
+
+    /// Enter the stage once the condition persists for `active_ms` or occurs `occurrences` times
+    pub const fn after_ms_or_occurrences(active_ms: u64, occurrences: u32) -> Self {
+        Self { active_ms: Some(active_ms), occurrences: Some(occurrences) }
+    }
+
+    fn is_met(&self, active_ms: u64, occurrences: u32) -> bool {
+        self.active_ms.is_some_and(|ms| active_ms >= ms) || self.occurrences.is_some_and(|count| occurrences >= count)
+    }
+}
+
+/// Triggers of the stages of an [`Escalation`].  A stage's trigger is only checked once the
+/// previous stage was entered, so a stage set to [`EscalationTrigger::NEVER`] ends the escalation.
+#[derive(Clone, Copy, Debug)]
+pub struct EscalationPolicy {
+    /// when to log the condition
+    pub log: EscalationTrigger,
+    /// when to call the hook
+    pub notify: EscalationTrigger,
+    /// when to turn the heater on
+    pub heater: EscalationTrigger,
+    /// when to request maintenance
+    pub maintenance: EscalationTrigger,
+    /// heater power while in the heater stage
+    pub heater_level: HeaterLevel,
+}
+impl EscalationPolicy {
+    /// Log at once, notify after 1 min or 3 occurrences, heat at 50% after 5 min and request
+    /// maintenance after 1 h or 10 occurrences
+    pub const MONITORING: Self = Self {
+        log: EscalationTrigger::IMMEDIATELY,
+        notify: EscalationTrigger::after_ms_or_occurrences(60_000, 3),
+        heater: EscalationTrigger::after_ms(300_000),
+        maintenance: EscalationTrigger::after_ms_or_occurrences(3_600_000, 10),
+        heater_level: HeaterLevel::On50Percent,
+    };
+
+    fn trigger(&self, stage: EscalationStage) -> EscalationTrigger {
+        match stage {
+            EscalationStage::Idle => EscalationTrigger::NEVER,
+            EscalationStage::Log => self.log,
+            EscalationStage::Notify => self.notify,
+            EscalationStage::Heater => self.heater,
+            EscalationStage::Maintenance => self.maintenance,
+        }
+    }
+}
+
+/// Hook called with every stage an [`Escalation`] enters from [`EscalationStage::Notify`] on
+pub type EscalationHook = fn(EscalationStage);
+
+/// Turns a repeated or persistent alert condition into staged actions: log, notify, heat and
+/// request maintenance
+///
+/// Call [`Escalation::update()`] with the condition, or `poll()` with the driver to read the
+/// device's alert status and switch the heater.  The stage drops back to idle when the condition
+/// clears, except that a maintenance request stays latched until acknowledged.
+#[derive(Debug)]
+pub struct Escalation<C> {
+    clock: C,
+    policy: EscalationPolicy,
+    hook: Option<EscalationHook>,
+    stage: EscalationStage,
+    active_since_ms: Option<u64>,
+    occurrences: u32,
+    maintenance: bool,
+    heater_on: bool,
+}
+impl<C: Clock> Escalation<C> {
+    /// Create an idle escalation
+    pub fn new(clock: C, policy: EscalationPolicy) -> Self {
+        Self {
+            clock,
+            policy,
+            hook: None,
+            stage: EscalationStage::Idle,
+            active_since_ms: None,
+            occurrences: 0,
+            maintenance: false,
+            heater_on: false,
+        }
+    }
+
+    /// Set or remove the hook called when entering the notify stage and above
+    pub fn set_hook(&mut self, hook: Option<EscalationHook>) {
+        self.hook = hook;
+    }
+
+    /// Current stage
+    pub fn stage(&self) -> EscalationStage {
+        self.stage
+    }
+
+    /// Times the condition became active since creation or the last acknowledge
+    pub fn occurrences(&self) -> u32 {
+        self.occurrences
+    }
+
+    /// true once the maintenance stage was reached, until acknowledged
+    pub fn maintenance_requested(&self) -> bool {
+        self.maintenance
+    }
+
+    /// Clear the maintenance request and the occurrence count, e.g. after servicing the sensor
+    pub fn acknowledge(&mut self) {
+        self.maintenance = false;
+        self.occurrences = 0;
+    }
+
+    /// Account for the current state of the condition and return the resulting stage
+    pub fn update(&mut self, active: bool) -> EscalationStage {
+        let now = self.clock.now_ms();
+        if !active {
+            self.active_since_ms = None;
+            self.stage = EscalationStage::Idle;
+            return self.stage;
+        }
+        let since = match self.active_since_ms {
+            Some(since) => since,
+            None => {
+                self.occurrences = self.occurrences.saturating_add(1);
+                *self.active_since_ms.insert(now)
+            }
+        };
+        let active_ms = now.saturating_sub(since);
+
+        // never de-escalate while the condition is active
+        let mut target = self.stage;
+        while target < EscalationStage::Maintenance && self.policy.trigger(target.next()).is_met(active_ms, self.occurrences) {
+            target = target.next();
+        }
+        while self.stage < target {
+            self.stage = self.stage.next();
+            self.enter(self.stage);
+        }
+        self.stage
+    }
+
+    /// Account for the device's alert status; the condition is any active alert
+    pub fn update_status(&mut self, status: &StatusBits) -> EscalationStage {
+        self.update(status.at_least_one_alert)
+    }
+
+    fn enter(&mut self, stage: EscalationStage) {
+        match stage {
+            EscalationStage::Idle => {}
+            EscalationStage::Log => {
+                warn!("hdc302x: alert condition active, occurrence {}", self.occurrences);
+            }
+            EscalationStage::Maintenance => {
+                warn!("hdc302x: alert condition persists, requesting maintenance");
+                self.maintenance = true;
+            }
+            EscalationStage::Notify | EscalationStage::Heater => {}
+        }
+        if let Some(hook) = self.hook
+            && stage >= EscalationStage::Notify
+        {
+            hook(stage);
+        }
+    }
+}
+
+#[blocking_and_async]
+impl<C: Clock> Escalation<C> {
+    /// Read the device's alert status, update the escalation with it and switch the heater on
+    /// in the heater stage and above, and off again once the condition clears
+    pub async fn poll_async<I2C, Delay, E>(&mut self, hdc302x: &mut Hdc302x<I2C, Delay>) -> Result<EscalationStage, Error<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+        Delay: embedded_hal_async::delay::DelayNs,
+    {
+        let status = hdc302x.read_status_async(false).await?;
+        let stage = self.update_status(&status);
+        let heat = stage >= EscalationStage::Heater;
+        if heat != self.heater_on {
+            let level = if heat { self.policy.heater_level } else { HeaterLevel::Off };
+            hdc302x.heater_async(level).await?;
+            self.heater_on = heat;
+        }
+        Ok(stage)
+    }
+}
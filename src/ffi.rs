@@ -0,0 +1,217 @@
+//! C ABI over the blocking driver, for mixed C/Rust firmware.
+//!
+//! The C side supplies the I2C bus and delay as callbacks in an [`Hdc302xFfiBus`] and owns the
+//! [`Hdc302xFfi`] storage, so no allocator is needed.  The handle holds the whole driver, so its
+//! state (device mode, start-up grace, CRC counters, ...) carries over from one call to the next.  Functions return 0 on success, the negated
+//! [`Error::code()`] on a driver error, or [`HDC302X_FFI_INVALID_ARGUMENT`].  The matching C
+//! declarations are in `include/hdc302x.h`.
+//!
+//! This crate stays an `rlib`; link it into C firmware through a small `staticlib` crate that
+//! depends on it with the `ffi` feature and provides the panic handler (or `std`).
+
+#![allow(unsafe_code)]
+
+use crate::alert::*;
+use crate::hw_def::*;
+use crate::types::*;
+
+use core::ffi::c_void;
+use core::mem::{MaybeUninit, align_of, offset_of, size_of};
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, Operation, SevenBitAddress};
+
+/// Returned for a null pointer, an invalid address or inconsistent alert limits
+pub const HDC302X_FFI_INVALID_ARGUMENT: i32 = -100;
+
+/// I2C bus and delay callbacks provided by the C side.  The bus callbacks return 0 on success.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Hdc302xFfiBus {
+    /// passed back to every callback
+    pub ctx: *mut c_void,
+    /// write `len` bytes to the 7-bit address `addr`
+    pub write: extern "C" fn(ctx: *mut c_void, addr: u8, data: *const u8, len: usize) -> i32,
+    /// read `len` bytes from the 7-bit address `addr`
+    pub read: extern "C" fn(ctx: *mut c_void, addr: u8, data: *mut u8, len: usize) -> i32,
+    /// wait at least `us` microseconds
+    pub delay_us: extern "C" fn(ctx: *mut c_void, us: u32),
+}
+
+/// Bytes reserved for the driver inside an [`Hdc302xFfi`]
+pub const HDC302X_FFI_STORAGE_SIZE: usize = 256;
+
+const HANDLE_MAGIC: u32 = 0x4844_4333;
+
+/// Device handle, allocated by the C side and filled in by [`hdc302x_init()`].  Opaque to C; it
+/// must not be copied once initialized.  The driver storage comes first so it gets the handle's
+/// alignment.
+#[repr(C, align(8))]
+#[derive(Debug)]
+pub struct Hdc302xFfi {
+    driver: MaybeUninit<[u8; HDC302X_FFI_STORAGE_SIZE]>,
+    magic: u32,
+}
+
+type FfiDriver = Hdc302x<FfiI2c, FfiDelay>;
+
+const _: () = assert!(size_of::<FfiDriver>() <= HDC302X_FFI_STORAGE_SIZE);
+const _: () = assert!(align_of::<FfiDriver>() <= align_of::<Hdc302xFfi>());
+const _: () = assert!(offset_of!(Hdc302xFfi, driver) % align_of::<FfiDriver>() == 0);
+
+/// Error reported by a bus callback
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FfiBusError(pub i32);
+impl embedded_hal::i2c::Error for FfiBusError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+struct FfiI2c(Hdc302xFfiBus);
+impl ErrorType for FfiI2c {
+    type Error = FfiBusError;
+}
+impl embedded_hal::i2c::I2c for FfiI2c {
+    fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            let status = match operation {
+                Operation::Write(bytes) => (self.0.write)(self.0.ctx, address, bytes.as_ptr(), bytes.len()),
+                Operation::Read(buf) => (self.0.read)(self.0.ctx, address, buf.as_mut_ptr(), buf.len()),
+            };
+            if status != 0 {
+                return Err(FfiBusError(status));
+            }
+        }
+        Ok(())
+    }
+}
+
+struct FfiDelay(Hdc302xFfiBus);
+impl embedded_hal::delay::DelayNs for FfiDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        (self.0.delay_us)(self.0.ctx, ns.div_ceil(1000));
+    }
+}
+
+fn with_driver<T>(
+    dev: *mut Hdc302xFfi,
+    op: impl FnOnce(&mut FfiDriver) -> Result<T, Error<FfiBusError>>,
+) -> Result<T, i32> {
+    // SAFETY: the C caller passes null or a pointer to a live handle
+    let Some(dev) = (unsafe { dev.as_mut() }) else {
+        return Err(HDC302X_FFI_INVALID_ARGUMENT);
+    };
+    if dev.magic != HANDLE_MAGIC {
+        return Err(HDC302X_FFI_INVALID_ARGUMENT);
+    }
+    // SAFETY: the magic is only set once hdc302x_init() has written a driver into the storage,
+    // which the asserts above show is large and aligned enough
+    let hdc302x = unsafe { &mut *dev.driver.as_mut_ptr().cast::<FfiDriver>() };
+    op(hdc302x).map_err(|error| -(error.code() as i32))
+}
+
+/// Fill in `dev` and check the device answers with the TI manufacturer ID, waiting for it to start
+/// up first.  `addr` is the 7-bit address, 0x44 to 0x47.
+///
+/// # Safety
+///
+/// `dev` must be null or point to writable storage for an `Hdc302xFfi`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdc302x_init(dev: *mut Hdc302xFfi, bus: Hdc302xFfiBus, addr: u8) -> i32 {
+    let addr = match addr {
+        0x44 => I2cAddr::Addr00,
+        0x45 => I2cAddr::Addr01,
+        0x46 => I2cAddr::Addr10,
+        0x47 => I2cAddr::Addr11,
+        _ => return HDC302X_FFI_INVALID_ARGUMENT,
+    };
+    // SAFETY: the C caller passes null or writable storage for the handle
+    let Some(handle) = (unsafe { dev.as_mut() }) else {
+        return HDC302X_FFI_INVALID_ARGUMENT;
+    };
+    let driver = Hdc302x::new(FfiI2c(bus), FfiDelay(bus), addr);
+    // SAFETY: the storage is large and aligned enough for the driver (asserted above)
+    unsafe { handle.driver.as_mut_ptr().cast::<FfiDriver>().write(driver) };
+    handle.magic = HANDLE_MAGIC;
+    match with_driver(dev, |hdc302x| hdc302x.read_manufacturer_id()) {
+        Ok(ManufacturerId::TexasInstruments) => 0,
+        Ok(_) => -(Error::<FfiBusError>::InvalidInputData.code() as i32),
+        Err(status) => status,
+    }
+}
+
+/// Take a lowest-noise one-shot sample
+///
+/// # Safety
+///
+/// `dev` must be null or a handle initialized by [`hdc302x_init()`]. Output pointers must be null or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdc302x_measure(dev: *mut Hdc302xFfi, centigrade: *mut f32, humidity_percent: *mut f32) -> i32 {
+    if centigrade.is_null() || humidity_percent.is_null() {
+        return HDC302X_FFI_INVALID_ARGUMENT;
+    }
+    match with_driver(dev, |hdc302x| hdc302x.one_shot(LowPowerMode::lowest_noise())) {
+        Ok(RawDatum::TempAndRelHumid(sample)) => {
+            // SAFETY: checked for null; the C caller provides writable outputs
+            unsafe {
+                centigrade.write(sample.centigrade());
+                humidity_percent.write(sample.humidity_percent());
+            }
+            0
+        }
+        Ok(_) => -(Error::<FfiBusError>::InvalidInputData.code() as i32),
+        Err(status) => status,
+    }
+}
+
+/// Read the raw status register without clearing it
+///
+/// # Safety
+///
+/// `dev` must be null or a handle initialized by [`hdc302x_init()`]. Output pointers must be null or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdc302x_read_status(dev: *mut Hdc302xFfi, status: *mut u16) -> i32 {
+    if status.is_null() {
+        return HDC302X_FFI_INVALID_ARGUMENT;
+    }
+    match with_driver(dev, |hdc302x| hdc302x.read_status(false)) {
+        Ok(bits) => {
+            // SAFETY: checked for null; the C caller provides a writable output
+            unsafe { status.write(bits.raw()) };
+            0
+        }
+        Err(status) => status,
+    }
+}
+
+/// Program all four alert thresholds (°C, %RH), validated like [`AlertLimits::new()`]
+///
+/// # Safety
+///
+/// `dev` must be null or a handle initialized by [`hdc302x_init()`].
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn hdc302x_set_alerts(
+    dev: *mut Hdc302xFfi,
+    set_low_centigrade: f32,
+    set_low_humidity_percent: f32,
+    set_high_centigrade: f32,
+    set_high_humidity_percent: f32,
+    clear_low_centigrade: f32,
+    clear_low_humidity_percent: f32,
+    clear_high_centigrade: f32,
+    clear_high_humidity_percent: f32,
+) -> i32 {
+    let Ok(limits) = AlertLimits::new(
+        (set_low_centigrade, set_low_humidity_percent),
+        (set_high_centigrade, set_high_humidity_percent),
+        (clear_low_centigrade, clear_low_humidity_percent),
+        (clear_high_centigrade, clear_high_humidity_percent),
+    ) else {
+        return HDC302X_FFI_INVALID_ARGUMENT;
+    };
+    match with_driver(dev, |hdc302x| hdc302x.write_alert_limits(&limits)) {
+        Ok(()) => 0,
+        Err(status) => status,
+    }
+}
@@ -0,0 +1,42 @@
+use crate::types::*;
+
+use fixed::types::I16F16;
+
+// Every conversion below is exact: the datasheet formulas divide by 2^16, which is the scale of
+// the fraction, so `offset * 2^16 + span * raw` is the I16F16 bit pattern of the result.
+
+/// Temperature in degrees centigrade from a raw sample, without rounding error
+pub const fn centigrade_fixed(raw: u16) -> I16F16 {
+    I16F16::from_bits(-45 * 65_536 + 175 * raw as i32)
+}
+
+/// Temperature in degrees fahrenheit from a raw sample, without rounding error
+pub const fn fahrenheit_fixed(raw: u16) -> I16F16 {
+    I16F16::from_bits(-49 * 65_536 + 315 * raw as i32)
+}
+
+/// Relative humidity in percent from a raw sample, without rounding error
+pub const fn humidity_percent_fixed(raw: u16) -> I16F16 {
+    I16F16::from_bits(100 * raw as i32)
+}
+
+/// Temp and relative humidity from the device after conversion to Q16.16 fixed point, for control
+/// loops without floating point
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FixedTempAndRelHumid {
+    /// degrees centigrade
+    pub centigrade: I16F16,
+    /// degrees fahrenheit
+    pub fahrenheit: I16F16,
+    /// relative humidity in percent
+    pub humidity_percent: I16F16,
+}
+impl From<&RawTempAndRelHumid> for FixedTempAndRelHumid {
+    fn from(raw: &RawTempAndRelHumid) -> Self {
+        Self {
+            centigrade: centigrade_fixed(raw.temperature),
+            fahrenheit: fahrenheit_fixed(raw.temperature),
+            humidity_percent: humidity_percent_fixed(raw.humidity),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Transcendental functions for the psychrometric calculations: accurate ones from `libm` with
+// the `math` feature, else fast approximations from `micromath`
+
+#[cfg(feature = "math")]
+pub(crate) use libm::{expf, fabsf, logf, sqrtf};
+
+#[cfg(not(feature = "math"))]
+use micromath::F32Ext;
+
+#[cfg(not(feature = "math"))]
+pub(crate) fn expf(x: f32) -> f32 {
+    F32Ext::exp(x)
+}
+
+#[cfg(not(feature = "math"))]
+pub(crate) fn fabsf(x: f32) -> f32 {
+    F32Ext::abs(x)
+}
+
+#[cfg(not(feature = "math"))]
+pub(crate) fn logf(x: f32) -> f32 {
+    // micromath's ln is accurate from 1 up but not below; relative humidity is always below
+    if x < 1.0 { -F32Ext::ln((1.0 / x).min(f32::MAX)) } else { F32Ext::ln(x) }
+}
+
+#[cfg(not(feature = "math"))]
+pub(crate) fn sqrtf(x: f32) -> f32 {
+    // one Newton step takes micromath's estimate from percent to permille accuracy
+    let estimate = F32Ext::sqrt(x);
+    if estimate > 0.0 { 0.5 * (estimate + x / estimate) } else { estimate }
+}
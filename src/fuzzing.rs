@@ -0,0 +1,43 @@
+//! Entry points for the cargo-fuzz targets in `fuzz/`.  Not part of the public API.
+
+use crate::device_impl::decode_words;
+use crate::quality::CrcPolicy;
+use crate::types::*;
+use crate::Error;
+
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+
+/// Sink that discards everything written to it, used to exercise `Display` impls
+struct Discard;
+impl Write for Discard {
+    fn write_str(&mut self, _s: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Decode `words.len()` CRC-protected words from a raw response frame
+pub fn decode_response(frame: &[u8], words: &mut [u16]) -> Result<(), Error<Infallible>> {
+    decode_words(frame, words, CrcPolicy::Fail, "fuzz").map(|_| ())
+}
+
+/// Run a raw frame through every response decoder the driver uses
+pub fn decode_all(frame: &[u8]) {
+    let mut words = [0u16; 3];
+    for num_words in 1..=words.len() {
+        for policy in [CrcPolicy::Ignore, CrcPolicy::WarnOnly, CrcPolicy::Fail] {
+            let _ = decode_words::<Infallible>(frame, &mut words[..num_words], policy, "fuzz");
+        }
+    }
+
+    let status = StatusBits::from(words[0]);
+    let _ = write!(Discard, "{}", status);
+    assert_eq!(status.raw(), words[0]);
+
+    let manufacturer_id = ManufacturerId::from(words[0]);
+    let _ = write!(Discard, "{}", manufacturer_id);
+    assert_eq!(u16::from(manufacturer_id), words[0]);
+
+    let serial_number = SerialNumber::from_words(words);
+    let _ = write!(Discard, "{}", serial_number);
+}
@@ -1,4 +1,10 @@
+#[cfg(feature="defmt")]
+use defmt::Format;
+#[cfg(feature = "serde")]
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
 /// I2C device address options, which are selected via the ADDR1 and ADDR pins.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum I2cAddr {
     /// ADDR1 = 0, ADDR0 = 0
@@ -11,6 +17,38 @@ pub enum I2cAddr {
     Addr11 = 0x47,
 }
 impl I2cAddr {
+    /// All four addresses, in strapping order
+    pub const ALL: [Self; 4] = [Self::Addr00, Self::Addr01, Self::Addr10, Self::Addr11];
+
+    /// Select the address matching the logic levels strapped on the ADDR1 and ADDR pins
+    pub fn from_strapping(addr1_high: bool, addr0_high: bool) -> Self {
+        match (addr1_high, addr0_high) {
+            (false, false) => Self::Addr00,
+            (false, true) => Self::Addr01,
+            (true, false) => Self::Addr10,
+            (true, true) => Self::Addr11,
+        }
+    }
+
+    /// Read the ADDR1 and ADDR strapping pins and select the matching address
+    pub fn from_strapping_pins<Addr1, Addr0, PinE>(addr1: &mut Addr1, addr0: &mut Addr0) -> Result<Self, PinE>
+    where
+        Addr1: embedded_hal::digital::InputPin<Error = PinE>,
+        Addr0: embedded_hal::digital::InputPin<Error = PinE>,
+    {
+        Ok(Self::from_strapping(addr1.is_high()?, addr0.is_high()?))
+    }
+
+    /// Default instance label, see [`Hdc302x::set_label()`](crate::Hdc302x::set_label)
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Self::Addr00 => "0x44",
+            Self::Addr01 => "0x45",
+            Self::Addr10 => "0x46",
+            Self::Addr11 => "0x47",
+        }
+    }
+
     pub(crate) fn as_u8(&self) -> u8 {
         match self {
             Self::Addr00 => 0x44,
@@ -21,7 +59,36 @@ impl I2cAddr {
     }
 }
 
+/// Addresses an HDC302x answered at, from `Hdc302x::detect()`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DetectedAddrs {
+    found: [bool; 4],
+}
+impl DetectedAddrs {
+    pub(crate) fn insert(&mut self, i2c_addr: I2cAddr) {
+        if let Some(i) = I2cAddr::ALL.iter().position(|addr| *addr == i2c_addr) {
+            self.found[i] = true;
+        }
+    }
+
+    /// true if an HDC302x answered at `i2c_addr`
+    pub fn contains(&self, i2c_addr: I2cAddr) -> bool {
+        I2cAddr::ALL.iter().zip(self.found).any(|(addr, found)| found && *addr == i2c_addr)
+    }
+
+    /// Addresses an HDC302x answered at, in strapping order
+    pub fn iter(&self) -> impl Iterator<Item = I2cAddr> + '_ {
+        I2cAddr::ALL.into_iter().zip(self.found).filter_map(|(addr, found)| found.then_some(addr))
+    }
+
+    /// Number of HDC302x found
+    pub fn count(&self) -> usize {
+        self.found.iter().filter(|found| **found).count()
+    }
+}
+
 /// Sample rate options, covering both the one-shot and auto modes.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SampleRate {
     /// initiate and read a single measurement, returning device back to sleep afterward
@@ -39,6 +106,7 @@ pub enum SampleRate {
 }
 
 /// Low power mode options, which control the trade-off between power consumption, measurement noise, and sample latency.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum LowPowerMode {
     /// lowest noise
@@ -67,9 +135,20 @@ impl LowPowerMode {
     pub fn lowest_power() -> Self {
         Self::LPM3
     }
+    /// Duration of a one-shot temperature and relative humidity conversion in this mode, in
+    /// microseconds, per the datasheet
+    pub const fn conversion_time_us(self) -> u32 {
+        match self {
+            Self::LPM0 => 12_500,
+            Self::LPM1 => 7_500,
+            Self::LPM2 => 5_000,
+            Self::LPM3 => 3_700,
+        }
+    }
 }
 
 /// Options for what to read from the device when in auto mode.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum AutoReadTarget {
     /// most recently sampled temperature and relative humidity
@@ -85,6 +164,8 @@ pub enum AutoReadTarget {
 }
 
 /// Options for the on-device heater.  The datasheet claims this may be useful to drive off condensation.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum HeaterLevel{
     /// heater off (post-reset default)
@@ -95,15 +176,92 @@ pub enum HeaterLevel{
     On50Percent,
     /// heater on at 100% power
     On100Percent,
+    /// heater on with a custom selection of heater elements
+    Custom(HeaterPower),
 }
 impl HeaterLevel {
     pub(crate) fn setting(&self) -> Option<u16> {
         match self {
             HeaterLevel::Off => None,
-            HeaterLevel::On25Percent => Some(0x9f),
-            HeaterLevel::On50Percent => Some(0x3ff),
-            HeaterLevel::On100Percent => Some(0x3FFF),
+            HeaterLevel::On25Percent => Some(HeaterPower::QUARTER.bits()),
+            HeaterLevel::On50Percent => Some(HeaterPower::HALF.bits()),
+            HeaterLevel::On100Percent => Some(HeaterPower::FULL.bits()),
+            HeaterLevel::Custom(power) => Some(power.bits()),
+        }
+    }
+
+    /// Level for an element selection, a preset level if one matches
+    pub(crate) fn from_power(power: HeaterPower) -> Self {
+        match power {
+            HeaterPower::QUARTER => Self::On25Percent,
+            HeaterPower::HALF => Self::On50Percent,
+            HeaterPower::FULL => Self::On100Percent,
+            power => Self::Custom(power),
+        }
+    }
+}
+
+/// Heater power as the heater configuration register value: each of the 14 low bits enables one
+/// heater element, so the register selects from 14 elements rather than a continuous power
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeaterPower(u16);
+impl HeaterPower {
+    /// Register bits that select heater elements
+    pub const MASK: u16 = 0x3fff;
+    /// Number of heater elements
+    pub const STEPS: u8 = 14;
+    /// [`HeaterLevel::On25Percent`]
+    pub const QUARTER: Self = Self(0x9f);
+    /// [`HeaterLevel::On50Percent`]
+    pub const HALF: Self = Self(0x3ff);
+    /// [`HeaterLevel::On100Percent`]
+    pub const FULL: Self = Self(0x3fff);
+
+    /// Heater power from a register value; `None` if no element or a bit outside
+    /// [`HeaterPower::MASK`] is set
+    pub const fn from_bits(bits: u16) -> Option<Self> {
+        if bits == 0 || bits & !Self::MASK != 0 {
+            return None;
+        }
+        Some(Self(bits))
+    }
+
+    /// Heater power with the `steps` lowest elements on, from 1 to [`HeaterPower::STEPS`]; `None`
+    /// outside that range
+    pub const fn from_steps(steps: u8) -> Option<Self> {
+        if steps == 0 || steps > Self::STEPS {
+            return None;
         }
+        Some(Self((1 << steps) - 1))
+    }
+
+    /// Heater configuration register value
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Number of heater elements on
+    pub const fn steps(&self) -> u8 {
+        self.0.count_ones() as u8
+    }
+
+    /// true if every element on in `self` is also on in `other`, so `self` draws at most as much
+    /// power
+    pub const fn is_within(&self, other: HeaterPower) -> bool {
+        self.0 & !other.0 == 0
+    }
+}
+#[cfg(feature = "serde")]
+impl Serialize for HeaterPower {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.0)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for HeaterPower {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_bits(u16::deserialize(deserializer)?).ok_or_else(|| D::Error::custom("no heater element selected, or a bit outside HeaterPower::MASK"))
     }
 }
 
@@ -136,8 +294,7 @@ pub(crate) fn start_sampling_command(sample_rate: SampleRate, low_power_mode: Lo
     }
 }
 
-// TODO: disable allow(unusued)
-#[allow(unused)]
+/// Data word for [`Command::ResetState`] selecting the power-on and reset measurement mode
 pub(crate) fn reset_state_value(sample_rate: SampleRate, low_power_mode: LowPowerMode) -> u16 {
     match (sample_rate, low_power_mode) {
         (SampleRate::OneShot, _) => 0x0081,
@@ -164,6 +321,23 @@ pub(crate) fn reset_state_value(sample_rate: SampleRate, low_power_mode: LowPowe
     }
 }
 
+/// Measurement mode selected by a [`Command::ResetState`] data word, `None` if not a known word
+pub(crate) fn decode_reset_state(word: u16) -> Option<(SampleRate, LowPowerMode)> {
+    const RATES: [SampleRate; 6] = [
+        SampleRate::OneShot,
+        SampleRate::Auto500mHz,
+        SampleRate::Auto1Hz,
+        SampleRate::Auto2Hz,
+        SampleRate::Auto4Hz,
+        SampleRate::Auto10Hz,
+    ];
+    const MODES: [LowPowerMode; 4] = [LowPowerMode::LPM0, LowPowerMode::LPM1, LowPowerMode::LPM2, LowPowerMode::LPM3];
+    RATES
+        .into_iter()
+        .flat_map(|rate| MODES.into_iter().map(move |mode| (rate, mode)))
+        .find(|(rate, mode)| reset_state_value(*rate, *mode) == word)
+}
+
 // TODO: disable allow(unusued)
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum Command {
@@ -174,24 +348,15 @@ pub(crate) enum Command {
     AutoReadMinRelHumid,
     AutoReadMaxRelHumid,
     
-    #[allow(unused)]
     WriteSetLowAlert,
-    #[allow(unused)]
     WriteSetHighAlert,
-    #[allow(unused)]
     WriteClearLowAlert,
-    #[allow(unused)]
     WriteClearHighAlert,
-    #[allow(unused)]
     AlertToNV,
 
-    #[allow(unused)]
     ReadSetLowAlert,
-    #[allow(unused)]
     ReadSetHighAlert,
-    #[allow(unused)]
     ReadClearLowAlert,
-    #[allow(unused)]
     ReadClearHighAlert,
 
     HeaterEnable,
@@ -201,7 +366,6 @@ pub(crate) enum Command {
     StatusRead,
     StatusClear,
 
-    #[allow(unused)]
     NVOffset,
 
     SoftReset,
@@ -212,11 +376,40 @@ pub(crate) enum Command {
 
     ManufacturerID,
 
-    #[allow(unused)]
     ResetState,
 }
 impl Command {
-    pub(crate) fn to_be_bytes(&self) -> [u8; 2] {
+    pub(crate) const ALL: [Command; 27] = [
+        Self::AutoExit,
+        Self::AutoReadTempAndRelHumid,
+        Self::AutoReadMinTemp,
+        Self::AutoReadMaxTemp,
+        Self::AutoReadMinRelHumid,
+        Self::AutoReadMaxRelHumid,
+        Self::WriteSetLowAlert,
+        Self::WriteSetHighAlert,
+        Self::WriteClearLowAlert,
+        Self::WriteClearHighAlert,
+        Self::AlertToNV,
+        Self::ReadSetLowAlert,
+        Self::ReadSetHighAlert,
+        Self::ReadClearLowAlert,
+        Self::ReadClearHighAlert,
+        Self::HeaterEnable,
+        Self::HeaterDisable,
+        Self::HeaterConfig,
+        Self::StatusRead,
+        Self::StatusClear,
+        Self::NVOffset,
+        Self::SoftReset,
+        Self::SerialID54,
+        Self::SerialID32,
+        Self::SerialID10,
+        Self::ManufacturerID,
+        Self::ResetState,
+    ];
+
+    pub(crate) fn as_u16(self) -> u16 {
         match self {
             Self::AutoExit => 0x3093_u16,
             Self::AutoReadTempAndRelHumid => 0xe000_u16,
@@ -254,7 +447,53 @@ impl Command {
             Self::ManufacturerID => 0x3781_u16,
 
             Self::ResetState => 0x61bb_u16,
-        }.to_be_bytes()
+        }
+    }
+
+    pub(crate) fn to_be_bytes(self) -> [u8; 2] {
+        self.as_u16().to_be_bytes()
+    }
+
+    pub(crate) fn from_u16(value: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|command| command.as_u16() == value)
+    }
+
+    /// Whether the device keeps sampling undisturbed when it receives this command in auto mode:
+    /// result, status, threshold and ID reads, status clear and heater control.  Mode changes
+    /// (auto exit, soft reset) are deliberate and also allowed.
+    pub(crate) fn is_safe_during_auto(self) -> bool {
+        matches!(
+            self,
+            Self::AutoExit
+                | Self::AutoReadTempAndRelHumid
+                | Self::AutoReadMinTemp
+                | Self::AutoReadMaxTemp
+                | Self::AutoReadMinRelHumid
+                | Self::AutoReadMaxRelHumid
+                | Self::ReadSetLowAlert
+                | Self::ReadSetHighAlert
+                | Self::ReadClearLowAlert
+                | Self::ReadClearHighAlert
+                | Self::HeaterEnable
+                | Self::HeaterDisable
+                | Self::HeaterConfig
+                | Self::StatusRead
+                | Self::StatusClear
+                | Self::SoftReset
+                | Self::SerialID54
+                | Self::SerialID32
+                | Self::SerialID10
+                | Self::ManufacturerID
+        )
+    }
+}
+
+/// Whether a raw command word can be sent in auto mode without disturbing sampling; restarting
+/// auto mode at another rate is a deliberate mode change and allowed, a one-shot trigger is not
+pub(crate) fn is_safe_during_auto(command: u16) -> bool {
+    match Command::from_u16(command) {
+        Some(command) => command.is_safe_during_auto(),
+        None => matches!(command >> 8, 0x20..=0x23 | 0x27),
     }
 }
 
@@ -280,14 +519,133 @@ pub(crate) const STATUS_FIELD_WIDTH_T_LOW_TRACKING_ALERT: usize = 1;
 pub(crate) const STATUS_FIELD_WIDTH_RESET_SINCE_CLEAR: usize = 1;
 pub(crate) const STATUS_FIELD_WIDTH_CHECKSUM_FAILURE: usize = 1;
 
+pub(crate) const STATUS_ALERT_MASK: u16 = (1 << STATUS_FIELD_LSBIT_AT_LEAST_ONE_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_RH_TRACKING_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_T_TRACKING_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_RH_HIGH_TRACKING_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_RH_LOW_TRACKING_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_T_HIGH_TRACKING_ALERT)
+    | (1 << STATUS_FIELD_LSBIT_T_LOW_TRACKING_ALERT);
+
+/// Relative humidity bits kept in an alert threshold register (the 7 MSBs)
+pub(crate) const ALERT_RH_MASK: u16 = 0xfe00;
+/// Right shift of the temperature MSBs kept in an alert threshold register (the 9 LSBs)
+pub(crate) const ALERT_T_SHIFT: u32 = 7;
+
+/// Highest I2C clock frequency the device supports (Fm+)
+pub const MAX_BUS_HZ: u32 = 1_000_000;
+
+/// I2C bus speed class, declared with [`Hdc302x::new_with_bus_hz()`](crate::Hdc302x::new_with_bus_hz)
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BusSpeed {
+    /// up to 100 kHz
+    Standard,
+    /// up to 400 kHz
+    Fast,
+    /// up to 1 MHz
+    FastPlus,
+}
+impl BusSpeed {
+    /// Classify a bus clock frequency; frequencies above [`MAX_BUS_HZ`] are out of spec and
+    /// classify as [`BusSpeed::FastPlus`]
+    pub fn from_hz(bus_hz: u32) -> Self {
+        match bus_hz {
+            0..=100_000 => Self::Standard,
+            100_001..=400_000 => Self::Fast,
+            _ => Self::FastPlus,
+        }
+    }
+
+    /// Extra settle time the driver inserts between writing a command and reading its response
+    pub fn settle_us(&self) -> u32 {
+        match self {
+            Self::Standard | Self::Fast => 0,
+            Self::FastPlus => 10,
+        }
+    }
+
+    /// Bus free time between transactions the I2C specification requires at this speed (t_BUF,
+    /// rounded up to whole microseconds); the default for
+    /// [`Hdc302x::set_min_transaction_gap_us()`](crate::Hdc302x::set_min_transaction_gap_us)
+    pub fn bus_free_us(&self) -> u32 {
+        match self {
+            Self::Standard => 5,
+            Self::Fast => 2,
+            Self::FastPlus => 1,
+        }
+    }
+}
+
+/// Datasheet maximum time from power-up or soft reset until the device accepts I2C commands
+pub const STARTUP_TIME_MS: u32 = 5;
+
+/// Default time a read is polled before the command is assumed lost; covers the longest one-shot
+/// conversion time
+pub const DEFAULT_READ_TIMEOUT_MS: u32 = 50;
+
+/// Time between the relative humidity samples `Hdc302x::dry_sensor()` takes while heating
+pub const DRYING_SAMPLE_INTERVAL_MS: u32 = 1_000;
+
+/// Datasheet maximum time for the device to program its non-volatile memory
+pub const NV_WRITE_TIME_MS: u32 = 77;
+
+/// Default number of non-volatile memory writes the driver allows per session, see
+/// [`Hdc302x::set_nv_write_limit()`](crate::Hdc302x::set_nv_write_limit)
+pub const NV_WRITE_SESSION_LIMIT: u32 = 8;
+
 pub(crate) const MANUFACTURER_ID_TEXAS_INSTRUMENTS: u16 = 0x3000u16;
 
-pub(crate) fn raw_temp_to_centigrade(raw: u16) -> f32 {
+pub(crate) const fn raw_temp_to_centigrade(raw: u16) -> f32 {
     -45.0 + 175.0 * (raw as f32) / 65536.0
 }
-pub(crate) fn raw_temp_to_fahrenheit(raw: u16) -> f32 {
+pub(crate) const fn raw_temp_to_fahrenheit(raw: u16) -> f32 {
     -49.0 + 315.0 * (raw as f32) / 65536.0
 }
-pub(crate) fn raw_rel_humid_to_percent(raw: u16) -> f32 {
+pub(crate) const fn raw_temp_to_kelvin(raw: u16) -> f32 {
+    228.15 + 175.0 * (raw as f32) / 65536.0
+}
+pub(crate) const fn raw_temp_to_millikelvin(raw: u16) -> i32 {
+    // exact in integers, rounded to the nearest millikelvin
+    228_150 + ((175_000 * raw as u64 + 32_768) / 65_536) as i32
+}
+// integer conversions round to nearest and never touch floating point, for targets without an FPU
+pub(crate) const fn raw_temp_to_centigrade_hundredths(raw: u16) -> i32 {
+    -4_500 + ((17_500 * raw as u32 + 32_768) / 65_536) as i32
+}
+pub(crate) const fn raw_temp_to_fahrenheit_hundredths(raw: u16) -> i32 {
+    -4_900 + ((31_500 * raw as u32 + 32_768) / 65_536) as i32
+}
+pub(crate) const fn raw_rel_humid_to_permille(raw: u16) -> u16 {
+    ((1_000 * raw as u32 + 32_768) / 65_536) as u16
+}
+pub(crate) const fn centigrade_to_millikelvin(centigrade: f32) -> i32 {
+    // float to int casts saturate; kelvin is never negative for a converted sample
+    ((centigrade + 273.15) * 1000.0 + 0.5) as i32
+}
+pub(crate) const fn raw_rel_humid_to_percent(raw: u16) -> f32 {
     100.0 * (raw as f32) / 65536.0
 }
+pub(crate) const fn centigrade_to_raw_temp(centigrade: f32) -> u16 {
+    // float to int casts saturate, so out-of-range inputs clamp to the raw range
+    ((centigrade + 45.0) * 65536.0 / 175.0 + 0.5) as u16
+}
+pub(crate) const fn percent_to_raw_rel_humid(percent: f32) -> u16 {
+    (percent * 65536.0 / 100.0 + 0.5) as u16
+}
+
+/// CRC-8 the device expects after each data word written to it (polynomial 0x31, init 0xff)
+pub(crate) const fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xffu8;
+    let mut ii = 0;
+    while ii < data.len() {
+        crc ^= data[ii];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x31 } else { crc << 1 };
+            bit += 1;
+        }
+        ii += 1;
+    }
+    crc
+}
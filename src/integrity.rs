@@ -0,0 +1,98 @@
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Expectations checked by the periodic integrity check
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IntegrityLimits {
+    /// serial number the device is expected to report, if known
+    pub expected_serial: Option<SerialNumber>,
+    /// lowest plausible temperature in degrees centigrade
+    pub min_centigrade: f32,
+    /// highest plausible temperature in degrees centigrade
+    pub max_centigrade: f32,
+    /// lowest plausible relative humidity in percent
+    pub min_humidity_percent: f32,
+    /// highest plausible relative humidity in percent
+    pub max_humidity_percent: f32,
+    /// highest acceptable number of CRC mismatches since the counter was last reset
+    pub max_crc_errors: u32,
+    /// the alert thresholds are programmed as a [`DriftWatch`](crate::DriftWatch), so a relative
+    /// humidity tracking alert means the RH offset drifted and needs recalibration
+    pub rh_alert_is_drift: bool,
+}
+impl Default for IntegrityLimits {
+    /// The device's specified operating range, no expected serial number and no CRC errors
+    fn default() -> Self {
+        Self {
+            expected_serial: None,
+            min_centigrade: -40.0,
+            max_centigrade: 125.0,
+            min_humidity_percent: 0.0,
+            max_humidity_percent: 100.0,
+            max_crc_errors: 0,
+            rh_alert_is_drift: false,
+        }
+    }
+}
+
+/// Outcome of the periodic integrity check
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IntegrityReport {
+    /// the serial number differs from the expected one
+    pub serial_changed: bool,
+    /// the device flagged a checksum failure on data written to it
+    pub device_checksum_failure: bool,
+    /// the one-shot temperature was outside the plausible range
+    pub temperature_implausible: bool,
+    /// the one-shot relative humidity was outside the plausible range
+    pub humidity_implausible: bool,
+    /// the CRC mismatch counter exceeded its limit
+    pub crc_errors_exceeded: bool,
+    /// CRC mismatch counter at the time of the check
+    pub crc_errors: u32,
+    /// relative humidity drift detected, see [`IntegrityLimits::rh_alert_is_drift`]: program a new
+    /// RH offset or recondition the sensor
+    pub humidity_drift: bool,
+}
+impl IntegrityReport {
+    pub(crate) fn evaluate(
+        limits: &IntegrityLimits,
+        serial: SerialNumber,
+        status: StatusBits,
+        sample: &RawTempAndRelHumid,
+        crc_errors: u32,
+    ) -> Self {
+        let centigrade = sample.centigrade();
+        let humidity_percent = sample.humidity_percent();
+        Self {
+            serial_changed: limits.expected_serial.is_some_and(|expected| expected != serial),
+            device_checksum_failure: status.checksum_failure,
+            temperature_implausible: !(limits.min_centigrade..=limits.max_centigrade).contains(&centigrade),
+            humidity_implausible: !(limits.min_humidity_percent..=limits.max_humidity_percent).contains(&humidity_percent),
+            crc_errors_exceeded: crc_errors > limits.max_crc_errors,
+            crc_errors,
+            humidity_drift: limits.rh_alert_is_drift && (status.rh_high_tracking_alert || status.rh_low_tracking_alert),
+        }
+    }
+
+    /// true if every check passed
+    pub fn passed(&self) -> bool {
+        self.bits() == 0
+    }
+
+    /// Failed checks packed into one byte for telemetry: bit 0 serial changed, bit 1 device
+    /// checksum failure, bit 2 temperature implausible, bit 3 humidity implausible, bit 4 CRC
+    /// errors exceeded, bit 5 humidity drift
+    pub fn bits(&self) -> u8 {
+        (self.serial_changed as u8)
+            | (self.device_checksum_failure as u8) << 1
+            | (self.temperature_implausible as u8) << 2
+            | (self.humidity_implausible as u8) << 3
+            | (self.crc_errors_exceeded as u8) << 4
+            | (self.humidity_drift as u8) << 5
+    }
+}
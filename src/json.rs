@@ -0,0 +1,72 @@
+use crate::types::*;
+
+use core::fmt::{self, Write};
+
+/// Sample encoded as a small JSON object for HTTP or MQTT backends, without an allocator or
+/// serde.  Fields always appear in the same order and values are fixed-point with two decimals,
+/// so the same input always gives the same bytes:
+///
+/// ```text
+/// {"serial":"0123456789AB","timestamp_ms":1000,"centigrade":21.50,"humidity_percent":45.25}
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct JsonSample {
+    buf: [u8; JsonSample::MAX_LEN],
+    len: usize,
+}
+impl JsonSample {
+    /// Longest document [`JsonSample::encode()`] produces, in bytes
+    pub const MAX_LEN: usize = "{\"serial\":\"".len()
+        + 12
+        + "\",\"timestamp_ms\":".len()
+        + "18446744073709551615".len()
+        + ",\"centigrade\":".len()
+        + "-99999.99".len()
+        + ",\"humidity_percent\":".len()
+        + "-99999.99".len()
+        + "}".len();
+
+    /// Encode a sample with the device serial number and its acquisition time.  Values beyond
+    /// ±99999.99 are clamped.
+    pub fn encode(sample: &TempAndRelHumid, serial: SerialNumber, timestamp_ms: u64) -> Self {
+        let mut json = Self { buf: [0; Self::MAX_LEN], len: 0 };
+        // MAX_LEN covers the longest serial, timestamp and clamped values
+        let written = write!(json, "{{\"serial\":\"{}\",\"timestamp_ms\":{},\"centigrade\":", serial, timestamp_ms)
+            .and_then(|()| json.write_hundredths(sample.centigrade))
+            .and_then(|()| json.write_str(",\"humidity_percent\":"))
+            .and_then(|()| json.write_hundredths(sample.humidity_percent))
+            .and_then(|()| json.write_char('}'));
+        debug_assert!(written.is_ok(), "JsonSample::MAX_LEN is too short");
+        json
+    }
+
+    /// The encoded document
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// The encoded document as a string
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    fn write_hundredths(&mut self, value: f32) -> fmt::Result {
+        let hundredths = match value * 100.0 {
+            scaled if scaled.is_nan() => 0,
+            scaled if scaled < 0.0 => ((scaled - 0.5) as i32).max(-9_999_999),
+            scaled => ((scaled + 0.5) as i32).min(9_999_999),
+        };
+        let sign = if hundredths < 0 { "-" } else { "" };
+        let magnitude = hundredths.unsigned_abs();
+        write!(self, "{}{}.{:02}", sign, magnitude / 100, magnitude % 100)
+    }
+}
+impl Write for JsonSample {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let dest = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
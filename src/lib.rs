@@ -8,30 +8,171 @@
 //!
 //! This driver allows you to:
 //! - Start and read samples in both one-shot and auto (self-timed) mode.
+//! - Trigger a one-shot, sleep for the conversion time and fetch the result later.
+//! - Take one-shot samples with the lowest latency (exact conversion-time wait, single read).
 //! - Read last temperature and humidity values in auto mode.
 //! - Read minimum and maximum temperature and humidity values in auto mode.
 //! - Exit auto mode.
-//! - Enable/disable the heater, including 100%, 50%, and 25% settings.
+//! - Enable/disable the heater, including 100%, 50%, and 25% settings and custom selections of its 14 elements.
+//! - Measure the temperature and humidity change over a heater burst to verify the heater in
+//!   production tests or tune reconditioning.
+//! - Dry a wet sensor in one call, heating until the relative humidity drops below a target or
+//!   a timeout elapses.
+//! - Pulse the heater at a fixed duty cycle within a power budget, backing off when the
+//!   temperature rises too far.
+//! - Read back the heater configuration and enable bit to diagnose unexpected current draw.
+//! - Refuse or derate the heater when the supply voltage is low.
+//! - Alternate measurement windows and heater bursts for defrosting (e.g. cold-storage doors),
+//!   with a guaranteed number of valid heater-off samples per window.
 //! - Trigger a software reset.
+//! - Program and read back the measurement mode (sleep or auto) entered at power-up and after a
+//!   reset.
+//! - Detect a programmed power-up auto mode at start-up, so the tracked mode matches provisioned
+//!   units from the first command.
+//! - Wait out the device start-up time before the first command after power-up or reset.
 //! - Read the manufacturer ID.
 //! - Read the device serial number.
 //! - Read and optionally clear the device status bits.
-//! - blocking API support.
-//! - async API support.
-//!
-//! This driver does not yet support the following device features:
-//! - Alerts (read/write and non-volatile storage of setpoints).
-//! - Offset calibration (non-volatile storage of temperature and relative humidity offsets).
-//! - Configuration of post-reset state (default behavior after power-on and software reset).
-//! - Blocking API support.
+//! - Track the device mode at run time and refuse commands that are invalid in it.
+//! - Optionally refuse commands that would disturb auto-mode sampling (strict auto mode).
+//! - Track the device mode (sleep, auto, one-shot pending) in the type system, so mode errors fail
+//!   to compile.
+//! - Program and read back the alert set and clear (hysteresis) thresholds, optionally validated
+//!   as a consistent set or taken from presets (comfort zone, freezer, greenhouse).
+//! - Store the alert thresholds in non-volatile memory, with read-back verification.
+//! - Optionally confirm after each configuration write that the device accepted its checksum.
+//! - Guard non-volatile memory against accidental or repeated writes (explicit confirmation and a
+//!   per-session write limit).
+//! - Program and read back the relative humidity and temperature offsets in non-volatile memory
+//!   to correct drift.
+//! - Apply per-unit linear (slope and offset) corrections to samples in the driver.
+//! - Convert alert thresholds to and from the packed register format at compile time.
+//! - Wait on the ALERT pin and report which alert fired.
+//! - Decode active alerts against the programmed thresholds (which quantity, high or low).
+//! - Latch alert status bits across polls so short-lived alerts are not missed.
+//! - Clear alert bits automatically once delivered, or keep them until explicitly acknowledged.
+//! - Choose per driver instance whether CRC mismatches fail the read, are only logged and counted,
+//!   or are not checked.
+//! - Run a periodic integrity check (serial number, status, sample plausibility, CRC errors),
+//!   optionally step by step through a token that keeps other commands from interleaving.
+//! - Provision the device (alert thresholds, offsets, power-up mode) or dry a wet sensor step by
+//!   step through the same kind of token.
+//! - Cross-check samples against a second HDC302x or a reference sensor, with divergence statistics
+//!   and an event when they disagree beyond their combined accuracy.
+//! - Track humidity exposure and estimate RH drift to know when to recalibrate the offset.
+//! - Detect RH offset drift with the tracking alert around a known reference humidity and report
+//!   it from the integrity check.
+//! - Mirror the non-volatile device configuration into user storage and detect mismatches at boot.
+//! - List the non-volatile registers that drifted from a golden profile in a compact, serializable
+//!   diff for fleet management.
+//! - Capture the device configuration and re-apply it in one call after a reset, or automatically
+//!   when a reset is detected.
+//! - Select the I2C address from the board's ADDR strapping pins.
+//! - Declare the I2C bus frequency to get out-of-spec warnings and extra settle time at 1 MHz, and
+//!   enforce a minimum gap between transactions for I2C peripherals that issue them back to back.
+//! - Bound how long a disconnected or hung device can stall a command (read timeout).
+//! - Share the I2C bus with other devices through `embedded-hal-bus` or `embassy-embedded-hal`,
+//!   without holding it across delays.
+//! - Share the driver between threads on std targets.
+//! - Test application code on the host against a simulated device.
+//! - Call the driver from C firmware through a minimal C ABI.
+//! - Publish the latest sample to interrupt handlers or other cores without locking.
+//! - Feed samples into a bounded queue with a drop-oldest, drop-newest or blocking overflow policy.
+//! - Escalate persistent or repeated alerts in stages: log, notify a hook, run the heater and
+//!   request maintenance.
+//! - Probe an address for an HDC302x at bring-up, failing fast on wrong address straps.
+//! - Scan the bus for HDC302x at all four addresses.
+//! - Label each driver instance (e.g. "intake", "exhaust") to tell sensors apart in log output.
+//! - Manage up to four sensors on one bus, starting, reading and triggering all of them at once
+//!   with a result per sensor.
+//! - Run without a HAL delay by busy-waiting on a tick counter (e.g. SysTick in a superloop).
+//! - Pace sampling from an injectable clock, so host tests can fast-forward virtual time.
+//! - Read temperatures in Kelvin, or as integer millikelvin for protocols that transport them so.
+//! - Convert raw samples with integer math only (hundredths of a degree, tenths of a percent)
+//!   on targets without an FPU.
+//! - Convert raw samples exactly to Q16.16 fixed point for fixed-point control loops (`fixed`).
+//! - Read samples and program alert thresholds and offsets as dimension-checked `uom` quantities
+//!   (`uom`).
+//! - Compute the dew point and, below freezing, the frost point of a sample (Magnus formula).
+//! - Compute the absolute humidity of a sample in g/m³, optionally corrected for barometric
+//!   pressure.
+//! - Compute the mixing ratio and specific humidity of a sample in g/kg at a given station
+//!   pressure.
+//! - Compute the vapor pressure deficit (VPD) of a sample in kPa, e.g. for greenhouse control.
+//! - Compute the heat index (apparent temperature) of a sample, in °C and °F.
+//! - Compute the humidex (Canadian comfort index) of a sample.
+//! - Choose accurate (`libm`) or fast, smaller (`micromath`) math for these calculations.
+//! - Compute relative humidity alert thresholds that track a constant dew point band.
+//! - Assemble sample processing (median, EMA, time-weighted mean, dew point, thresholds) as a
+//!   composable pipeline.
+//! - Flag or discard the first samples after a device reset.
+//! - Return saturated readings as-is, clamped or flagged.
+//! - Flag samples taken with the heater on or re-measured after a CRC failure.
+//! - Flag auto-mode samples fetched after waking from deep sleep that were measured before it.
+//! - Emit samples as fixed-layout binary frames (e.g. for RTT) or fixed-format `defmt` records.
+//! - Read binary sample frames logged by older firmware versions, converted to the current
+//!   layout.
+//! - Log samples with their acquisition timestamp and sequence number through `defmt`.
+//! - Encode samples with serial number and timestamp as JSON without an allocator (`json`).
+//! - Serialize samples and configurations with `serde`, e.g. to forward readings or store
+//!   provisioning profiles as JSON or CBOR (`serde`).
+//! - Derive psychrometric values with an optional external reference temperature.
+//! - Show the sensor state (OK, alert, missing, recovering) on a status LED.
+//! - Observe driver state transitions (auto mode, heater, resets, recovery, non-volatile writes)
+//!   through a hook.
+//! - Import the common types and traits at once with `use hdc302x::prelude::*`.
+//! - Use the same API blocking or async: both are generated from one implementation, so every
+//!   method exists in both flavors (the async ones with an `_async` suffix).
 //!
 //! ## Features
 //!
 //! - 'async`: Enables async API.
+//! - `atomic`: Enables `SampleCell`, a lock-free latest-sample cell for interrupt handlers and
+//!   other cores (via `portable-atomic`).
 //! - `blocking`: Enables blocking API.
-//! - `crc`: Checks received CRC against computed CRC.
-//! - `defmt`: Enables logging using the `defmt` framework.
+//! - `crc`: Checks received CRC against computed CRC, as the driver's `CrcPolicy` says.
+//! - `defmt`: Enables logging using the `defmt` framework, and `defmt::Format` for the public
+//!   types; converted samples print with units (e.g. `23.4 °C 45.1 %RH`), raw ones as raw words.
+//! - `ffi`: Enables a minimal C ABI (init, measure, read status, set alerts) in the `ffi` module
+//!   (requires `blocking`).
+//! - `fixed`: Enables `FixedTempAndRelHumid` and exact conversions of raw samples to Q16.16 fixed
+//!   point (via `fixed`).
+//! - `heapless`: Enables `SampleBridge`, which feeds samples into a bounded lock-free queue with
+//!   an explicit overflow policy.
+//! - `json`: Enables `JsonSample`, a deterministic fixed-size JSON encoding of a sample for
+//!   HTTP/MQTT backends, without an allocator.
 //! - `log`: Enables logging using the `log` framework.
+//! - `math`: Enables calculations that need transcendental functions (via `libm`), such as the
+//!   dew and frost points, absolute humidity, mixing ratio, vapor pressure deficit, the heat
+//!   index, humidex and the dew point band alert controller.
+//! - `micromath`: Enables the same calculations as `math` with fast approximations (via
+//!   `micromath`) for smaller flash, to within a few tenths of a degree or percent; `math` takes
+//!   precedence when both are enabled.
+//! - `serde`: Enables `Serialize`/`Deserialize` for samples, status bits, serial numbers, alert
+//!   limits, offsets and the measurement and stored configuration types.
+//! - `sim`: Enables `Hdc302xSim`, a simulated device on a simulated I2C bus for host tests.
+//! - `soak`: Enables the randomized soak test against `Hdc302xSim` (`tests/soak.rs`, requires
+//!   `std` and `blocking`).
+//! - `std`: Enables `SyncHdc302x`, a thread-safe handle with an optional background sampling
+//!   thread (requires `blocking`).
+//! - `uom`: Enables `uom` quantities for the temperature and relative humidity of samples, alert
+//!   thresholds and offsets.
+//!
+//! ## Sharing the bus
+//!
+//! The driver takes any `I2c` implementation by value, including `&mut I2C` and the bus-sharing
+//! devices of [`embedded-hal-bus`] (e.g. `RefCellDevice`, `CriticalSectionDevice`) and
+//! `embassy-embedded-hal` (`I2cDevice` on an async mutex).  Every command is a separate bus
+//! transaction and the bus is never held across a delay, such as start-up, conversion or
+//! non-volatile write waits, so other devices can use it meanwhile.
+//!
+//! [`embedded-hal-bus`]: https://github.com/rust-embedded/embedded-hal/tree/master/embedded-hal-bus
+//!
+//! ## Panics
+//!
+//! No driver method panics: invalid arguments and malformed or missing device responses are
+//! reported as errors (e.g. [`Error::InvalidInputData`], [`Error::ShortRead`]).  Only the
+//! `const` alert presets assert their consistency, at compile time.
 //!
 //! ## Supported devices: HDC3020, HDC3021, HDC3022, HDC3020-Q1, HDC3021-Q1, HDC3022-Q1
 //!
@@ -64,7 +205,7 @@
 //!
 //! ## Async Example:
 //!
-//! ```
+//! ```ignore
 //! use hdc302x::{
 //!     Datum,
 //!     Hdc302x,
@@ -106,7 +247,7 @@
 //! 
 //! ## Blocking Example:
 //!
-//! ```
+//! ```ignore
 //! use hdc302x::{
 //!     Datum,
 //!     Hdc302x,
@@ -148,16 +289,84 @@
 
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(not(any(feature = "async", feature = "blocking")))]
 compile_error!("At least one of \"async\" and \"blocking\" features must be enabled");
 
 #[cfg(all(feature = "defmt", feature = "log"))]
 compile_error!("Features \"defmt\" and \"log\" are mutually exclusive and cannot be enabled together");
 
+mod aging;
+mod alert;
+mod array;
+#[cfg(feature = "heapless")]
+mod bridge;
+mod config_diff;
+mod config_store;
+mod correction;
+mod cross_check;
+mod dedup;
+mod defrost;
 mod device_impl;
+mod duty_cycle;
+mod escalation;
+#[cfg(any(feature = "math", feature = "micromath"))]
+mod float;
+#[cfg(feature = "fixed")]
+mod fixed_point;
+#[cfg(any(feature = "math", feature = "micromath"))]
+mod dew_point_band;
 mod hw_def;
+mod integrity;
+#[cfg(feature = "json")]
+mod json;
+mod log_frame;
+mod observer;
+mod offset;
+mod operation;
+mod pacing;
+mod pipeline;
+mod quality;
+#[cfg(feature = "atomic")]
+mod sample_cell;
+#[cfg(feature = "sim")]
+mod sim;
+mod status_led;
+mod supply;
+mod tick_delay;
+#[cfg(any(feature = "math", feature = "micromath"))]
+mod psychrometrics;
+#[cfg(all(feature = "std", feature = "blocking"))]
+mod sync;
 mod types;
+mod typestate;
+#[cfg(feature = "uom")]
+mod units;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+pub mod prelude;
 
-pub use crate::{hw_def::*, types::*};
+pub use crate::{aging::*, alert::*, array::*, config_diff::*, config_store::*, correction::*, cross_check::*, dedup::*, defrost::*, duty_cycle::*, escalation::*, hw_def::*, integrity::*, log_frame::*, observer::*, offset::{Offsets, RH_OFFSET_STEP_PERCENT, TEMP_OFFSET_STEP_CENTIGRADE}, operation::*, pacing::*, pipeline::*, quality::*, status_led::*, supply::*, tick_delay::*, types::*, typestate::{mode, Hdc302xTyped}};
+#[cfg(feature = "heapless")]
+pub use crate::bridge::*;
+#[cfg(feature = "atomic")]
+pub use crate::sample_cell::*;
+#[cfg(feature = "sim")]
+pub use crate::sim::*;
+#[cfg(feature = "fixed")]
+pub use crate::fixed_point::*;
+#[cfg(feature = "json")]
+pub use crate::json::*;
+#[cfg(any(feature = "math", feature = "micromath"))]
+pub use crate::{dew_point_band::*, psychrometrics::{absolute_humidity_g_per_m3, dew_point_centigrade, frost_point_centigrade, heat_index, humidex, mixing_ratio_g_per_kg, specific_humidity_g_per_kg, vapor_pressure_deficit_kpa, Psychrometrics, ReferenceTemperature, SensorTemperature, TemperatureSource}};
+#[cfg(all(feature = "std", feature = "blocking"))]
+pub use crate::sync::*;
@@ -0,0 +1,250 @@
+use crate::pacing::*;
+use crate::quality::*;
+use crate::types::*;
+
+/// Fixed-layout binary record of one sample for high-rate debug captures, e.g. written verbatim
+/// to an RTT up channel or a UART.
+///
+/// Layout (multi-byte fields little-endian):
+///
+/// | offset | size | field |
+/// |--------|------|-------|
+/// | 0  | 1 | sync byte `0xA5` |
+/// | 1  | 1 | format version |
+/// | 2  | 4 | sequence number |
+/// | 6  | 2 | raw temperature |
+/// | 8  | 2 | raw relative humidity |
+/// | 10 | 2 | temperature in 0.01 °C (i16) |
+/// | 12 | 2 | relative humidity in 0.01 %RH |
+/// | 14 | 1 | flags: bit 0 post-reset, bit 1 stale, bit 2 saturated, bit 3 heater on, bit 4 CRC retried |
+/// | 15 | 1 | checksum: two's complement of the sum of bytes 0..15 |
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogFrame(pub [u8; LogFrame::LEN]);
+impl LogFrame {
+    /// Frame length in bytes
+    pub const LEN: usize = 16;
+    /// First byte of every frame
+    pub const SYNC: u8 = 0xa5;
+    /// Format version in byte 1.  Versions are never reused; a layout change gets the next one
+    /// and [`LogFrame::migrate()`] learns to convert the previous layout.
+    pub const VERSION: u8 = 1;
+    /// Oldest format version [`LogFrame::migrate()`] converts
+    pub const MIN_VERSION: u8 = 1;
+
+    /// Encode a sample with its sequence number
+    pub fn encode(seq: u32, sample: &RawTempAndRelHumid, flags: MeasurementFlags) -> Self {
+        let mut frame = [0u8; Self::LEN];
+        frame[0] = Self::SYNC;
+        frame[1] = Self::VERSION;
+        frame[2..6].copy_from_slice(&seq.to_le_bytes());
+        frame[6..8].copy_from_slice(&sample.temperature.to_le_bytes());
+        frame[8..10].copy_from_slice(&sample.humidity.to_le_bytes());
+        frame[10..12].copy_from_slice(&(round(sample.centigrade() * 100.0) as i16).to_le_bytes());
+        frame[12..14].copy_from_slice(&(round(sample.humidity_percent() * 100.0) as u16).to_le_bytes());
+        frame[14] = flags.post_reset as u8
+            | (flags.stale as u8) << 1
+            | (flags.saturated as u8) << 2
+            | (flags.heater_on as u8) << 3
+            | (flags.crc_retried as u8) << 4;
+        frame[15] = checksum(&frame[..15]);
+        Self(frame)
+    }
+
+    /// Encode a flagged sample with its sequence number
+    pub fn encode_flagged(seq: u32, sample: &FlaggedSample) -> Self {
+        Self::encode(seq, &sample.sample, sample.flags)
+    }
+
+    /// Decode a frame, or `None` if the sync byte, version or checksum is wrong
+    pub fn decode(bytes: &[u8; Self::LEN]) -> Option<(u32, RawTempAndRelHumid, MeasurementFlags)> {
+        if bytes[0] != Self::SYNC || bytes[1] != Self::VERSION || checksum(&bytes[..15]) != bytes[15] {
+            return None;
+        }
+        let seq = u32::from_le_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]);
+        let sample = RawTempAndRelHumid {
+            temperature: u16::from_le_bytes([bytes[6], bytes[7]]),
+            humidity: u16::from_le_bytes([bytes[8], bytes[9]]),
+        };
+        let flags = MeasurementFlags {
+            post_reset: bytes[14] & 1 != 0,
+            stale: bytes[14] & 2 != 0,
+            saturated: bytes[14] & 4 != 0,
+            heater_on: bytes[14] & 8 != 0,
+            crc_retried: bytes[14] & 16 != 0,
+        };
+        Some((seq, sample, flags))
+    }
+
+    /// Frame bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Length in bytes of a frame of format `version`, `None` for an unknown version
+    pub fn len_of_version(version: u8) -> Option<usize> {
+        match version {
+            1 => Some(16),
+            _ => None,
+        }
+    }
+
+    /// Convert a frame of any supported format version at the start of `bytes` into the current
+    /// layout, e.g. to read flash logged by older firmware.  Returns the frame and the number of
+    /// bytes it took in `bytes`.
+    pub fn migrate(bytes: &[u8]) -> Result<(Self, usize), MigrateError> {
+        let (&sync, &version) = match bytes {
+            [sync, version, ..] => (sync, version),
+            _ => return Err(MigrateError::Truncated),
+        };
+        if sync != Self::SYNC {
+            return Err(MigrateError::NotAFrame);
+        }
+        if version < Self::MIN_VERSION {
+            return Err(MigrateError::UnsupportedVersion(version));
+        }
+        let len = Self::len_of_version(version).ok_or(MigrateError::UnsupportedVersion(version))?;
+        let frame = bytes.get(..len).ok_or(MigrateError::Truncated)?;
+        if checksum(&frame[..len - 1]) != frame[len - 1] {
+            return Err(MigrateError::Corrupt);
+        }
+        // Version 1 is the current layout.  A layout change adds the conversion of the previous
+        // version here, before the copy.
+        let mut current = [0u8; Self::LEN];
+        current.copy_from_slice(frame);
+        Ok((Self(current), len))
+    }
+
+    /// Convert a log of back-to-back frames of any supported versions into the current layout,
+    /// passing each to `emit` in order.  Stops at the first frame that can not be converted and
+    /// returns the error with the number of bytes converted before it; returns the total length
+    /// if all of `bytes` was converted.
+    pub fn migrate_log(bytes: &[u8], mut emit: impl FnMut(Self)) -> Result<usize, (usize, MigrateError)> {
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let (frame, len) = Self::migrate(&bytes[offset..]).map_err(|error| (offset, error))?;
+            emit(frame);
+            offset += len;
+        }
+        Ok(offset)
+    }
+}
+
+/// Why [`LogFrame::migrate()`] could not convert a frame
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MigrateError {
+    /// the bytes end before the frame does
+    Truncated,
+    /// the sync byte is wrong, e.g. erased flash
+    NotAFrame,
+    /// the format version is newer than this driver or older than [`LogFrame::MIN_VERSION`]
+    UnsupportedVersion(u8),
+    /// the checksum does not match
+    Corrupt,
+}
+
+fn round(value: f32) -> f32 {
+    if value < 0.0 { value - 0.5 } else { value + 0.5 }
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)).wrapping_neg()
+}
+
+/// Sample with its sequence number, printed by `defmt` in one fixed, typed format:
+/// `hdc302x seq=<u32> t_raw=<u16> rh_raw=<u16> post_reset=<bool> stale=<bool> saturated=<bool>
+/// heater_on=<bool> crc_retried=<bool>`
+#[derive(Clone, Copy, Debug)]
+pub struct LogRecord {
+    /// sequence number
+    pub seq: u32,
+    /// the sample and its flags
+    pub sample: FlaggedSample,
+}
+#[cfg(feature = "defmt")]
+impl defmt::Format for LogRecord {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "hdc302x seq={=u32} t_raw={=u16} rh_raw={=u16} post_reset={=bool} stale={=bool} saturated={=bool} heater_on={=bool} crc_retried={=bool}",
+            self.seq,
+            self.sample.sample.temperature,
+            self.sample.sample.humidity,
+            self.sample.flags.post_reset,
+            self.sample.flags.stale,
+            self.sample.flags.saturated,
+            self.sample.flags.heater_on,
+            self.sample.flags.crc_retried,
+        )
+    }
+}
+
+/// Sample with its sequence number and acquisition timestamp, printed by `defmt` in one fixed,
+/// typed format:
+/// `hdc302x seq=<u32> ts_ms=<u64> t_raw=<u16> rh_raw=<u16> post_reset=<bool> stale=<bool>
+/// saturated=<bool> heater_on=<bool> crc_retried=<bool>`
+#[derive(Clone, Copy, Debug)]
+pub struct TimedLogRecord {
+    /// sequence number
+    pub seq: u32,
+    /// acquisition time from the [`Clock`] in milliseconds
+    pub timestamp_ms: u64,
+    /// the sample and its flags
+    pub sample: FlaggedSample,
+}
+#[cfg(feature = "defmt")]
+impl defmt::Format for TimedLogRecord {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "hdc302x seq={=u32} ts_ms={=u64} t_raw={=u16} rh_raw={=u16} post_reset={=bool} stale={=bool} saturated={=bool} heater_on={=bool} crc_retried={=bool}",
+            self.seq,
+            self.timestamp_ms,
+            self.sample.sample.temperature,
+            self.sample.sample.humidity,
+            self.sample.flags.post_reset,
+            self.sample.flags.stale,
+            self.sample.flags.saturated,
+            self.sample.flags.heater_on,
+            self.sample.flags.crc_retried,
+        )
+    }
+}
+
+/// Stamps samples with a sequence number and the [`Clock`] time and, with the `defmt` feature,
+/// logs each as a [`TimedLogRecord`] so off-target tooling can rebuild the acquisition timeline.
+///
+/// In async code, call [`SampleLog::record()`] right after the measurement future completes, or
+/// take the timestamp there and pass it to [`SampleLog::record_at()`], so later awaits do not
+/// skew it.
+#[derive(Clone, Copy, Debug)]
+pub struct SampleLog<C> {
+    clock: C,
+    seq: u32,
+}
+impl<C: Clock> SampleLog<C> {
+    /// Create a log starting at sequence number 0
+    pub fn new(clock: C) -> Self {
+        Self { clock, seq: 0 }
+    }
+
+    /// Stamp `sample` with the current time and the next sequence number, and log it
+    pub fn record(&mut self, sample: FlaggedSample) -> TimedLogRecord {
+        let timestamp_ms = self.clock.now_ms();
+        self.record_at(timestamp_ms, sample)
+    }
+
+    /// Stamp `sample` with `timestamp_ms` and the next sequence number, and log it
+    pub fn record_at(&mut self, timestamp_ms: u64, sample: FlaggedSample) -> TimedLogRecord {
+        let record = TimedLogRecord { seq: self.seq, timestamp_ms, sample };
+        self.seq = self.seq.wrapping_add(1);
+        #[cfg(feature = "defmt")]
+        defmt::info!("{}", record);
+        record
+    }
+
+    /// Sequence number the next record will get
+    pub fn next_seq(&self) -> u32 {
+        self.seq
+    }
+}
@@ -0,0 +1,45 @@
+use crate::hw_def::*;
+
+/// Driver state transition, reported to the observer set with
+/// [`Hdc302x::set_observer()`](crate::Hdc302x::set_observer)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StateEvent {
+    /// the device entered auto mode (sleep to auto)
+    AutoStarted {
+        /// auto mode sample rate
+        sample_rate: SampleRate,
+        /// auto mode low power mode
+        low_power_mode: LowPowerMode,
+    },
+    /// the device left auto mode (auto to sleep)
+    AutoStopped,
+    /// the heater setting changed
+    HeaterChanged {
+        /// previous setting, `None` if the driver did not know it
+        from: Option<HeaterLevel>,
+        /// new setting
+        to: HeaterLevel,
+    },
+    /// a software reset was issued
+    SoftwareReset,
+    /// a device reset was detected and the saved configuration is being applied again
+    Recovering,
+    /// the saved configuration was applied again after a device reset
+    Recovered,
+    /// a non-volatile memory write completed
+    Provisioned(NvItem),
+}
+
+/// Non-volatile setting written, see [`StateEvent::Provisioned`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NvItem {
+    /// the four alert thresholds
+    AlertThresholds,
+    /// the temperature and relative humidity offsets
+    Offsets,
+    /// the power-up measurement mode
+    PowerOnMode,
+}
+
+/// Hook called with every [`StateEvent`]
+pub type StateObserver = fn(&StateEvent);
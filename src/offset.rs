@@ -0,0 +1,76 @@
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Relative humidity offset step of the NV offset register in %RH (raw bit 7)
+pub const RH_OFFSET_STEP_PERCENT: f32 = 100.0 * 128.0 / 65536.0;
+/// Temperature offset step of the NV offset register in °C (raw bit 6)
+pub const TEMP_OFFSET_STEP_CENTIGRADE: f32 = 175.0 * 64.0 / 65536.0;
+
+/// Sign bit of an offset byte, set for a positive offset
+const OFFSET_SIGN: u8 = 0x80;
+/// Magnitude bits of an offset byte
+const OFFSET_MAGNITUDE: u8 = 0x7f;
+
+/// Encode a signed offset into a sign-magnitude offset byte, rounding to the nearest step, or
+/// `None` if it does not fit
+fn encode_offset(offset: f32, step: f32) -> Option<u8> {
+    let steps = offset.abs() / step + 0.5;
+    let in_range = steps < OFFSET_MAGNITUDE as f32 + 1.0;
+    if !in_range {
+        return None;
+    }
+    let magnitude = steps as u8;
+    Some(match offset >= 0.0 && magnitude != 0 {
+        true => OFFSET_SIGN | magnitude,
+        false => magnitude,
+    })
+}
+
+fn decode_offset(byte: u8, step: f32) -> f32 {
+    let magnitude = (byte & OFFSET_MAGNITUDE) as f32 * step;
+    if byte & OFFSET_SIGN != 0 { magnitude } else { -magnitude }
+}
+
+/// Encode a relative humidity offset in %RH into the RH byte (MSB) of the NV offset register
+pub(crate) fn encode_rh_offset(percent: f32) -> Option<u8> {
+    encode_offset(percent, RH_OFFSET_STEP_PERCENT)
+}
+
+/// Decode the RH byte of the NV offset register into %RH
+pub(crate) fn decode_rh_offset(byte: u8) -> f32 {
+    decode_offset(byte, RH_OFFSET_STEP_PERCENT)
+}
+
+/// Encode a temperature offset in °C into the temperature byte (LSB) of the NV offset register
+pub(crate) fn encode_temp_offset(centigrade: f32) -> Option<u8> {
+    encode_offset(centigrade, TEMP_OFFSET_STEP_CENTIGRADE)
+}
+
+/// Decode the temperature byte of the NV offset register into °C
+pub(crate) fn decode_temp_offset(byte: u8) -> f32 {
+    decode_offset(byte, TEMP_OFFSET_STEP_CENTIGRADE)
+}
+
+/// Temperature and relative humidity offsets programmed in the NV offset register
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Offsets {
+    /// temperature offset in °C
+    pub centigrade: f32,
+    /// relative humidity offset in %RH
+    pub humidity_percent: f32,
+}
+impl Offsets {
+    /// Decode a raw NV offset register word (RH byte first, then temperature)
+    pub fn from_word(word: u16) -> Self {
+        let [rh_byte, temp_byte] = word.to_be_bytes();
+        Self {
+            centigrade: decode_temp_offset(temp_byte),
+            humidity_percent: decode_rh_offset(rh_byte),
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use crate::alert::*;
+use crate::config_store::*;
+use crate::hw_def::*;
+use crate::integrity::*;
+use crate::offset::{encode_rh_offset, encode_temp_offset};
+use crate::types::*;
+
+use hdc302x_macros::blocking_and_async;
+
+#[cfg(feature = "defmt")]
+use defmt::warn;
+#[cfg(feature = "log")]
+use log::warn;
+#[cfg(not(any(feature = "defmt", feature = "log")))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Progress of a multi-step operation
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperationProgress<T> {
+    /// `done` of `total` steps have completed
+    InProgress {
+        /// completed steps
+        done: u8,
+        /// total steps
+        total: u8,
+    },
+    /// the operation finished with this result
+    Done(T),
+}
+
+/// Step-by-step integrity self-test, started with [`Hdc302x::begin_self_test()`].
+///
+/// The token borrows the driver mutably until it is dropped, so no other command can be
+/// interleaved with the sequence, not even through a shared wrapper (whose lock guard stays
+/// borrowed as well).  Each call to `step()` issues one part of the sequence, so the caller can
+/// yield, feed a watchdog or report progress in between.
+#[derive(Debug)]
+pub struct SelfTest<'d, I2C, Delay> {
+    driver: &'d mut Hdc302x<I2C, Delay>,
+    limits: IntegrityLimits,
+    serial: Option<SerialNumber>,
+    status: Option<StatusBits>,
+}
+impl<I2C, Delay> Hdc302x<I2C, Delay> {
+    /// Start a step-by-step integrity self-test, see [`SelfTest`]
+    pub fn begin_self_test(&mut self, limits: IntegrityLimits) -> SelfTest<'_, I2C, Delay> {
+        SelfTest {
+            driver: self,
+            limits,
+            serial: None,
+            status: None,
+        }
+    }
+}
+impl<I2C, Delay> SelfTest<'_, I2C, Delay> {
+    const STEPS: u8 = 3;
+
+    fn progress<T>(&self) -> OperationProgress<T> {
+        OperationProgress::InProgress {
+            done: self.serial.is_some() as u8 + self.status.is_some() as u8,
+            total: Self::STEPS,
+        }
+    }
+
+    fn check_heater<E>(&self) -> Result<(), Error<E>> {
+        if self.driver.heater_level.is_some_and(|level| level != HeaterLevel::Off) {
+            return Err(Error::HeaterActiveDuringMeasure);
+        }
+        Ok(())
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> SelfTest<'_, I2C, Delay>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Run the next step: read the serial number, read the status, then sample and evaluate
+    pub async fn step_async(&mut self) -> Result<OperationProgress<IntegrityReport>, Error<E>> {
+        self.check_heater()?;
+        let (Some(serial), Some(status)) = (self.serial, self.status) else {
+            if self.serial.is_none() {
+                self.serial = Some(self.driver.read_serial_number_async().await?);
+            } else {
+                self.status = Some(self.driver.read_status_async(false).await?);
+            }
+            return Ok(self.progress());
+        };
+        let sample = match self.driver.one_shot_async(LowPowerMode::lowest_noise()).await? {
+            RawDatum::TempAndRelHumid(sample) => sample,
+            _ => return Err(Error::InvalidInputData),
+        };
+        Ok(OperationProgress::Done(IntegrityReport::evaluate(&self.limits, serial, status, &sample, self.driver.crc_errors)))
+    }
+
+    /// Run the remaining steps
+    pub async fn finish_async(mut self) -> Result<IntegrityReport, Error<E>> {
+        loop {
+            if let OperationProgress::Done(report) = self.step_async().await? {
+                return Ok(report);
+            }
+        }
+    }
+}
+
+/// Non-volatile and volatile settings programmed by a [`Provisioning`] run, in this order; `None`
+/// (or `false`) skips the step
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProvisioningPlan {
+    /// alert thresholds to program
+    pub alert_limits: Option<AlertLimits>,
+    /// copy the alert thresholds into non-volatile memory
+    pub store_alert_thresholds: bool,
+    /// temperature offset in °C to program in non-volatile memory
+    pub temp_offset_centigrade: Option<f32>,
+    /// relative humidity offset in %RH to program in non-volatile memory
+    pub rh_offset_percent: Option<f32>,
+    /// auto mode to enter at power-up, programmed in non-volatile memory
+    pub power_on_mode: Option<(SampleRate, LowPowerMode)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ProvisioningStep {
+    AlertLimits,
+    StoreAlertThresholds,
+    TempOffset,
+    RhOffset,
+    PowerOnMode,
+    ReadBack,
+}
+
+/// Step-by-step provisioning, started with [`Hdc302x::begin_provisioning()`].
+///
+/// Like [`SelfTest`], the token borrows the driver mutably until it is dropped, so no other
+/// command can be interleaved between the steps of the plan.  Starting provisioning confirms
+/// each of the plan's non-volatile writes (see [`Hdc302x::confirm_nv_write()`]); the session
+/// limit still applies, and a failed step withdraws its confirmation.  The last step reads back the non-volatile configuration, e.g. to
+/// mirror it with [`Hdc302x::mirror_nv_config()`].
+#[derive(Debug)]
+pub struct Provisioning<'d, I2C, Delay> {
+    driver: &'d mut Hdc302x<I2C, Delay>,
+    plan: ProvisioningPlan,
+    done: u8,
+}
+impl<I2C: embedded_hal::i2c::ErrorType, Delay> Hdc302x<I2C, Delay> {
+    /// Start step-by-step provisioning with `plan`, see [`Provisioning`].  Returns
+    /// [`Error::InvalidInputData`] if an offset in the plan is out of range, before anything is
+    /// written.
+    pub fn begin_provisioning(&mut self, plan: ProvisioningPlan) -> Result<Provisioning<'_, I2C, Delay>, Error<I2C::Error>> {
+        if plan.temp_offset_centigrade.is_some_and(|centigrade| encode_temp_offset(centigrade).is_none())
+            || plan.rh_offset_percent.is_some_and(|percent| encode_rh_offset(percent).is_none())
+        {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(Provisioning { driver: self, plan, done: 0 })
+    }
+}
+impl<I2C, Delay> Provisioning<'_, I2C, Delay> {
+    fn steps(&self) -> impl Iterator<Item = ProvisioningStep> {
+        let plan = self.plan;
+        [
+            (plan.alert_limits.is_some(), ProvisioningStep::AlertLimits),
+            (plan.store_alert_thresholds, ProvisioningStep::StoreAlertThresholds),
+            (plan.temp_offset_centigrade.is_some(), ProvisioningStep::TempOffset),
+            (plan.rh_offset_percent.is_some(), ProvisioningStep::RhOffset),
+            (plan.power_on_mode.is_some(), ProvisioningStep::PowerOnMode),
+            (true, ProvisioningStep::ReadBack),
+        ]
+        .into_iter()
+        .filter_map(|(planned, step)| planned.then_some(step))
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> Provisioning<'_, I2C, Delay>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Run the next step of the plan
+    pub async fn step_async(&mut self) -> Result<OperationProgress<NvConfig>, Error<E>> {
+        let total = self.steps().count() as u8;
+        let step = self.steps().nth(usize::from(self.done)).unwrap_or(ProvisioningStep::ReadBack);
+        if let Err(error) = self.run_step_async(step).await {
+            // a step that failed before its write must not leave the confirmation armed
+            self.driver.nv_write_confirmed = false;
+            return Err(error);
+        }
+        if step == ProvisioningStep::ReadBack {
+            return Ok(OperationProgress::Done(self.driver.read_nv_config_async().await?));
+        }
+        self.done += 1;
+        Ok(OperationProgress::InProgress { done: self.done, total })
+    }
+
+    async fn run_step_async(&mut self, step: ProvisioningStep) -> Result<(), Error<E>> {
+        let plan = self.plan;
+        match step {
+            ProvisioningStep::AlertLimits => {
+                if let Some(limits) = plan.alert_limits {
+                    self.driver.write_alert_limits_async(&limits).await?;
+                }
+            }
+            ProvisioningStep::StoreAlertThresholds => {
+                self.driver.confirm_nv_write();
+                self.driver.store_alert_thresholds_nv_async().await?;
+            }
+            ProvisioningStep::TempOffset => {
+                if let Some(centigrade) = plan.temp_offset_centigrade {
+                    self.driver.confirm_nv_write();
+                    self.driver.write_temp_offset_async(centigrade).await?;
+                }
+            }
+            ProvisioningStep::RhOffset => {
+                if let Some(percent) = plan.rh_offset_percent {
+                    self.driver.confirm_nv_write();
+                    self.driver.write_rh_offset_async(percent).await?;
+                }
+            }
+            ProvisioningStep::PowerOnMode => {
+                if let Some((sample_rate, low_power_mode)) = plan.power_on_mode {
+                    self.driver.confirm_nv_write();
+                    self.driver.write_power_on_mode_async(sample_rate, low_power_mode).await?;
+                }
+            }
+            ProvisioningStep::ReadBack => {}
+        }
+        Ok(())
+    }
+
+    /// Run the remaining steps
+    pub async fn finish_async(mut self) -> Result<NvConfig, Error<E>> {
+        loop {
+            if let OperationProgress::Done(config) = self.step_async().await? {
+                return Ok(config);
+            }
+        }
+    }
+}
+
+/// Step-by-step condensation removal, started with [`Hdc302x::begin_recondition()`]; see
+/// [`Hdc302x::dry_sensor()`], which runs one to completion.
+///
+/// Like [`SelfTest`], the token borrows the driver mutably until it is dropped, so no other
+/// command can be interleaved while the heater runs.  The first step samples and, unless the
+/// sensor is already dry, turns the heater on; every further step waits
+/// [`DRYING_SAMPLE_INTERVAL_MS`] and samples again.  The heater is turned off when the run ends,
+/// also on error.  Dropping the token between steps leaves the heater on, so stop a run early with
+/// [`Recondition::abort()`].
+#[must_use = "dropping the token between steps leaves the heater on; finish or abort it"]
+#[derive(Debug)]
+pub struct Recondition<'d, I2C, Delay> {
+    driver: &'d mut Hdc302x<I2C, Delay>,
+    heater_level: HeaterLevel,
+    target_humidity_percent: f32,
+    max_duration_ms: u32,
+    low_power_mode: LowPowerMode,
+    report: Option<DryingReport>,
+}
+impl<I2C, Delay> Hdc302x<I2C, Delay> {
+    /// Start step-by-step condensation removal, see [`Recondition`]
+    pub fn begin_recondition(
+        &mut self,
+        heater_level: HeaterLevel,
+        target_humidity_percent: f32,
+        max_duration_ms: u32,
+        low_power_mode: LowPowerMode,
+    ) -> Recondition<'_, I2C, Delay> {
+        Recondition { driver: self, heater_level, target_humidity_percent, max_duration_ms, low_power_mode, report: None }
+    }
+}
+impl<I2C, Delay> Recondition<'_, I2C, Delay> {
+    fn progress<T>(&self, report: &DryingReport) -> OperationProgress<T> {
+        let steps = |ms: u32| 1 + ms.div_ceil(DRYING_SAMPLE_INTERVAL_MS);
+        OperationProgress::InProgress {
+            done: u8::try_from(steps(report.heated_ms)).unwrap_or(u8::MAX),
+            total: u8::try_from(steps(self.max_duration_ms)).unwrap_or(u8::MAX),
+        }
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> Recondition<'_, I2C, Delay>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Run the next step: sample and start heating, or wait, sample and check the target
+    pub async fn step_async(&mut self) -> Result<OperationProgress<DryingReport>, Error<E>> {
+        let Some(mut report) = self.report else {
+            let first = self.driver.sample_async(self.low_power_mode).await?;
+            let report = DryingReport { outcome: DryingOutcome::TimedOut, first, last: first, heated_ms: 0 };
+            if first.humidity_percent() < self.target_humidity_percent {
+                return Ok(OperationProgress::Done(DryingReport { outcome: DryingOutcome::AlreadyDry, ..report }));
+            }
+            self.driver.heater_async(self.heater_level).await?;
+            self.report = Some(report);
+            return Ok(self.progress(&report));
+        };
+        if report.heated_ms < self.max_duration_ms {
+            let interval_ms = DRYING_SAMPLE_INTERVAL_MS.min(self.max_duration_ms - report.heated_ms);
+            self.driver.delay.delay_ms(interval_ms).await;
+            report.heated_ms += interval_ms;
+            report.last = match self.driver.sample_async(self.low_power_mode).await {
+                Ok(sample) => sample,
+                Err(error) => {
+                    self.heater_off_after_error_async().await;
+                    return Err(error);
+                }
+            };
+            if report.last.humidity_percent() < self.target_humidity_percent {
+                report.outcome = DryingOutcome::Dried;
+            }
+        }
+        if report.outcome == DryingOutcome::Dried || report.heated_ms >= self.max_duration_ms {
+            self.driver.heater_async(HeaterLevel::Off).await?;
+            return Ok(OperationProgress::Done(report));
+        }
+        self.report = Some(report);
+        Ok(self.progress(&report))
+    }
+
+    /// Run the remaining steps
+    pub async fn finish_async(mut self) -> Result<DryingReport, Error<E>> {
+        loop {
+            if let OperationProgress::Done(report) = self.step_async().await? {
+                return Ok(report);
+            }
+        }
+    }
+
+    /// Stop the run early, turning the heater off if it was turned on
+    pub async fn abort_async(self) -> Result<(), Error<E>> {
+        if self.report.is_some() {
+            self.driver.heater_async(HeaterLevel::Off).await?;
+        }
+        Ok(())
+    }
+
+    // keep the error that ended the run, only logging a failure to turn the heater off
+    async fn heater_off_after_error_async(&mut self) {
+        if self.driver.heater_async(HeaterLevel::Off).await.is_err() {
+            warn!("hdc302x[{}]::Recondition::step_async(): turning the heater off failed", self.driver.label);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use core::cell::Cell;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Monotonic millisecond time source used to pace sampling.
+///
+/// On target this is typically backed by a hardware timer or the executor's clock; host tests can
+/// use [`ManualClock`] to fast-forward virtual time.
+pub trait Clock {
+    /// Milliseconds elapsed since an arbitrary, fixed origin
+    fn now_ms(&mut self) -> u64;
+}
+impl<C: Clock + ?Sized> Clock for &mut C {
+    fn now_ms(&mut self) -> u64 {
+        (**self).now_ms()
+    }
+}
+
+/// Virtual clock that only moves when told to, for deterministic tests of paced sampling
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ms: Cell<u64>,
+}
+impl ManualClock {
+    /// Create a clock starting at `now_ms`
+    pub fn new(now_ms: u64) -> Self {
+        Self { now_ms: Cell::new(now_ms) }
+    }
+    /// Move virtual time forward
+    pub fn advance_ms(&self, ms: u64) {
+        self.now_ms.set(self.now_ms.get().saturating_add(ms));
+    }
+    /// Jump to an absolute virtual time
+    pub fn set_ms(&self, now_ms: u64) {
+        self.now_ms.set(now_ms);
+    }
+}
+impl Clock for ManualClock {
+    fn now_ms(&mut self) -> u64 {
+        self.now_ms.get()
+    }
+}
+impl Clock for &ManualClock {
+    fn now_ms(&mut self) -> u64 {
+        self.now_ms.get()
+    }
+}
+
+/// Fixed-period sampling schedule driven by a [`Clock`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Debug)]
+pub struct Pacer<C> {
+    clock: C,
+    period_ms: u32,
+    next_due_ms: Option<u64>,
+}
+impl<C: Clock> Pacer<C> {
+    /// Create a pacer with the first sample due immediately
+    pub fn new(clock: C, period_ms: u32) -> Self {
+        Self { clock, period_ms, next_due_ms: None }
+    }
+
+    /// Sampling period in milliseconds
+    pub fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Change the sampling period, taking effect after the next sample
+    pub fn set_period_ms(&mut self, period_ms: u32) {
+        self.period_ms = period_ms;
+    }
+
+    /// Current time of the underlying clock
+    pub fn now_ms(&mut self) -> u64 {
+        self.clock.now_ms()
+    }
+
+    /// Milliseconds until the next sample is due, 0 if it is due now
+    pub fn until_due_ms(&mut self) -> u32 {
+        match self.next_due_ms {
+            None => 0,
+            Some(due) => due.saturating_sub(self.clock.now_ms()).min(u32::MAX as u64) as u32,
+        }
+    }
+
+    /// true if a sample is due now
+    pub fn is_due(&mut self) -> bool {
+        self.until_due_ms() == 0
+    }
+
+    /// Record that a sample was taken now and schedule the next one.  The schedule keeps its
+    /// phase unless the sample was more than one period late.
+    pub fn mark_sampled(&mut self) {
+        let now = self.clock.now_ms();
+        let period = self.period_ms as u64;
+        let next = match self.next_due_ms {
+            Some(due) if now < due.saturating_add(period) => due.saturating_add(period),
+            _ => now.saturating_add(period),
+        };
+        self.next_due_ms = Some(next);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::types::*;
+
+#[cfg(any(feature = "math", feature = "micromath"))]
+use crate::psychrometrics::dew_point_centigrade;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// One step of a sample processing [`Pipeline`].
+///
+/// A stage consumes one input and produces zero or one output: filters may hold samples back
+/// (e.g. while a window fills), derive stages attach computed values, sinks return `()`.
+pub trait Stage<In> {
+    /// Output of this stage
+    type Out;
+    /// Process one input
+    fn process(&mut self, input: In) -> Option<Self::Out>;
+}
+
+/// Two stages run back to back, built by [`Pipeline::then()`]
+#[derive(Clone, Debug)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+impl<In, A, B> Stage<In> for Chain<A, B>
+where
+    A: Stage<In>,
+    B: Stage<A::Out>,
+{
+    type Out = B::Out;
+    fn process(&mut self, input: In) -> Option<Self::Out> {
+        self.first.process(input).and_then(|mid| self.second.process(mid))
+    }
+}
+
+/// Composable `source → filter → derive → sink` processing over a stream of samples.
+///
+/// ```ignore
+/// let mut pipeline = Pipeline::new(Median::<5>::new())
+///     .then(Ema::new(0.2))
+///     .then(DewPoint)
+///     .then(Sink(|sample: DewPointSample| display.show(sample)));
+/// loop {
+///     if let Datum::TempAndRelHumid(sample) = Datum::from(&hdc302x.one_shot(LowPowerMode::lowest_noise())?) {
+///         pipeline.push(sample);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pipeline<S> {
+    stages: S,
+}
+impl<S> Pipeline<S> {
+    /// Start a pipeline with its first stage
+    pub fn new(first: S) -> Self {
+        Self { stages: first }
+    }
+    /// Append a stage
+    pub fn then<N>(self, next: N) -> Pipeline<Chain<S, N>> {
+        Pipeline { stages: Chain { first: self.stages, second: next } }
+    }
+    /// Feed one input through every stage
+    pub fn push<In>(&mut self, input: In) -> Option<S::Out>
+    where
+        S: Stage<In>,
+    {
+        self.stages.process(input)
+    }
+}
+impl<In, S: Stage<In>> Stage<In> for Pipeline<S> {
+    type Out = S::Out;
+    fn process(&mut self, input: In) -> Option<Self::Out> {
+        self.stages.process(input)
+    }
+}
+
+/// Stage applying a function to every input
+#[derive(Clone, Copy, Debug)]
+pub struct Map<F>(pub F);
+impl<In, Out, F: FnMut(In) -> Out> Stage<In> for Map<F> {
+    type Out = Out;
+    fn process(&mut self, input: In) -> Option<Out> {
+        Some((self.0)(input))
+    }
+}
+
+/// Stage passing on only the inputs the predicate accepts
+#[derive(Clone, Copy, Debug)]
+pub struct Filter<F>(pub F);
+impl<In, F: FnMut(&In) -> bool> Stage<In> for Filter<F> {
+    type Out = In;
+    fn process(&mut self, input: In) -> Option<In> {
+        (self.0)(&input).then_some(input)
+    }
+}
+
+/// Terminal stage handing every input to a function
+#[derive(Clone, Copy, Debug)]
+pub struct Sink<F>(pub F);
+impl<In, F: FnMut(In)> Stage<In> for Sink<F> {
+    type Out = ();
+    fn process(&mut self, input: In) -> Option<()> {
+        (self.0)(input);
+        Some(())
+    }
+}
+
+/// Running median over the last `N` samples, applied to temperature and humidity independently.
+/// Outputs nothing until the window is full.
+#[derive(Clone, Copy, Debug)]
+pub struct Median<const N: usize> {
+    centigrade: [f32; N],
+    humidity_percent: [f32; N],
+    next: usize,
+    len: usize,
+}
+impl<const N: usize> Median<N> {
+    /// Create an empty window
+    pub fn new() -> Self {
+        Self {
+            centigrade: [0.0; N],
+            humidity_percent: [0.0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+}
+impl<const N: usize> Default for Median<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+fn median<const N: usize>(window: &[f32; N]) -> f32 {
+    let mut sorted = *window;
+    sorted.sort_unstable_by(f32::total_cmp);
+    if N % 2 == 1 {
+        sorted[N / 2]
+    } else {
+        (sorted[N / 2 - 1] + sorted[N / 2]) / 2.0
+    }
+}
+impl<const N: usize> Stage<TempAndRelHumid> for Median<N> {
+    type Out = TempAndRelHumid;
+    fn process(&mut self, input: TempAndRelHumid) -> Option<TempAndRelHumid> {
+        if N == 0 {
+            return Some(input);
+        }
+        self.centigrade[self.next] = input.centigrade;
+        self.humidity_percent[self.next] = input.humidity_percent;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+        if self.len < N {
+            return None;
+        }
+        let centigrade = median(&self.centigrade);
+        Some(TempAndRelHumid {
+            centigrade,
+            fahrenheit: centigrade * 9.0 / 5.0 + 32.0,
+            humidity_percent: median(&self.humidity_percent),
+        })
+    }
+}
+
+/// Exponential moving average with smoothing factor `alpha` (0 < alpha <= 1, larger follows the
+/// input more closely)
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug)]
+pub struct Ema {
+    alpha: f32,
+    state: Option<TempAndRelHumid>,
+}
+impl Ema {
+    /// Create an average that starts at the first sample
+    pub fn new(alpha: f32) -> Self {
+        Self { alpha, state: None }
+    }
+}
+impl Stage<TempAndRelHumid> for Ema {
+    type Out = TempAndRelHumid;
+    fn process(&mut self, input: TempAndRelHumid) -> Option<TempAndRelHumid> {
+        let next = match self.state {
+            None => input,
+            Some(prev) => {
+                let blend = |prev: f32, new: f32| prev + self.alpha * (new - prev);
+                TempAndRelHumid {
+                    centigrade: blend(prev.centigrade, input.centigrade),
+                    fahrenheit: blend(prev.fahrenheit, input.fahrenheit),
+                    humidity_percent: blend(prev.humidity_percent, input.humidity_percent),
+                }
+            }
+        };
+        self.state = Some(next);
+        Some(next)
+    }
+}
+
+/// Sample with its dew point, produced by the [`DewPoint`] stage
+#[cfg(any(feature = "math", feature = "micromath"))]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug)]
+pub struct DewPointSample {
+    /// the input sample
+    pub sample: TempAndRelHumid,
+    /// dew point in degrees centigrade
+    pub dew_point_centigrade: f32,
+}
+
+/// Stage attaching the dew point to each sample
+#[cfg(any(feature = "math", feature = "micromath"))]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DewPoint;
+#[cfg(any(feature = "math", feature = "micromath"))]
+impl Stage<TempAndRelHumid> for DewPoint {
+    type Out = DewPointSample;
+    fn process(&mut self, input: TempAndRelHumid) -> Option<DewPointSample> {
+        Some(DewPointSample {
+            sample: input,
+            dew_point_centigrade: dew_point_centigrade(input.centigrade, input.humidity_percent),
+        })
+    }
+}
+
+/// Which limits of a [`Thresholds`] stage a sample is outside of
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ThresholdFlags {
+    /// temperature below the low limit
+    pub temp_low: bool,
+    /// temperature above the high limit
+    pub temp_high: bool,
+    /// relative humidity below the low limit
+    pub rh_low: bool,
+    /// relative humidity above the high limit
+    pub rh_high: bool,
+}
+impl ThresholdFlags {
+    /// true if any limit is exceeded
+    pub fn any(&self) -> bool {
+        self.temp_low || self.temp_high || self.rh_low || self.rh_high
+    }
+}
+
+/// Stage comparing each sample against software limits and attaching the result
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// low temperature limit in degrees centigrade
+    pub min_centigrade: f32,
+    /// high temperature limit in degrees centigrade
+    pub max_centigrade: f32,
+    /// low relative humidity limit in percent
+    pub min_humidity_percent: f32,
+    /// high relative humidity limit in percent
+    pub max_humidity_percent: f32,
+}
+impl Stage<TempAndRelHumid> for Thresholds {
+    type Out = (TempAndRelHumid, ThresholdFlags);
+    fn process(&mut self, input: TempAndRelHumid) -> Option<Self::Out> {
+        let flags = ThresholdFlags {
+            temp_low: input.centigrade < self.min_centigrade,
+            temp_high: input.centigrade > self.max_centigrade,
+            rh_low: input.humidity_percent < self.min_humidity_percent,
+            rh_high: input.humidity_percent > self.max_humidity_percent,
+        };
+        Some((input, flags))
+    }
+}
+
+/// Time-weighted running mean of timestamped samples (`(timestamp_ms, sample)`), integrating
+/// linearly between consecutive samples so irregular intervals (adaptive sampling, retry delays)
+/// do not bias the result.  Outputs nothing until the samples span a non-zero time.  A sample
+/// whose timestamp is not after the previous one adds no weight but starts the next interval.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeWeightedMean {
+    last: Option<(u64, TempAndRelHumid)>,
+    span_ms: u64,
+    centigrade_ms: f64,
+    humidity_percent_ms: f64,
+}
+impl TimeWeightedMean {
+    /// Create an empty mean
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mean over the samples so far, `None` until they span a non-zero time
+    pub fn mean(&self) -> Option<TempAndRelHumid> {
+        if self.span_ms == 0 {
+            return None;
+        }
+        let centigrade = (self.centigrade_ms / self.span_ms as f64) as f32;
+        Some(TempAndRelHumid {
+            centigrade,
+            fahrenheit: centigrade * 9.0 / 5.0 + 32.0,
+            humidity_percent: (self.humidity_percent_ms / self.span_ms as f64) as f32,
+        })
+    }
+
+    /// Time covered by the mean in milliseconds
+    pub fn span_ms(&self) -> u64 {
+        self.span_ms
+    }
+
+    /// Forget all samples, e.g. at the start of a new reporting interval
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+impl Stage<(u64, TempAndRelHumid)> for TimeWeightedMean {
+    type Out = TempAndRelHumid;
+    fn process(&mut self, (timestamp_ms, input): (u64, TempAndRelHumid)) -> Option<TempAndRelHumid> {
+        match self.last {
+            Some((last_ms, last)) if timestamp_ms > last_ms => {
+                let dt_ms = (timestamp_ms - last_ms) as f64;
+                self.span_ms += timestamp_ms - last_ms;
+                self.centigrade_ms += dt_ms * (last.centigrade as f64 + input.centigrade as f64) / 2.0;
+                self.humidity_percent_ms += dt_ms * (last.humidity_percent as f64 + input.humidity_percent as f64) / 2.0;
+            }
+            _ => {}
+        }
+        self.last = Some((timestamp_ms, input));
+        self.mean()
+    }
+}
@@ -0,0 +1,36 @@
+//! Commonly used types and traits, for a single glob import:
+//!
+//! ```ignore
+//! use hdc302x::prelude::*;
+//! ```
+//!
+//! The prelude is versioned: `prelude::*` re-exports the latest version, while `prelude::v1::*`
+//! stays fixed so an explicit version never gains names that could clash with application items.
+
+pub use self::v1::*;
+
+/// Version 1 of the prelude
+pub mod v1 {
+    pub use crate::{
+        AlertLimits,
+        AutoReadTarget,
+        Clock,
+        ConfigStore,
+        Datum,
+        Error,
+        FlaggedSample,
+        Hdc302x,
+        HeaterLevel,
+        I2cAddr,
+        LowPowerMode,
+        Pipeline,
+        RawDatum,
+        RawTempAndRelHumid,
+        SampleRate,
+        Stage,
+        StatusBits,
+        TempAndRelHumid,
+    };
+    #[cfg(any(feature = "math", feature = "micromath"))]
+    pub use crate::TemperatureSource;
+}
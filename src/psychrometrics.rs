@@ -0,0 +1,280 @@
+use crate::float::{expf, fabsf, logf, sqrtf};
+use crate::types::*;
+
+// Magnus formula coefficients over liquid water (Sonntag 1990)
+const MAGNUS_A: f32 = 17.62;
+const MAGNUS_B: f32 = 243.12;
+
+// Saturation vapor pressure over liquid water at 0 °C, in hPa
+const MAGNUS_C_HPA: f32 = 6.112;
+
+// Specific gas constant of water vapor, in J/(kg·K)
+const WATER_VAPOR_GAS_CONSTANT: f32 = 461.5;
+
+// Ratio of the molar masses of water vapor and dry air
+const MOLAR_MASS_RATIO: f32 = 0.622;
+
+// Standard sea level pressure, in hPa
+const STANDARD_PRESSURE_HPA: f32 = 1013.25;
+
+// Magnus formula coefficients over ice (Sonntag 1990)
+const MAGNUS_ICE_A: f32 = 22.46;
+const MAGNUS_ICE_B: f32 = 272.62;
+
+fn magnus_gamma(centigrade: f32) -> f32 {
+    MAGNUS_A * centigrade / (MAGNUS_B + centigrade)
+}
+
+fn vapor_gamma(centigrade: f32, humidity_percent: f32) -> f32 {
+    logf(humidity_percent.max(f32::MIN_POSITIVE) / 100.0) + magnus_gamma(centigrade)
+}
+
+/// Dew point in degrees centigrade from temperature and relative humidity (Magnus formula, within
+/// 0.35 °C from -45 °C to 60 °C)
+pub fn dew_point_centigrade(centigrade: f32, humidity_percent: f32) -> f32 {
+    let gamma = vapor_gamma(centigrade, humidity_percent);
+    MAGNUS_B * gamma / (MAGNUS_A - gamma)
+}
+
+/// Frost point in degrees centigrade from temperature and relative humidity (over liquid water,
+/// as the HDC302x reports it).  Below 0 °C the saturation point is computed over ice; above, it
+/// is the dew point.
+pub fn frost_point_centigrade(centigrade: f32, humidity_percent: f32) -> f32 {
+    let dew_point = dew_point_centigrade(centigrade, humidity_percent);
+    if dew_point >= 0.0 {
+        return dew_point;
+    }
+    let gamma = vapor_gamma(centigrade, humidity_percent);
+    MAGNUS_ICE_B * gamma / (MAGNUS_ICE_A - gamma)
+}
+
+/// Saturation vapor pressure over liquid water at `centigrade`, in hPa
+fn saturation_vapor_pressure_hpa(centigrade: f32) -> f32 {
+    MAGNUS_C_HPA * expf(magnus_gamma(centigrade))
+}
+
+/// Water vapor enhancement factor of moist air at `pressure_hpa` (WMO)
+fn enhancement_factor(pressure_hpa: f32) -> f32 {
+    1.0016 + 3.15e-6 * pressure_hpa - 0.074 / pressure_hpa
+}
+
+/// Partial pressure of water vapor in hPa, corrected for moist air if the pressure is known
+fn vapor_pressure_hpa(centigrade: f32, humidity_percent: f32, pressure_hpa: Option<f32>) -> f32 {
+    let enhancement = pressure_hpa.map_or(1.0, enhancement_factor);
+    saturation_vapor_pressure_hpa(centigrade) * enhancement * humidity_percent.max(0.0) / 100.0
+}
+
+/// Absolute humidity in grams of water vapor per cubic meter from temperature and relative
+/// humidity.  With the barometric pressure in hPa, the saturation vapor pressure is corrected
+/// for moist air (about +0.4 % at sea level); without, it is taken over pure water.
+pub fn absolute_humidity_g_per_m3(centigrade: f32, humidity_percent: f32, pressure_hpa: Option<f32>) -> f32 {
+    let vapor_pressure_pa = 100.0 * vapor_pressure_hpa(centigrade, humidity_percent, pressure_hpa);
+    1000.0 * vapor_pressure_pa / (WATER_VAPOR_GAS_CONSTANT * (centigrade + 273.15))
+}
+
+/// Mixing ratio in grams of water vapor per kilogram of dry air from temperature, relative
+/// humidity and the station pressure in hPa (standard sea level pressure if `None`)
+pub fn mixing_ratio_g_per_kg(centigrade: f32, humidity_percent: f32, pressure_hpa: Option<f32>) -> f32 {
+    let pressure_hpa = pressure_hpa.unwrap_or(STANDARD_PRESSURE_HPA);
+    let vapor_pressure_hpa = vapor_pressure_hpa(centigrade, humidity_percent, Some(pressure_hpa));
+    1000.0 * MOLAR_MASS_RATIO * vapor_pressure_hpa / (pressure_hpa - vapor_pressure_hpa)
+}
+
+/// Specific humidity in grams of water vapor per kilogram of moist air from temperature,
+/// relative humidity and the station pressure in hPa (standard sea level pressure if `None`)
+pub fn specific_humidity_g_per_kg(centigrade: f32, humidity_percent: f32, pressure_hpa: Option<f32>) -> f32 {
+    let pressure_hpa = pressure_hpa.unwrap_or(STANDARD_PRESSURE_HPA);
+    let vapor_pressure_hpa = vapor_pressure_hpa(centigrade, humidity_percent, Some(pressure_hpa));
+    1000.0 * MOLAR_MASS_RATIO * vapor_pressure_hpa / (pressure_hpa - (1.0 - MOLAR_MASS_RATIO) * vapor_pressure_hpa)
+}
+
+/// Vapor pressure deficit in kPa from temperature and relative humidity: how much more water
+/// vapor the air could hold before saturating
+pub fn vapor_pressure_deficit_kpa(centigrade: f32, humidity_percent: f32) -> f32 {
+    saturation_vapor_pressure_hpa(centigrade) / 10.0 * (1.0 - humidity_percent.clamp(0.0, 100.0) / 100.0)
+}
+
+/// Heat index (apparent temperature) from temperature and relative humidity, following the US
+/// National Weather Service: Steadman's simple formula in mild conditions, else the Rothfusz
+/// regression with its low and high humidity adjustments
+pub fn heat_index(centigrade: f32, humidity_percent: f32) -> Temp {
+    let t = centigrade * 1.8 + 32.0;
+    let rh = humidity_percent.clamp(0.0, 100.0);
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let fahrenheit = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut hi = -42.379 + 2.049_015_2 * t + 10.143_331 * rh
+            - 0.224_755_4 * t * rh
+            - 6.837_83e-3 * t * t
+            - 5.481_717e-2 * rh * rh
+            + 1.228_74e-3 * t * t * rh
+            + 8.5282e-4 * t * rh * rh
+            - 1.99e-6 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            hi -= (13.0 - rh) / 4.0 * sqrtf((17.0 - fabsf(t - 95.0)) / 17.0);
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            hi += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        hi
+    };
+    Temp { centigrade: (fahrenheit - 32.0) / 1.8, fahrenheit }
+}
+
+/// Humidex from temperature and relative humidity, the Canadian comfort index (Environment
+/// Canada formula); a dimensionless value on the degrees centigrade scale
+pub fn humidex(centigrade: f32, humidity_percent: f32) -> f32 {
+    let dew_point_kelvin = dew_point_centigrade(centigrade, humidity_percent) + 273.15;
+    let vapor_pressure_hpa = 6.11 * expf(5417.753 * (1.0 / 273.16 - 1.0 / dew_point_kelvin));
+    centigrade + 0.5555 * (vapor_pressure_hpa - 10.0)
+}
+
+/// Relative humidity in percent at which air at `centigrade` has the given dew point
+pub(crate) fn humidity_percent_for_dew_point(centigrade: f32, dew_point_centigrade: f32) -> f32 {
+    100.0 * expf(magnus_gamma(dew_point_centigrade) - magnus_gamma(centigrade))
+}
+
+impl TempAndRelHumid {
+    /// Dew point in degrees centigrade, see [`dew_point_centigrade()`]
+    pub fn dew_point_centigrade(&self) -> f32 {
+        dew_point_centigrade(self.centigrade, self.humidity_percent)
+    }
+
+    /// Frost point in degrees centigrade, see [`frost_point_centigrade()`]
+    pub fn frost_point_centigrade(&self) -> f32 {
+        frost_point_centigrade(self.centigrade, self.humidity_percent)
+    }
+
+    /// Absolute humidity in g/m³, see [`absolute_humidity_g_per_m3()`]
+    pub fn absolute_humidity_g_per_m3(&self, pressure_hpa: Option<f32>) -> f32 {
+        absolute_humidity_g_per_m3(self.centigrade, self.humidity_percent, pressure_hpa)
+    }
+
+    /// Mixing ratio in g/kg, see [`mixing_ratio_g_per_kg()`]
+    pub fn mixing_ratio_g_per_kg(&self, pressure_hpa: Option<f32>) -> f32 {
+        mixing_ratio_g_per_kg(self.centigrade, self.humidity_percent, pressure_hpa)
+    }
+
+    /// Specific humidity in g/kg, see [`specific_humidity_g_per_kg()`]
+    pub fn specific_humidity_g_per_kg(&self, pressure_hpa: Option<f32>) -> f32 {
+        specific_humidity_g_per_kg(self.centigrade, self.humidity_percent, pressure_hpa)
+    }
+
+    /// Vapor pressure deficit in kPa, see [`vapor_pressure_deficit_kpa()`]
+    pub fn vapor_pressure_deficit_kpa(&self) -> f32 {
+        vapor_pressure_deficit_kpa(self.centigrade, self.humidity_percent)
+    }
+
+    /// Heat index, see [`heat_index()`]
+    pub fn heat_index(&self) -> Temp {
+        heat_index(self.centigrade, self.humidity_percent)
+    }
+
+    /// Humidex, see [`humidex()`]
+    pub fn humidex(&self) -> f32 {
+        humidex(self.centigrade, self.humidity_percent)
+    }
+}
+
+/// Source of the temperature used by [`Psychrometrics`] derivations
+pub trait TemperatureSource {
+    /// Temperature in degrees centigrade to use for a sample whose sensor temperature is
+    /// `sensor_centigrade`
+    fn centigrade(&mut self, sensor_centigrade: f32) -> f32;
+}
+
+/// Use the HDC302x's own temperature
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SensorTemperature;
+impl TemperatureSource for SensorTemperature {
+    fn centigrade(&mut self, sensor_centigrade: f32) -> f32 {
+        sensor_centigrade
+    }
+}
+
+/// Use a fixed reference temperature in degrees centigrade, e.g. the latest PT100 reading
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReferenceTemperature(pub f32);
+impl TemperatureSource for ReferenceTemperature {
+    fn centigrade(&mut self, _sensor_centigrade: f32) -> f32 {
+        self.0
+    }
+}
+
+impl<F: FnMut(f32) -> f32> TemperatureSource for F {
+    fn centigrade(&mut self, sensor_centigrade: f32) -> f32 {
+        self(sensor_centigrade)
+    }
+}
+
+/// Context for psychrometric derivations from HDC302x samples.
+///
+/// The relative humidity always comes from the HDC302x; the temperature comes from the
+/// context's [`TemperatureSource`], which can substitute an external, higher-accuracy reference.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Psychrometrics<S = SensorTemperature> {
+    source: S,
+}
+impl Psychrometrics<SensorTemperature> {
+    /// Derive from the HDC302x's own temperature
+    pub fn new() -> Self {
+        Self { source: SensorTemperature }
+    }
+}
+impl<S: TemperatureSource> Psychrometrics<S> {
+    /// Derive from the temperature provided by `source`
+    pub fn with_temperature_source(source: S) -> Self {
+        Self { source }
+    }
+
+    /// The temperature source
+    pub fn temperature_source(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Temperature in degrees centigrade the derivations use for `sample`
+    pub fn centigrade(&mut self, sample: &TempAndRelHumid) -> f32 {
+        self.source.centigrade(sample.centigrade)
+    }
+
+    /// Dew point in degrees centigrade
+    pub fn dew_point_centigrade(&mut self, sample: &TempAndRelHumid) -> f32 {
+        dew_point_centigrade(self.centigrade(sample), sample.humidity_percent)
+    }
+
+    /// Frost point in degrees centigrade
+    pub fn frost_point_centigrade(&mut self, sample: &TempAndRelHumid) -> f32 {
+        frost_point_centigrade(self.centigrade(sample), sample.humidity_percent)
+    }
+
+    /// Absolute humidity in g/m³, optionally corrected for the barometric pressure in hPa
+    pub fn absolute_humidity_g_per_m3(&mut self, sample: &TempAndRelHumid, pressure_hpa: Option<f32>) -> f32 {
+        absolute_humidity_g_per_m3(self.centigrade(sample), sample.humidity_percent, pressure_hpa)
+    }
+
+    /// Mixing ratio in g/kg at the station pressure in hPa (standard sea level pressure if `None`)
+    pub fn mixing_ratio_g_per_kg(&mut self, sample: &TempAndRelHumid, pressure_hpa: Option<f32>) -> f32 {
+        mixing_ratio_g_per_kg(self.centigrade(sample), sample.humidity_percent, pressure_hpa)
+    }
+
+    /// Specific humidity in g/kg at the station pressure in hPa (standard sea level pressure if
+    /// `None`)
+    pub fn specific_humidity_g_per_kg(&mut self, sample: &TempAndRelHumid, pressure_hpa: Option<f32>) -> f32 {
+        specific_humidity_g_per_kg(self.centigrade(sample), sample.humidity_percent, pressure_hpa)
+    }
+
+    /// Vapor pressure deficit in kPa
+    pub fn vapor_pressure_deficit_kpa(&mut self, sample: &TempAndRelHumid) -> f32 {
+        vapor_pressure_deficit_kpa(self.centigrade(sample), sample.humidity_percent)
+    }
+
+    /// Heat index (apparent temperature)
+    pub fn heat_index(&mut self, sample: &TempAndRelHumid) -> Temp {
+        heat_index(self.centigrade(sample), sample.humidity_percent)
+    }
+
+    /// Humidex
+    pub fn humidex(&mut self, sample: &TempAndRelHumid) -> f32 {
+        humidex(self.centigrade(sample), sample.humidity_percent)
+    }
+}
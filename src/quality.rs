@@ -0,0 +1,115 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Quality flags attached to a sample
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MeasurementFlags {
+    /// taken shortly after a device reset, when readings are less reliable
+    pub post_reset: bool,
+    /// measured before the application restarted, see [`SampleDedup`](crate::SampleDedup)
+    pub stale: bool,
+    /// temperature or relative humidity at the end of the raw range (0x0000 or 0xffff), see
+    /// [`SaturationPolicy::Flag`]
+    pub saturated: bool,
+    /// taken with the heater on, so thermally biased: too warm and too dry
+    pub heater_on: bool,
+    /// the first measurement failed its CRC check and this one was taken again; the sample itself
+    /// is sound, the flag tracks bus health
+    pub crc_retried: bool,
+}
+impl MeasurementFlags {
+    /// true if no flag marking the sample as biased or unreliable is set
+    /// ([`MeasurementFlags::crc_retried`] does not)
+    pub fn is_clean(&self) -> bool {
+        !self.post_reset && !self.stale && !self.saturated && !self.heater_on
+    }
+}
+
+/// Sample together with its quality flags
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug)]
+pub struct FlaggedSample {
+    /// the sample
+    pub sample: RawTempAndRelHumid,
+    /// quality flags
+    pub flags: MeasurementFlags,
+}
+
+/// What to do with the first samples after the device reports a reset
+/// ([`StatusBits::reset_since_clear`])
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PostResetPolicy {
+    /// treat them like any other sample
+    Keep,
+    /// return this many samples with [`MeasurementFlags::post_reset`] set
+    Flag(u8),
+    /// silently take and drop this many samples before returning one
+    Discard(u8),
+}
+impl Default for PostResetPolicy {
+    fn default() -> Self {
+        Self::Flag(1)
+    }
+}
+impl PostResetPolicy {
+    pub(crate) fn count(&self) -> u8 {
+        match self {
+            Self::Keep => 0,
+            Self::Flag(count) | Self::Discard(count) => *count,
+        }
+    }
+}
+
+/// What to do when the CRC of a response word does not match (with the `crc` feature)
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CrcPolicy {
+    /// do not check CRCs, e.g. on a bus known to corrupt them in transit
+    Ignore,
+    /// log and count the mismatch in [`Hdc302x::crc_error_count()`], and use the data anyway
+    WarnOnly,
+    /// count the mismatch and fail with [`Error::CrcMismatch`](crate::Error)
+    #[default]
+    Fail,
+}
+
+/// What to do with saturated readings, raw 0x0000 or 0xffff (e.g. relative humidity pinned at
+/// 100% during condensation)
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SaturationPolicy {
+    /// return them unchanged
+    #[default]
+    AsIs,
+    /// clamp temperature to the specified range of -40 °C to 125 °C; relative humidity is
+    /// already limited to 0% to 100% by its raw range
+    Clamp,
+    /// return them unchanged with [`MeasurementFlags::saturated`] set
+    Flag,
+}
+impl SaturationPolicy {
+    /// Apply the policy to every value in `datum`
+    pub fn apply(&self, datum: RawDatum) -> RawDatum {
+        if *self != Self::Clamp {
+            return datum;
+        }
+        let clamp = |raw: u16| raw.clamp(MIN_SPECIFIED_RAW_TEMP, MAX_SPECIFIED_RAW_TEMP);
+        match datum {
+            RawDatum::TempAndRelHumid(sample) => RawDatum::TempAndRelHumid(RawTempAndRelHumid {
+                temperature: clamp(sample.temperature),
+                humidity: sample.humidity,
+            }),
+            RawDatum::MinTemp(raw) => RawDatum::MinTemp(clamp(raw)),
+            RawDatum::MaxTemp(raw) => RawDatum::MaxTemp(clamp(raw)),
+            other => other,
+        }
+    }
+}
+
+const MIN_SPECIFIED_RAW_TEMP: u16 = centigrade_to_raw_temp(-40.0);
+const MAX_SPECIFIED_RAW_TEMP: u16 = centigrade_to_raw_temp(125.0);
@@ -0,0 +1,58 @@
+use crate::types::*;
+
+use portable_atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Latest sample, shared lock-free between the sampling task and readers in interrupt handlers
+/// or on other cores.
+///
+/// The raw temperature and humidity are packed into one atomic word, so a reader never sees
+/// halves of two different samples.  Place it in a `static` and share it by reference:
+///
+/// ```ignore
+/// static LATEST: SampleCell = SampleCell::new();
+///
+/// // sampling task
+/// LATEST.store(&sample);
+///
+/// // display refresh interrupt
+/// if let Some(sample) = LATEST.load() { /* ... */ }
+/// ```
+#[derive(Debug, Default)]
+pub struct SampleCell {
+    packed: AtomicU32,
+    valid: AtomicBool,
+}
+impl SampleCell {
+    /// Create an empty cell
+    pub const fn new() -> Self {
+        Self {
+            packed: AtomicU32::new(0),
+            valid: AtomicBool::new(false),
+        }
+    }
+
+    /// Replace the stored sample
+    pub fn store(&self, sample: &RawTempAndRelHumid) {
+        let packed = (sample.temperature as u32) << 16 | sample.humidity as u32;
+        self.packed.store(packed, Ordering::Release);
+        self.valid.store(true, Ordering::Release);
+    }
+
+    /// Latest stored sample, or `None` before the first store
+    pub fn load(&self) -> Option<RawTempAndRelHumid> {
+        if !self.valid.load(Ordering::Acquire) {
+            return None;
+        }
+        let packed = self.packed.load(Ordering::Acquire);
+        Some(RawTempAndRelHumid {
+            temperature: (packed >> 16) as u16,
+            humidity: packed as u16,
+        })
+    }
+
+    /// Empty the cell, e.g. when the sensor goes missing and the last sample is no longer
+    /// meaningful
+    pub fn clear(&self) {
+        self.valid.store(false, Ordering::Release);
+    }
+}
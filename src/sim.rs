@@ -0,0 +1,438 @@
+use crate::hw_def::*;
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+/// Error returned by [`Hdc302xSim`]: the simulated device did not acknowledge
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SimNack;
+impl embedded_hal::i2c::Error for SimNack {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+    }
+}
+
+/// Measurement mode of the simulated device
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SimMode {
+    /// idle between one-shot measurements
+    Sleep,
+    /// sampling continuously
+    Auto,
+}
+
+const STATUS_HEATER: u16 = 1 << STATUS_FIELD_LSBIT_HEATER_ENABLED;
+const STATUS_RESET: u16 = 1 << STATUS_FIELD_LSBIT_RESET_SINCE_CLEAR;
+const STATUS_CHECKSUM: u16 = 1 << STATUS_FIELD_LSBIT_CHECKSUM_FAILURE;
+const STATUS_T_HIGH: u16 = 1 << STATUS_FIELD_LSBIT_T_HIGH_TRACKING_ALERT;
+const STATUS_T_LOW: u16 = 1 << STATUS_FIELD_LSBIT_T_LOW_TRACKING_ALERT;
+const STATUS_RH_HIGH: u16 = 1 << STATUS_FIELD_LSBIT_RH_HIGH_TRACKING_ALERT;
+const STATUS_RH_LOW: u16 = 1 << STATUS_FIELD_LSBIT_RH_LOW_TRACKING_ALERT;
+
+// set low, set high, clear low, clear high: never alert
+const DEFAULT_THRESHOLDS: [u16; 4] = [0x0000, 0xffff, 0x0000, 0xffff];
+
+/// Simulated HDC302x implementing the `embedded-hal` (and with the `async` feature the
+/// `embedded-hal-async`) I2C traits.
+///
+/// The simulation decodes commands, answers with CRC-protected words, runs one-shot and auto
+/// measurements of a settable environment, evaluates the alert thresholds and keeps the status
+/// register.  Auto-mode samples are taken when the test calls [`Hdc302xSim::advance_auto()`].
+/// Commands that would disturb auto-mode sampling stop auto mode and are counted by
+/// [`Hdc302xSim::auto_disruptions()`].
+#[derive(Clone, Debug)]
+pub struct Hdc302xSim {
+    addr: u8,
+    temperature: u16,
+    humidity: u16,
+    mode: SimMode,
+    last: (u16, u16),
+    min: (u16, u16),
+    max: (u16, u16),
+    auto_samples: u32,
+    auto_disruptions: u32,
+    status: u16,
+    heater_config: u16,
+    thresholds: [u16; 4],
+    nv_thresholds: [u16; 4],
+    nv_offset: u16,
+    reset_state: u16,
+    serial: [u16; 3],
+    response: [u8; 6],
+    response_len: usize,
+    busy_reads: u8,
+    one_shot_busy_reads: u8,
+    write_nacks: u8,
+    truncate_read: Option<usize>,
+    corrupt_write: bool,
+    commands: u32,
+}
+impl Hdc302xSim {
+    /// Simulate a freshly powered device at `addr`, at 25 °C and 50 %RH
+    pub fn new(addr: I2cAddr) -> Self {
+        Self {
+            addr: addr.as_u8(),
+            temperature: centigrade_to_raw_temp(25.0),
+            humidity: percent_to_raw_rel_humid(50.0),
+            mode: SimMode::Sleep,
+            last: (0, 0),
+            min: (u16::MAX, u16::MAX),
+            max: (0, 0),
+            auto_samples: 0,
+            auto_disruptions: 0,
+            status: STATUS_RESET,
+            heater_config: 0,
+            thresholds: DEFAULT_THRESHOLDS,
+            nv_thresholds: DEFAULT_THRESHOLDS,
+            nv_offset: 0,
+            reset_state: reset_state_value(SampleRate::OneShot, LowPowerMode::LPM0),
+            serial: [0x1234, 0x5678, 0x9abc],
+            response: [0; 6],
+            response_len: 0,
+            busy_reads: 0,
+            one_shot_busy_reads: 1,
+            write_nacks: 0,
+            truncate_read: None,
+            corrupt_write: false,
+            commands: 0,
+        }
+    }
+
+    /// Set the environment the next measurements see
+    pub fn set_environment(&mut self, centigrade: f32, humidity_percent: f32) {
+        self.temperature = centigrade_to_raw_temp(centigrade);
+        self.humidity = percent_to_raw_rel_humid(humidity_percent);
+    }
+
+    /// Set the serial number words (bytes 5-4, 3-2, 1-0)
+    pub fn set_serial_words(&mut self, words: [u16; 3]) {
+        self.serial = words;
+    }
+
+    /// Number of reads NACKed while a one-shot measurement is in progress (default 1)
+    pub fn set_one_shot_busy_reads(&mut self, reads: u8) {
+        self.one_shot_busy_reads = reads;
+    }
+
+    /// NACK the next `count` writes
+    pub fn nack_next_writes(&mut self, count: u8) {
+        self.write_nacks = count;
+    }
+
+    /// Corrupt the next data word written, like a bit flip on the bus: the device flags a checksum
+    /// failure and ignores it
+    pub fn corrupt_next_data_write(&mut self) {
+        self.corrupt_write = true;
+    }
+
+    /// Deliver only the first `bytes` bytes of the next response, leaving the rest of the read
+    /// buffer untouched, like a HAL that ends a read early without reporting an error
+    pub fn truncate_next_read(&mut self, bytes: usize) {
+        self.truncate_read = Some(bytes);
+    }
+
+    /// Take `samples` auto-mode samples, if auto mode is running
+    pub fn advance_auto(&mut self, samples: u32) {
+        if self.mode != SimMode::Auto {
+            return;
+        }
+        for _ in 0..samples {
+            self.measure();
+            self.auto_samples += 1;
+        }
+    }
+
+    /// Current measurement mode
+    pub fn mode(&self) -> SimMode {
+        self.mode
+    }
+
+    /// Auto-mode samples taken since the device was created
+    pub fn auto_samples(&self) -> u32 {
+        self.auto_samples
+    }
+
+    /// Commands received in auto mode that stopped it
+    pub fn auto_disruptions(&self) -> u32 {
+        self.auto_disruptions
+    }
+
+    /// Commands accepted since the device was created
+    pub fn command_count(&self) -> u32 {
+        self.commands
+    }
+
+    /// Raw status register
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Whether the heater is on
+    pub fn heater_on(&self) -> bool {
+        self.status & STATUS_HEATER != 0
+    }
+
+    /// Last heater configuration written
+    pub fn heater_config(&self) -> u16 {
+        self.heater_config
+    }
+
+    /// Alert threshold registers: set low, set high, clear low, clear high
+    pub fn thresholds(&self) -> [u16; 4] {
+        self.thresholds
+    }
+
+    /// Alert thresholds stored in non-volatile memory
+    pub fn nv_thresholds(&self) -> [u16; 4] {
+        self.nv_thresholds
+    }
+
+    /// Offset register
+    pub fn nv_offset(&self) -> u16 {
+        self.nv_offset
+    }
+
+    /// Power-up/reset state register
+    pub fn reset_state(&self) -> u16 {
+        self.reset_state
+    }
+
+    /// Simulate a power cycle
+    pub fn power_cycle(&mut self) {
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.mode = match decode_reset_state(self.reset_state) {
+            Some((SampleRate::OneShot, _)) | None => SimMode::Sleep,
+            Some(_) => SimMode::Auto,
+        };
+        self.min = (u16::MAX, u16::MAX);
+        self.max = (0, 0);
+        self.status = STATUS_RESET;
+        self.thresholds = self.nv_thresholds;
+        self.response_len = 0;
+        self.busy_reads = 0;
+    }
+
+    fn measure(&mut self) {
+        let sample = (self.temperature, self.humidity);
+        self.last = sample;
+        self.min = (self.min.0.min(sample.0), self.min.1.min(sample.1));
+        self.max = (self.max.0.max(sample.0), self.max.1.max(sample.1));
+        self.update_alerts();
+    }
+
+    fn update_alerts(&mut self) {
+        let t = self.last.0 >> ALERT_T_SHIFT;
+        let rh = self.last.1 & ALERT_RH_MASK;
+        let t_of = |word: u16| word & !ALERT_RH_MASK;
+        let rh_of = |word: u16| word & ALERT_RH_MASK;
+        let [set_low, set_high, clear_low, clear_high] = self.thresholds;
+        let mut track = |bit: u16, set: bool, clear: bool| {
+            if set {
+                self.status |= bit;
+            } else if clear {
+                self.status &= !bit;
+            }
+        };
+        track(STATUS_T_HIGH, t > t_of(set_high), t < t_of(clear_high));
+        track(STATUS_T_LOW, t < t_of(set_low), t > t_of(clear_low));
+        track(STATUS_RH_HIGH, rh > rh_of(set_high), rh < rh_of(clear_high));
+        track(STATUS_RH_LOW, rh < rh_of(set_low), rh > rh_of(clear_low));
+        let t_alert = self.status & (STATUS_T_HIGH | STATUS_T_LOW) != 0;
+        let rh_alert = self.status & (STATUS_RH_HIGH | STATUS_RH_LOW) != 0;
+        self.status &= !STATUS_ALERT_MASK | STATUS_T_HIGH | STATUS_T_LOW | STATUS_RH_HIGH | STATUS_RH_LOW;
+        if t_alert {
+            self.status |= 1 << STATUS_FIELD_LSBIT_T_TRACKING_ALERT;
+        }
+        if rh_alert {
+            self.status |= 1 << STATUS_FIELD_LSBIT_RH_TRACKING_ALERT;
+        }
+        if t_alert || rh_alert {
+            self.status |= 1 << STATUS_FIELD_LSBIT_AT_LEAST_ONE_ALERT;
+        }
+    }
+
+    fn respond(&mut self, words: &[u16]) {
+        for (chunk, word) in self.response.chunks_exact_mut(3).zip(words) {
+            let bytes = word.to_be_bytes();
+            chunk[0..2].copy_from_slice(&bytes);
+            chunk[2] = crc8(&bytes);
+        }
+        self.response_len = 3 * words.len();
+    }
+
+    fn data_word(&mut self, bytes: &[u8]) -> Option<u16> {
+        let (data, crc) = (&bytes[2..4], bytes[4]);
+        if crc8(data) != crc || core::mem::take(&mut self.corrupt_write) {
+            self.status |= STATUS_CHECKSUM;
+            return None;
+        }
+        self.status &= !STATUS_CHECKSUM;
+        Some(u16::from_be_bytes([data[0], data[1]]))
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), SimNack> {
+        if self.write_nacks > 0 {
+            self.write_nacks -= 1;
+            return Err(SimNack);
+        }
+        if bytes.len() < 2 {
+            return Err(SimNack);
+        }
+        let raw = u16::from_be_bytes([bytes[0], bytes[1]]);
+        if self.mode == SimMode::Auto && !is_safe_during_auto(raw) {
+            self.auto_disruptions += 1;
+            self.mode = SimMode::Sleep;
+        }
+        self.commands += 1;
+        self.response_len = 0;
+        self.busy_reads = 0;
+
+        let Some(command) = Command::from_u16(raw) else {
+            return match raw >> 8 {
+                0x24 => {
+                    self.measure();
+                    self.respond(&[self.last.0, self.last.1]);
+                    self.busy_reads = self.one_shot_busy_reads;
+                    Ok(())
+                }
+                0x20..=0x23 | 0x27 => {
+                    self.mode = SimMode::Auto;
+                    self.min = (u16::MAX, u16::MAX);
+                    self.max = (0, 0);
+                    Ok(())
+                }
+                _ => Err(SimNack),
+            };
+        };
+        let with_data = bytes.len() >= 5;
+        match command {
+            Command::AutoExit => self.mode = SimMode::Sleep,
+            // a sleeping device has no auto mode results and NACKs the read
+            Command::AutoReadTempAndRelHumid
+            | Command::AutoReadMinTemp
+            | Command::AutoReadMaxTemp
+            | Command::AutoReadMinRelHumid
+            | Command::AutoReadMaxRelHumid
+                if self.mode == SimMode::Sleep => {}
+            Command::AutoReadTempAndRelHumid => self.respond(&[self.last.0, self.last.1]),
+            Command::AutoReadMinTemp => self.respond(&[self.min.0]),
+            Command::AutoReadMaxTemp => self.respond(&[self.max.0]),
+            Command::AutoReadMinRelHumid => self.respond(&[self.min.1]),
+            Command::AutoReadMaxRelHumid => self.respond(&[self.max.1]),
+            Command::WriteSetLowAlert
+            | Command::WriteSetHighAlert
+            | Command::WriteClearLowAlert
+            | Command::WriteClearHighAlert => {
+                if !with_data {
+                    return Err(SimNack);
+                }
+                let index = match command {
+                    Command::WriteSetLowAlert => 0,
+                    Command::WriteSetHighAlert => 1,
+                    Command::WriteClearLowAlert => 2,
+                    _ => 3,
+                };
+                if let Some(word) = self.data_word(bytes) {
+                    self.thresholds[index] = word;
+                }
+            }
+            Command::AlertToNV => self.nv_thresholds = self.thresholds,
+            Command::ReadSetLowAlert => self.respond(&[self.thresholds[0]]),
+            Command::ReadSetHighAlert => self.respond(&[self.thresholds[1]]),
+            Command::ReadClearLowAlert => self.respond(&[self.thresholds[2]]),
+            Command::ReadClearHighAlert => self.respond(&[self.thresholds[3]]),
+            Command::HeaterEnable => self.status |= STATUS_HEATER,
+            Command::HeaterDisable => self.status &= !STATUS_HEATER,
+            Command::HeaterConfig => {
+                if with_data {
+                    if let Some(word) = self.data_word(bytes) {
+                        self.heater_config = word;
+                    }
+                } else if bytes.len() == 4 {
+                    // data without its CRC is ignored, like a CRC mismatch
+                    self.status |= STATUS_CHECKSUM;
+                } else if bytes.len() == 2 {
+                    self.respond(&[self.heater_config]);
+                } else {
+                    return Err(SimNack);
+                }
+            }
+            Command::StatusRead => self.respond(&[self.status]),
+            Command::StatusClear => self.status &= STATUS_HEATER,
+            Command::NVOffset => match with_data {
+                true => {
+                    if let Some(word) = self.data_word(bytes) {
+                        self.nv_offset = word;
+                    }
+                }
+                false => self.respond(&[self.nv_offset]),
+            },
+            Command::ResetState => match with_data {
+                true => {
+                    if let Some(word) = self.data_word(bytes) {
+                        self.reset_state = word;
+                    }
+                }
+                false => self.respond(&[self.reset_state]),
+            },
+            Command::SoftReset => self.reset(),
+            Command::SerialID54 => self.respond(&[self.serial[0]]),
+            Command::SerialID32 => self.respond(&[self.serial[1]]),
+            Command::SerialID10 => self.respond(&[self.serial[2]]),
+            Command::ManufacturerID => self.respond(&[MANUFACTURER_ID_TEXAS_INSTRUMENTS]),
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), SimNack> {
+        if self.response_len == 0 {
+            return Err(SimNack);
+        }
+        if self.busy_reads > 0 {
+            self.busy_reads -= 1;
+            return Err(SimNack);
+        }
+        let len = buf.len().min(self.response_len);
+        self.response_len = 0;
+        if let Some(bytes) = self.truncate_read.take() {
+            let bytes = bytes.min(len);
+            buf[..bytes].copy_from_slice(&self.response[..bytes]);
+            return Ok(());
+        }
+        buf[..len].copy_from_slice(&self.response[..len]);
+        buf[len..].fill(0xff);
+        Ok(())
+    }
+
+    fn run(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), SimNack> {
+        if address != self.addr {
+            return Err(SimNack);
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => self.write(bytes)?,
+                Operation::Read(buf) => self.read(buf)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ErrorType for Hdc302xSim {
+    type Error = SimNack;
+}
+
+impl embedded_hal::i2c::I2c for Hdc302xSim {
+    fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        self.run(address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for Hdc302xSim {
+    async fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        self.run(address, operations)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::types::*;
+
+use embedded_hal::digital::OutputPin;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Sensor state shown on a [`StatusLed`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LedState {
+    /// sensor responding, no alert
+    Ok,
+    /// sensor responding with an active alert
+    Alert,
+    /// sensor not responding
+    SensorMissing,
+    /// application is re-initializing the sensor
+    Recovering,
+}
+impl LedState {
+    /// State matching the outcome of a status read
+    pub fn from_status<E>(status: &Result<StatusBits, Error<E>>) -> Self {
+        match status {
+            Ok(status) if status.at_least_one_alert => Self::Alert,
+            Ok(_) => Self::Ok,
+            Err(_) => Self::SensorMissing,
+        }
+    }
+}
+
+/// Blink pattern: the LED is on for the first `on_ms` of every `period_ms`
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LedPattern {
+    /// on time per period in milliseconds
+    pub on_ms: u32,
+    /// period in milliseconds, 0 for a steady level
+    pub period_ms: u32,
+}
+impl LedPattern {
+    /// LED steadily on
+    pub const ON: Self = Self { on_ms: 1, period_ms: 0 };
+    /// LED steadily off
+    pub const OFF: Self = Self { on_ms: 0, period_ms: 0 };
+
+    fn is_on(&self, phase_ms: u64) -> bool {
+        if self.period_ms == 0 {
+            return self.on_ms > 0;
+        }
+        phase_ms % (self.period_ms as u64) < (self.on_ms as u64)
+    }
+}
+
+/// Patterns used for each [`LedState`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LedPatterns {
+    /// short heartbeat by default
+    pub ok: LedPattern,
+    /// fast blink by default
+    pub alert: LedPattern,
+    /// steady on by default
+    pub sensor_missing: LedPattern,
+    /// slow blink by default
+    pub recovering: LedPattern,
+}
+impl Default for LedPatterns {
+    fn default() -> Self {
+        Self {
+            ok: LedPattern { on_ms: 50, period_ms: 2000 },
+            alert: LedPattern { on_ms: 125, period_ms: 250 },
+            sensor_missing: LedPattern::ON,
+            recovering: LedPattern { on_ms: 500, period_ms: 1000 },
+        }
+    }
+}
+
+/// Drives a user-provided LED pin with a blink pattern reflecting the sensor state.
+///
+/// Call [`StatusLed::update()`] regularly (every few tens of milliseconds) with a millisecond
+/// timestamp, e.g. from a [`Clock`](crate::Clock).
+#[derive(Debug)]
+pub struct StatusLed<P> {
+    pin: P,
+    patterns: LedPatterns,
+    state: LedState,
+    since_ms: Option<u64>,
+}
+impl<P: OutputPin> StatusLed<P> {
+    /// Drive `pin` (active high) with the default patterns, starting in [`LedState::Ok`]
+    pub fn new(pin: P) -> Self {
+        Self::with_patterns(pin, LedPatterns::default())
+    }
+
+    /// Drive `pin` (active high) with custom patterns
+    pub fn with_patterns(pin: P, patterns: LedPatterns) -> Self {
+        Self { pin, patterns, state: LedState::Ok, since_ms: None }
+    }
+
+    /// Current state
+    pub fn state(&self) -> LedState {
+        self.state
+    }
+
+    /// Change the state; the new pattern starts from its beginning on the next update
+    pub fn set_state(&mut self, state: LedState) {
+        if state != self.state {
+            self.state = state;
+            self.since_ms = None;
+        }
+    }
+
+    /// Set the LED level for the current time
+    pub fn update(&mut self, now_ms: u64) -> Result<(), P::Error> {
+        let since = *self.since_ms.get_or_insert(now_ms);
+        let pattern = match self.state {
+            LedState::Ok => self.patterns.ok,
+            LedState::Alert => self.patterns.alert,
+            LedState::SensorMissing => self.patterns.sensor_missing,
+            LedState::Recovering => self.patterns.recovering,
+        };
+        if pattern.is_on(now_ms.saturating_sub(since)) {
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        }
+    }
+
+    /// Release the pin
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
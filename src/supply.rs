@@ -0,0 +1,52 @@
+use crate::hw_def::*;
+
+#[cfg(feature="defmt")]
+use defmt::Format;
+
+/// Source of the current supply voltage, e.g. an ADC reading of the battery
+pub trait SupplyVoltage {
+    /// Supply voltage in millivolts
+    fn millivolts(&mut self) -> u32;
+}
+impl<F: FnMut() -> u32> SupplyVoltage for F {
+    fn millivolts(&mut self) -> u32 {
+        self()
+    }
+}
+
+/// Supply voltage limits for heater activation, see [`Hdc302x::heater_with_supply()`](crate::Hdc302x::heater_with_supply).
+///
+/// The heater draws tens of milliamps, enough to brown out a coin cell near the end of its life.
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeaterLockout {
+    /// below this, the heater is refused
+    pub min_millivolts: u32,
+    /// below this (and at or above `min_millivolts`), the heater is limited to 25% power (custom
+    /// levels using only elements of the 25% level are kept)
+    pub derate_below_millivolts: u32,
+}
+impl Default for HeaterLockout {
+    fn default() -> Self {
+        Self {
+            min_millivolts: 2400,
+            derate_below_millivolts: 2700,
+        }
+    }
+}
+impl HeaterLockout {
+    /// Heater level allowed at `millivolts` for a requested `level`, or `None` if the heater
+    /// must stay off.  Turning the heater off is always allowed.
+    pub fn allowed_level(&self, level: HeaterLevel, millivolts: u32) -> Option<HeaterLevel> {
+        match level {
+            HeaterLevel::Off => Some(HeaterLevel::Off),
+            _ if millivolts < self.min_millivolts => None,
+            // a custom level using only elements of the 25% level draws no more than it
+            HeaterLevel::Custom(power) if millivolts < self.derate_below_millivolts && power.is_within(HeaterPower::QUARTER) => {
+                Some(level)
+            }
+            _ if millivolts < self.derate_below_millivolts => Some(HeaterLevel::On25Percent),
+            level => Some(level),
+        }
+    }
+}
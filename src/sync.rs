@@ -0,0 +1,164 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Thread-safe handle to an HDC302x driver for std (e.g. Linux gateway) applications.
+///
+/// The driver lives behind a `Mutex`, so the handle is `Send + Sync` and cheap to clone; every
+/// method locks the driver for the duration of one blocking call.
+#[derive(Debug)]
+pub struct SyncHdc302x<I2C, Delay> {
+    driver: Arc<Mutex<Hdc302x<I2C, Delay>>>,
+    latest: Arc<Mutex<Option<RawTempAndRelHumid>>>,
+    sample_errors: Arc<AtomicU32>,
+}
+impl<I2C, Delay> Clone for SyncHdc302x<I2C, Delay> {
+    fn clone(&self) -> Self {
+        Self {
+            driver: Arc::clone(&self.driver),
+            latest: Arc::clone(&self.latest),
+            sample_errors: Arc::clone(&self.sample_errors),
+        }
+    }
+}
+
+/// Background sampling thread started by [`SyncHdc302x::spawn_sampler()`].  The thread is stopped
+/// and joined when this handle is dropped.
+#[derive(Debug)]
+pub struct SamplerHandle {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+impl SamplerHandle {
+    /// Stop the sampling thread and wait for it to finish
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // dropping the sender wakes the thread immediately
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+impl Drop for SamplerHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl<I2C, Delay> SyncHdc302x<I2C, Delay> {
+    /// Wrap a driver instance
+    pub fn new(driver: Hdc302x<I2C, Delay>) -> Self {
+        Self {
+            driver: Arc::new(Mutex::new(driver)),
+            latest: Arc::new(Mutex::new(None)),
+            sample_errors: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Lock the driver for a sequence of calls that must not be interleaved with other users
+    pub fn lock(&self) -> MutexGuard<'_, Hdc302x<I2C, Delay>> {
+        // a panic while holding the lock cannot leave the driver itself inconsistent
+        self.driver.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Most recent sample taken by the background sampler, if any
+    pub fn latest(&self) -> Option<RawTempAndRelHumid> {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Number of failed samples in the background sampler
+    pub fn sample_errors(&self) -> u32 {
+        self.sample_errors.load(Ordering::Relaxed)
+    }
+}
+
+impl<I2C, Delay, E> SyncHdc302x<I2C, Delay>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E>,
+    Delay: embedded_hal::delay::DelayNs,
+{
+    /// Trigger a one-shot measurement and return the raw sample pair
+    pub fn one_shot(&self, low_power_mode: LowPowerMode) -> Result<RawDatum, Error<E>> {
+        self.lock().one_shot(low_power_mode)
+    }
+
+    /// Enter auto mode (continuous self-timed sampling)
+    pub fn auto_start(&self, sample_rate: SampleRate, low_power_mode: LowPowerMode) -> Result<(), Error<E>> {
+        self.lock().auto_start(sample_rate, low_power_mode)
+    }
+
+    /// exit auto mode and return to sleep
+    pub fn auto_stop(&self) -> Result<(), Error<E>> {
+        self.lock().auto_stop()
+    }
+
+    /// read most recent temperature and relative humidity from auto mode
+    pub fn auto_read(&self, target: AutoReadTarget) -> Result<RawDatum, Error<E>> {
+        self.lock().auto_read(target)
+    }
+
+    /// Condensation heater
+    pub fn heater(&self, heater_level: HeaterLevel) -> Result<(), Error<E>> {
+        self.lock().heater(heater_level)
+    }
+
+    /// Read and optionally clear status bits
+    pub fn read_status(&self, clear: bool) -> Result<StatusBits, Error<E>> {
+        self.lock().read_status(clear)
+    }
+
+    /// Read the NIST-tracable serial number
+    pub fn read_serial_number(&self) -> Result<SerialNumber, Error<E>> {
+        self.lock().read_serial_number()
+    }
+
+    /// Read the NIST-tracable manufacturer ID
+    pub fn read_manufacturer_id(&self) -> Result<ManufacturerId, Error<E>> {
+        self.lock().read_manufacturer_id()
+    }
+
+    /// software reset
+    pub fn software_reset(&self) -> Result<(), Error<E>> {
+        self.lock().software_reset()
+    }
+}
+
+impl<I2C, Delay, E> SyncHdc302x<I2C, Delay>
+where
+    I2C: embedded_hal::i2c::I2c<Error = E> + Send + 'static,
+    Delay: embedded_hal::delay::DelayNs + Send + 'static,
+{
+    /// Start a thread taking a one-shot sample every `period`, available through
+    /// [`SyncHdc302x::latest()`].  The driver is only locked while a sample is being taken.
+    pub fn spawn_sampler(&self, period: Duration, low_power_mode: LowPowerMode) -> SamplerHandle {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = self.clone();
+        let thread = thread::spawn(move || loop {
+            match handle.one_shot(low_power_mode) {
+                Ok(RawDatum::TempAndRelHumid(sample)) => {
+                    *handle.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(sample);
+                }
+                _ => {
+                    handle.sample_errors.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            match stopped.recv_timeout(period) {
+                Err(RecvTimeoutError::Timeout) => continue,
+                _ => break,
+            }
+        });
+        SamplerHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+/// Free-running tick counter, e.g. a SysTick or timer count kept by an interrupt in a superloop
+///
+/// The counter may wrap around at `u32::MAX`.
+pub trait TickSource {
+    /// Current tick count
+    fn ticks(&mut self) -> u32;
+    /// Counter rate in ticks per second
+    fn tick_hz(&self) -> u32;
+}
+impl<T: TickSource + ?Sized> TickSource for &mut T {
+    fn ticks(&mut self) -> u32 {
+        (**self).ticks()
+    }
+    fn tick_hz(&self) -> u32 {
+        (**self).tick_hz()
+    }
+}
+
+/// `DelayNs` implementation that busy-waits on a [`TickSource`], for systems without a HAL delay
+///
+/// Delays last at least the requested time, rounded up to whole ticks plus one for the partial
+/// tick in progress.  The async implementation busy-waits as well, without yielding.
+#[derive(Debug)]
+pub struct TickDelay<T> {
+    source: T,
+}
+impl<T: TickSource> TickDelay<T> {
+    /// Wrap a tick source
+    pub fn new(source: T) -> Self {
+        Self { source }
+    }
+
+    /// Give the tick source back
+    pub fn release(self) -> T {
+        self.source
+    }
+
+    /// Wait `amount` units of `1 / units_per_second` seconds
+    fn wait(&mut self, amount: u32, units_per_second: u64) {
+        if amount == 0 {
+            return;
+        }
+        let ticks = (u64::from(amount) * u64::from(self.source.tick_hz())).div_ceil(units_per_second);
+        self.wait_ticks(ticks + 1);
+    }
+
+    fn wait_ticks(&mut self, mut ticks: u64) {
+        // wait in chunks well below the wrap-around of the counter
+        const CHUNK: u32 = u32::MAX / 2;
+        while ticks > 0 {
+            let chunk = ticks.min(u64::from(CHUNK)) as u32;
+            let start = self.source.ticks();
+            while self.source.ticks().wrapping_sub(start) < chunk {}
+            ticks -= u64::from(chunk);
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: TickSource> embedded_hal::delay::DelayNs for TickDelay<T> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.wait(ns, 1_000_000_000);
+    }
+    fn delay_us(&mut self, us: u32) {
+        self.wait(us, 1_000_000);
+    }
+    fn delay_ms(&mut self, ms: u32) {
+        self.wait(ms, 1_000);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: TickSource> embedded_hal_async::delay::DelayNs for TickDelay<T> {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.wait(ns, 1_000_000_000);
+    }
+    async fn delay_us(&mut self, us: u32) {
+        self.wait(us, 1_000_000);
+    }
+    async fn delay_ms(&mut self, ms: u32) {
+        self.wait(ms, 1_000);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::alert::AlertLimitsError;
 use crate::hw_def::*;
 
 use core::fmt;
@@ -5,12 +6,52 @@ use core::fmt;
 #[cfg(feature="defmt")]
 use defmt::Format;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// HDC302x(-Q1) device driver
 #[derive(Debug)]
 pub struct Hdc302x<I2C, Delay> {
     pub(crate) i2c: I2C,
     pub(crate) delay: Delay,
     pub(crate) i2c_addr: crate::hw_def::I2cAddr,
+    pub(crate) label: &'static str,
+    pub(crate) crc_errors: u32,
+    pub(crate) crc_policy: crate::quality::CrcPolicy,
+    pub(crate) verify_writes: bool,
+    pub(crate) post_reset_policy: crate::quality::PostResetPolicy,
+    pub(crate) post_reset_remaining: u8,
+    pub(crate) mode: DeviceMode,
+    pub(crate) heater_level: Option<HeaterLevel>,
+    pub(crate) startup_grace_ms: u32,
+    pub(crate) startup_pending_ms: u32,
+    pub(crate) read_timeout_ms: u32,
+    pub(crate) bus_speed: crate::hw_def::BusSpeed,
+    pub(crate) min_gap_us: u32,
+    pub(crate) strict_auto: bool,
+    pub(crate) coefficients: crate::correction::ConversionCoefficients,
+    pub(crate) nv_write_confirmed: bool,
+    pub(crate) nv_writes: u32,
+    pub(crate) nv_write_limit: u32,
+    pub(crate) saturation_policy: crate::quality::SaturationPolicy,
+    pub(crate) auto_mode: Option<(crate::hw_def::SampleRate, crate::hw_def::LowPowerMode)>,
+    pub(crate) reset_recovery: Option<crate::config_store::SavedConfig>,
+    pub(crate) observer: Option<crate::observer::StateObserver>,
+    pub(crate) alert_clear_policy: crate::alert::AlertClearPolicy,
+}
+
+/// Measurement mode the driver last put the device in
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeviceMode {
+    /// not known, e.g. after creation or a reset with a programmed power-on auto mode
+    Unknown,
+    /// sleeping between one-shot measurements
+    Sleep,
+    /// auto (self-timed) measurement mode
+    Auto,
+    /// a one-shot was triggered and its result has not been read yet
+    MeasurementPending,
 }
 
 /// All possible errors in this crate
@@ -24,11 +65,125 @@ pub enum Error<E> {
     /// Failure of a checksum from the device was detected
     #[cfg(feature = "crc")]
     CrcMismatch,
+    /// The configuration store failed or holds an unreadable blob
+    ConfigStore,
+    /// The operation reads auto mode results but the device is not in auto mode
+    AutoModeRequired,
+    /// The operation is not possible while the device is in auto mode
+    AutoModeActive,
+    /// The measurement would be biased by the heater, which is on
+    HeaterActiveDuringMeasure,
+    /// Programming non-volatile memory failed: the device reported a checksum failure or the
+    /// values read back differ from the ones written
+    NvWriteFailed,
+    /// Reading or waiting on the ALERT pin failed
+    AlertPin,
+    /// The supply voltage is too low to turn on the heater
+    SupplyTooLow,
+    /// The device response was shorter than expected; `got` of `expected` bytes were received
+    ShortRead {
+        /// response length in bytes
+        expected: usize,
+        /// bytes actually received
+        got: usize,
+    },
+    /// A non-volatile memory write was not confirmed with [`Hdc302x::confirm_nv_write()`] or
+    /// would exceed the session limit set by [`Hdc302x::set_nv_write_limit()`]
+    NvWriteRefused,
+    /// The device reset since its status was last cleared; the configuration set with
+    /// [`Hdc302x::set_reset_recovery()`] was applied again, so the operation can be retried
+    DeviceWasReset,
+    /// The operation is not valid in the mode the driver is in, e.g. a command other than reading
+    /// the result or a software reset while a one-shot result is pending
+    WrongMode(DeviceMode),
+    /// The device did not answer a read within the read timeout (see
+    /// [`Hdc302x::set_read_timeout_ms()`]), even after the command was issued again; it is
+    /// probably disconnected or hung
+    Timeout,
+    /// The device flagged a checksum failure on a data write, so the write did not take effect
+    /// (see [`Hdc302x::set_write_verification()`])
+    DeviceRejectedWrite,
+    /// No HDC302x answered at the address, or the device that did is not an HDC302x (see
+    /// `Hdc302x::probe()`)
+    DeviceNotFound,
+    /// Alert thresholds were rejected, for the given reason
+    InvalidAlertLimits(AlertLimitsError),
+}
+impl<E> Error<E> {
+    /// Stable numeric code for compact telemetry.  Codes are never reused or renumbered, so they
+    /// can be aggregated across firmware versions.
+    ///
+    /// | code | error |
+    /// |------|-------|
+    /// | 1 | `I2c` |
+    /// | 2 | `InvalidInputData` |
+    /// | 3 | `CrcMismatch` |
+    /// | 4 | `ConfigStore` |
+    /// | 5 | `AutoModeRequired` |
+    /// | 6 | `AutoModeActive` |
+    /// | 7 | `HeaterActiveDuringMeasure` |
+    /// | 8 | `NvWriteFailed` |
+    /// | 9 | `AlertPin` |
+    /// | 10 | `SupplyTooLow` |
+    /// | 11 | `ShortRead` |
+    /// | 12 | `NvWriteRefused` |
+    /// | 13 | `DeviceWasReset` |
+    /// | 14 | `WrongMode` |
+    /// | 15 | `Timeout` |
+    /// | 16 | `DeviceRejectedWrite` |
+    /// | 17 | `DeviceNotFound` |
+    /// | 18 | `InvalidAlertLimits` |
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::I2c(_) => 1,
+            Self::InvalidInputData => 2,
+            #[cfg(feature = "crc")]
+            Self::CrcMismatch => 3,
+            Self::ConfigStore => 4,
+            Self::AutoModeRequired => 5,
+            Self::AutoModeActive => 6,
+            Self::HeaterActiveDuringMeasure => 7,
+            Self::NvWriteFailed => 8,
+            Self::AlertPin => 9,
+            Self::SupplyTooLow => 10,
+            Self::ShortRead { .. } => 11,
+            Self::NvWriteRefused => 12,
+            Self::DeviceWasReset => 13,
+            Self::WrongMode(_) => 14,
+            Self::Timeout => 15,
+            Self::DeviceRejectedWrite => 16,
+            Self::DeviceNotFound => 17,
+            Self::InvalidAlertLimits(_) => 18,
+        }
+    }
+
+    /// Attribute the error to the driver instance labelled `label`, see
+    /// [`Hdc302x::set_label()`]
+    pub fn labelled(self, label: &'static str) -> LabelledError<E> {
+        LabelledError { label, error: self }
+    }
 }
 
-/// Raw (still in u16 format) temperature and/or humidity from the device
+/// Driver error together with the label of the instance that returned it, see
+/// [`Hdc302x::labelled()`].  Displays as `hdc302x[intake]: Timeout`, like the log output.
 #[cfg_attr(feature = "defmt", derive(Format))]
 #[derive(Debug)]
+pub struct LabelledError<E> {
+    /// label of the driver instance, see [`Hdc302x::set_label()`]
+    pub label: &'static str,
+    /// the error itself
+    pub error: Error<E>,
+}
+impl<E: fmt::Debug> fmt::Display for LabelledError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hdc302x[{}]: {:?}", self.label, self.error)
+    }
+}
+
+/// Raw (still in u16 format) temperature and/or humidity from the device
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
 pub enum RawDatum {
     /// temerature and relative humidity from one-shot or auto mode
     TempAndRelHumid(RawTempAndRelHumid),
@@ -62,6 +217,46 @@ impl RawDatum {
             Self::MaxRelHumid(_) => None,
         }
     }
+    /// Get temperature in Kelvin
+    pub fn kelvin(&self) -> Option<f32> {
+        match self {
+            Self::TempAndRelHumid(RawTempAndRelHumid{temperature, ..}) => Some(raw_temp_to_kelvin(*temperature)),
+            Self::MinTemp(u16) => Some(raw_temp_to_kelvin(*u16)),
+            Self::MaxTemp(u16) => Some(raw_temp_to_kelvin(*u16)),
+            Self::MinRelHumid(_) => None,
+            Self::MaxRelHumid(_) => None,
+        }
+    }
+    /// Get temperature in millikelvin, rounded
+    pub fn millikelvin(&self) -> Option<i32> {
+        match self {
+            Self::TempAndRelHumid(RawTempAndRelHumid{temperature, ..}) => Some(raw_temp_to_millikelvin(*temperature)),
+            Self::MinTemp(u16) => Some(raw_temp_to_millikelvin(*u16)),
+            Self::MaxTemp(u16) => Some(raw_temp_to_millikelvin(*u16)),
+            Self::MinRelHumid(_) => None,
+            Self::MaxRelHumid(_) => None,
+        }
+    }
+    /// Get temperature in hundredths of a degree Centigrade, rounded, without floating point
+    pub fn centigrade_hundredths(&self) -> Option<i32> {
+        match self {
+            Self::TempAndRelHumid(RawTempAndRelHumid{temperature, ..}) => Some(raw_temp_to_centigrade_hundredths(*temperature)),
+            Self::MinTemp(u16) => Some(raw_temp_to_centigrade_hundredths(*u16)),
+            Self::MaxTemp(u16) => Some(raw_temp_to_centigrade_hundredths(*u16)),
+            Self::MinRelHumid(_) => None,
+            Self::MaxRelHumid(_) => None,
+        }
+    }
+    /// Get temperature in hundredths of a degree Fahrenheit, rounded, without floating point
+    pub fn fahrenheit_hundredths(&self) -> Option<i32> {
+        match self {
+            Self::TempAndRelHumid(RawTempAndRelHumid{temperature, ..}) => Some(raw_temp_to_fahrenheit_hundredths(*temperature)),
+            Self::MinTemp(u16) => Some(raw_temp_to_fahrenheit_hundredths(*u16)),
+            Self::MaxTemp(u16) => Some(raw_temp_to_fahrenheit_hundredths(*u16)),
+            Self::MinRelHumid(_) => None,
+            Self::MaxRelHumid(_) => None,
+        }
+    }
     /// Get relative humidity in percent
     pub fn humidity_percent(&self) -> Option<f32> {
         match self {
@@ -72,11 +267,22 @@ impl RawDatum {
             Self::MaxRelHumid(u16) => Some(raw_rel_humid_to_percent(*u16)),
         }
     }
+    /// Get relative humidity in tenths of a percent, rounded, without floating point
+    pub fn humidity_permille(&self) -> Option<u16> {
+        match self {
+            Self::TempAndRelHumid(_) => None,
+            Self::MinTemp(_) => None,
+            Self::MaxTemp(_) => None,
+            Self::MinRelHumid(u16) => Some(raw_rel_humid_to_permille(*u16)),
+            Self::MaxRelHumid(u16) => Some(raw_rel_humid_to_permille(*u16)),
+        }
+    }
 }
 
 /// Raw (still in u16 format) temperature and relative humidity from the device
 #[cfg_attr(feature = "defmt", derive(Format))]
-#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct RawTempAndRelHumid{
     /// unprocessed temperature
     pub temperature: u16,
@@ -92,15 +298,86 @@ impl RawTempAndRelHumid {
     pub fn centigrade(&self) -> f32 {
         raw_temp_to_centigrade(self.temperature)
     }
+    /// Get temperature in Kelvin
+    pub fn kelvin(&self) -> f32 {
+        raw_temp_to_kelvin(self.temperature)
+    }
+    /// Get temperature in millikelvin, rounded
+    pub fn millikelvin(&self) -> i32 {
+        raw_temp_to_millikelvin(self.temperature)
+    }
+    /// Get temperature in hundredths of a degree Centigrade, rounded, without floating point
+    pub fn centigrade_hundredths(&self) -> i32 {
+        raw_temp_to_centigrade_hundredths(self.temperature)
+    }
+    /// Get temperature in hundredths of a degree Fahrenheit, rounded, without floating point
+    pub fn fahrenheit_hundredths(&self) -> i32 {
+        raw_temp_to_fahrenheit_hundredths(self.temperature)
+    }
     /// Get relative humidity in percent
     pub fn humidity_percent(&self) -> f32 {
         raw_rel_humid_to_percent(self.humidity)
     }
+    /// Get relative humidity in tenths of a percent, rounded, without floating point
+    pub fn humidity_permille(&self) -> u16 {
+        raw_rel_humid_to_permille(self.humidity)
+    }
+    /// true if either value is at the end of the raw range (0x0000 or 0xffff)
+    pub fn is_saturated(&self) -> bool {
+        matches!(self.temperature, 0x0000 | 0xffff) || matches!(self.humidity, 0x0000 | 0xffff)
+    }
 }
 
-/// Temp and/or humidity from the device after conversion
+/// Samples before and during a heater burst, from [`Hdc302x::measure_heater_delta()`]
 #[cfg_attr(feature = "defmt", derive(Format))]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeaterDelta {
+    /// sample with the heater off
+    pub before: RawTempAndRelHumid,
+    /// sample at the end of the heater dwell
+    pub during: RawTempAndRelHumid,
+}
+impl HeaterDelta {
+    /// temperature rise in °C
+    pub fn centigrade_delta(&self) -> f32 {
+        self.during.centigrade() - self.before.centigrade()
+    }
+    /// relative humidity change in %RH, negative for a working heater
+    pub fn humidity_delta_percent(&self) -> f32 {
+        self.during.humidity_percent() - self.before.humidity_percent()
+    }
+}
+
+/// How a drying run of `Hdc302x::dry_sensor()` ended
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DryingOutcome {
+    /// the relative humidity was below the target before heating, so the heater stayed off
+    AlreadyDry,
+    /// the relative humidity dropped below the target
+    Dried,
+    /// the relative humidity was still at or above the target when the time ran out
+    TimedOut,
+}
+
+/// What a drying run of `Hdc302x::dry_sensor()` did
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DryingReport {
+    /// how the run ended
+    pub outcome: DryingOutcome,
+    /// sample taken before heating
+    pub first: RawTempAndRelHumid,
+    /// last sample taken, with the heater on unless the sensor was already dry
+    pub last: RawTempAndRelHumid,
+    /// heating time in milliseconds, counted in sample intervals
+    pub heated_ms: u32,
+}
+
+/// Temp and/or humidity from the device after conversion, printed by `defmt` with units and
+/// `min`/`max` for the auto mode extremes
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
 pub enum Datum {
     /// temerature and relative humidity from one-shot or auto mode
     TempAndRelHumid(TempAndRelHumid),
@@ -113,6 +390,35 @@ pub enum Datum {
     /// maximum relative humidity since auto mode was enabled
     MaxRelHumid(f32),
 }
+#[cfg(feature = "defmt")]
+impl Format for Datum {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::TempAndRelHumid(sample) => defmt::write!(f, "{}", sample),
+            Self::MinTemp(temp) => defmt::write!(f, "min {}", temp),
+            Self::MaxTemp(temp) => defmt::write!(f, "max {}", temp),
+            Self::MinRelHumid(percent) => defmt::write!(f, "min {} %RH", Tenths(*percent)),
+            Self::MaxRelHumid(percent) => defmt::write!(f, "max {} %RH", Tenths(*percent)),
+        }
+    }
+}
+
+// converted value printed by defmt rounded to one decimal, e.g. `23.4`
+#[cfg(feature = "defmt")]
+struct Tenths(f32);
+#[cfg(feature = "defmt")]
+impl Format for Tenths {
+    fn format(&self, f: defmt::Formatter) {
+        let tenths = match self.0 * 10.0 {
+            scaled if scaled.is_nan() => 0,
+            scaled if scaled < 0.0 => (scaled - 0.5) as i32,
+            scaled => (scaled + 0.5) as i32,
+        };
+        let sign = if tenths < 0 { "-" } else { "" };
+        let magnitude = tenths.unsigned_abs();
+        defmt::write!(f, "{=str}{=u32}.{=u32}", sign, magnitude / 10, magnitude % 10)
+    }
+}
 impl From<&RawDatum> for Datum {
     fn from(raw: &RawDatum) -> Self {
         match raw {
@@ -125,9 +431,10 @@ impl From<&RawDatum> for Datum {
     }
 }
 
-/// Temp and relative humidity from the device after conversion
-#[cfg_attr(feature = "defmt", derive(Format))]
-#[derive(Debug)]
+/// Temp and relative humidity from the device after conversion, printed by `defmt` with units,
+/// e.g. `23.4 °C 45.1 %RH`
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
 pub struct TempAndRelHumid {
     /// degrees centigrade
     pub centigrade: f32,
@@ -136,6 +443,12 @@ pub struct TempAndRelHumid {
     /// relative humidity in percent
     pub humidity_percent: f32,
 }
+#[cfg(feature = "defmt")]
+impl Format for TempAndRelHumid {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{} °C {} %RH", Tenths(self.centigrade), Tenths(self.humidity_percent))
+    }
+}
 impl From<&RawTempAndRelHumid> for TempAndRelHumid {
     fn from(raw: &RawTempAndRelHumid) -> Self {
         Self {
@@ -145,15 +458,31 @@ impl From<&RawTempAndRelHumid> for TempAndRelHumid {
         }
     }
 }
-/// Temp after conversion
-#[cfg_attr(feature = "defmt", derive(Format))]
-#[derive(Debug)]
+impl TempAndRelHumid {
+    /// Get temperature in Kelvin
+    pub fn kelvin(&self) -> f32 {
+        self.centigrade + 273.15
+    }
+    /// Get temperature in millikelvin, rounded
+    pub fn millikelvin(&self) -> i32 {
+        centigrade_to_millikelvin(self.centigrade)
+    }
+}
+/// Temp after conversion, printed by `defmt` with units
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
 pub struct Temp{
     /// degrees centigrade
     pub centigrade: f32,
     /// degrees fahrenheit
     pub fahrenheit: f32,
 }
+#[cfg(feature = "defmt")]
+impl Format for Temp {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{} °C", Tenths(self.centigrade))
+    }
+}
 impl From<u16> for Temp {
     fn from(raw: u16) -> Self {
         Self {
@@ -162,9 +491,19 @@ impl From<u16> for Temp {
         }
     }
 }
+impl Temp {
+    /// Get temperature in Kelvin
+    pub fn kelvin(&self) -> f32 {
+        self.centigrade + 273.15
+    }
+    /// Get temperature in millikelvin, rounded
+    pub fn millikelvin(&self) -> i32 {
+        centigrade_to_millikelvin(self.centigrade)
+    }
+}
 
-/// Status bits from the device
-#[cfg_attr(feature = "defmt", derive(Format))]
+/// Status bits from the device, printed by `defmt` as the raw word and the names of the set bits
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(from = "u16", into = "u16"))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct StatusBits {
     raw: u16,
@@ -172,17 +511,20 @@ pub struct StatusBits {
     pub at_least_one_alert: bool,
     /// heater is enabled
     pub heater_enabled: bool,
-    /// relative humidity tracking alert
+    /// relative humidity tracking alert: the last relative humidity measured crossed a set
+    /// threshold and has not yet come back past the matching clear threshold
     pub rh_tracking_alert: bool,
-    /// temperature tracking alert
+    /// temperature tracking alert, like [`StatusBits::rh_tracking_alert`] for temperature
     pub t_tracking_alert: bool,
-    /// relative humidity high tracking alert
+    /// relative humidity high tracking alert: above the set high threshold, until below clear
+    /// high.  With the thresholds programmed around a known reference humidity (see
+    /// [`DriftWatch`](crate::DriftWatch)), this flags offset drift.
     pub rh_high_tracking_alert: bool,
-    /// relative humidity low tracking alert
+    /// relative humidity low tracking alert: below the set low threshold, until above clear low
     pub rh_low_tracking_alert: bool,
-    /// temperature high tracking alert
+    /// temperature high tracking alert: above the set high threshold, until below clear high
     pub t_high_tracking_alert: bool,
-    /// temperature low tracking alert
+    /// temperature low tracking alert: below the set low threshold, until above clear low
     pub t_low_tracking_alert: bool,
     /// reset (power-on or software) detected since last clear of status register
     pub reset_since_clear: bool,
@@ -206,12 +548,38 @@ impl From<u16> for StatusBits {
         }
     }
 }
+impl From<StatusBits> for u16 {
+    fn from(status: StatusBits) -> Self {
+        status.raw
+    }
+}
 impl StatusBits {
     /// Get the raw status bits
     pub fn raw(&self) -> u16 {
         self.raw
     }
 }
+#[cfg(feature = "defmt")]
+impl Format for StatusBits {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "status {=u16:#06x}", self.raw);
+        let names = [
+            (self.at_least_one_alert, "at_least_one_alert"),
+            (self.heater_enabled, "heater_enabled"),
+            (self.rh_tracking_alert, "rh_tracking_alert"),
+            (self.t_tracking_alert, "t_tracking_alert"),
+            (self.rh_high_tracking_alert, "rh_high_tracking_alert"),
+            (self.rh_low_tracking_alert, "rh_low_tracking_alert"),
+            (self.t_high_tracking_alert, "t_high_tracking_alert"),
+            (self.t_low_tracking_alert, "t_low_tracking_alert"),
+            (self.reset_since_clear, "reset_since_clear"),
+            (self.checksum_failure, "checksum_failure"),
+        ];
+        for (_, name) in names.iter().filter(|(set, _)| *set) {
+            defmt::write!(f, " {=str}", name);
+        }
+    }
+}
 impl fmt::Display for StatusBits {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "StatusBits {{ 0x{:02x}; ", self.raw)?;
@@ -249,10 +617,117 @@ impl fmt::Display for StatusBits {
     }
 }
 
+/// Heater configuration read back from the device, see `Hdc302x::read_heater_state()`
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeaterState {
+    /// heater configuration register value
+    pub config: u16,
+    /// heater enabled, from the status register
+    pub enabled: bool,
+    /// heater setting the driver last applied, `None` if it does not know it
+    pub expected: Option<HeaterLevel>,
+}
+impl HeaterState {
+    /// Programmed heater power, `None` if the register selects no element or has bits outside
+    /// [`HeaterPower::MASK`] set
+    pub fn power(&self) -> Option<HeaterPower> {
+        HeaterPower::from_bits(self.config)
+    }
+
+    /// Heater level in effect, `None` if the heater is enabled with an invalid configuration.
+    /// Preset element selections decode to the preset levels.
+    pub fn level(&self) -> Option<HeaterLevel> {
+        if !self.enabled {
+            return Some(HeaterLevel::Off);
+        }
+        self.power().map(HeaterLevel::from_power)
+    }
+
+    /// true if the device runs the heater as the driver last set it
+    pub fn is_consistent(&self) -> bool {
+        let Some(expected) = self.expected else {
+            return false;
+        };
+        match (expected, self.level()) {
+            (HeaterLevel::Off, _) => !self.enabled,
+            (expected, Some(level)) => expected.setting() == level.setting(),
+            (_, None) => false,
+        }
+    }
+}
+
 
 /// Serial number of the device
 #[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct SerialNumber(pub [u8; 6]);
+impl SerialNumber {
+    /// Assemble the serial number from the words read by SerialID54, SerialID32 and SerialID10
+    pub(crate) fn from_words(words: [u16; 3]) -> Self {
+        let [b5, b4] = words[0].to_be_bytes();
+        let [b3, b2] = words[1].to_be_bytes();
+        let [b1, b0] = words[2].to_be_bytes();
+        Self([b0, b1, b2, b3, b4, b5])
+    }
+
+    /// Split into the three words the device reports the NIST ID in, most significant byte
+    /// first as transmitted.  The datasheet does not document any finer partition (lot, wafer,
+    /// ...) of the ID.
+    pub fn fields(&self) -> SerialNumberFields {
+        self.fields_in(SerialByteOrder::BigEndian)
+    }
+
+    /// Split into the three words like [`SerialNumber::fields()`], with the bytes of each word in
+    /// `order`, e.g. to match a traceability database that stored the words little-endian
+    pub fn fields_in(&self, order: SerialByteOrder) -> SerialNumberFields {
+        let b = self.0;
+        let word = |msb: u8, lsb: u8| match order {
+            SerialByteOrder::BigEndian => u16::from_be_bytes([msb, lsb]),
+            SerialByteOrder::LittleEndian => u16::from_le_bytes([msb, lsb]),
+        };
+        SerialNumberFields {
+            bytes_5_4: word(b[5], b[4]),
+            bytes_3_2: word(b[3], b[2]),
+            bytes_1_0: word(b[1], b[0]),
+        }
+    }
+
+    /// The ID as a 48-bit integer, byte 5 most significant (the order used by `Display`)
+    pub fn as_u64(&self) -> u64 {
+        self.0.iter().rev().fold(0u64, |acc, byte| acc << 8 | *byte as u64)
+    }
+
+    /// The ID in transmission order: byte 5 first, byte 0 last
+    pub fn to_be_bytes(&self) -> [u8; 6] {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
+}
+
+/// Byte order of the words in [`SerialNumberFields`], see [`SerialNumber::fields_in()`]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SerialByteOrder {
+    /// higher-numbered byte first, the order the device transmits
+    BigEndian,
+    /// lower-numbered byte first
+    LittleEndian,
+}
+
+/// Serial number split into the words read by the SerialID54, SerialID32 and SerialID10 commands
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SerialNumberFields {
+    /// NIST ID bytes 5 (MSB) and 4
+    pub bytes_5_4: u16,
+    /// NIST ID bytes 3 (MSB) and 2
+    pub bytes_3_2: u16,
+    /// NIST ID bytes 1 (MSB) and 0
+    pub bytes_1_0: u16,
+}
 impl fmt::Display for SerialNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for byte in self.0.iter().rev() {
@@ -279,9 +754,9 @@ impl From<u16> for ManufacturerId {
         }
     }
 }
-impl Into<u16> for ManufacturerId {
-    fn into(self) -> u16 {
-        match self {
+impl From<ManufacturerId> for u16 {
+    fn from(mid: ManufacturerId) -> Self {
+        match mid {
             ManufacturerId::TexasInstruments => MANUFACTURER_ID_TEXAS_INSTRUMENTS,
             ManufacturerId::Other(id) => id,
         }
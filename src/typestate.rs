@@ -0,0 +1,166 @@
+use crate::hw_def::*;
+use crate::types::*;
+
+use core::marker::PhantomData;
+
+use hdc302x_macros::blocking_and_async;
+
+/// Device modes tracked by [`Hdc302xTyped`]
+pub mod mode {
+    /// Sleeping between measurements: one-shots and entering auto mode are allowed
+    #[derive(Clone, Copy, Debug)]
+    pub struct Sleep;
+    /// Sampling in auto mode: auto-mode results can be read
+    #[derive(Clone, Copy, Debug)]
+    pub struct Auto;
+    /// A one-shot was triggered and its result has not been read yet
+    #[derive(Clone, Copy, Debug)]
+    pub struct MeasurementPending;
+
+    /// Modes in which status, ID and heater commands are allowed
+    pub trait Idle: sealed::Sealed {}
+    impl Idle for Sleep {}
+    impl Idle for Auto {}
+
+    mod sealed {
+        pub trait Sealed {}
+        impl Sealed for super::Sleep {}
+        impl Sealed for super::Auto {}
+    }
+}
+
+/// Typestate wrapper around [`Hdc302x`]: the device mode is part of the type, so reading auto-mode
+/// results outside auto mode, triggering a one-shot while auto mode runs or issuing commands while
+/// a one-shot result is pending do not compile.
+///
+/// Transitions consume the wrapper.  When a transition fails, the error is returned together with
+/// the wrapper in its previous mode.  [`Hdc302xTyped::into_inner()`] gives up the type-level
+/// tracking and returns the plain driver.
+#[derive(Debug)]
+pub struct Hdc302xTyped<I2C, Delay, Mode> {
+    driver: Hdc302x<I2C, Delay>,
+    mode: PhantomData<Mode>,
+}
+
+impl<I2C, Delay, Mode> Hdc302xTyped<I2C, Delay, Mode> {
+    fn retype<Next>(self) -> Hdc302xTyped<I2C, Delay, Next> {
+        Hdc302xTyped { driver: self.driver, mode: PhantomData }
+    }
+
+    /// Return the plain driver, dropping the type-level mode tracking
+    pub fn into_inner(self) -> Hdc302x<I2C, Delay> {
+        self.driver
+    }
+}
+
+impl<I2C, Delay> Hdc302xTyped<I2C, Delay, mode::Sleep> {
+    /// Create a driver for a device in sleep, its factory default power-up mode.  Use
+    /// [`Hdc302xTyped::from_driver()`] if the device may have been programmed to power up in auto
+    /// mode.
+    pub fn new(i2c: I2C, delay: Delay, i2c_addr: I2cAddr) -> Self {
+        Self { driver: Hdc302x::new(i2c, delay, i2c_addr), mode: PhantomData }
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> Hdc302xTyped<I2C, Delay, mode::Sleep>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Wrap `driver`, first stopping auto mode so the device is known to sleep
+    pub async fn from_driver_async(mut driver: Hdc302x<I2C, Delay>) -> Result<Self, (Hdc302x<I2C, Delay>, Error<E>)> {
+        match driver.auto_stop_async().await {
+            Ok(()) => Ok(Self { driver, mode: PhantomData }),
+            Err(error) => Err((driver, error)),
+        }
+    }
+
+    /// Take a one-shot measurement and wait for its result, see [`Hdc302x::one_shot_async()`]
+    pub async fn one_shot_async(&mut self, low_power_mode: LowPowerMode) -> Result<RawDatum, Error<E>> {
+        self.driver.one_shot_async(low_power_mode).await
+    }
+
+    /// Trigger a one-shot measurement; read its result from the returned wrapper
+    pub async fn start_one_shot_async(
+        mut self,
+        low_power_mode: LowPowerMode,
+    ) -> Result<Hdc302xTyped<I2C, Delay, mode::MeasurementPending>, (Self, Error<E>)> {
+        match self.driver.start_one_shot_async(low_power_mode).await {
+            Ok(()) => Ok(self.retype()),
+            Err(error) => Err((self, error)),
+        }
+    }
+
+    /// Enter auto mode.  [`SampleRate::OneShot`] is not an auto-mode rate and is refused with
+    /// [`Error::InvalidInputData`].
+    pub async fn into_auto_async(
+        mut self,
+        sample_rate: SampleRate,
+        low_power_mode: LowPowerMode,
+    ) -> Result<Hdc302xTyped<I2C, Delay, mode::Auto>, (Self, Error<E>)> {
+        if sample_rate == SampleRate::OneShot {
+            return Err((self, Error::InvalidInputData));
+        }
+        match self.driver.auto_start_async(sample_rate, low_power_mode).await {
+            Ok(()) => Ok(self.retype()),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> Hdc302xTyped<I2C, Delay, mode::MeasurementPending>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Read the pending one-shot result, polling while the device is still converting.  The
+    /// device is back in sleep afterwards, whether or not the read succeeded.
+    pub async fn read_one_shot_async(mut self) -> (Hdc302xTyped<I2C, Delay, mode::Sleep>, Result<RawDatum, Error<E>>) {
+        let result = self.driver.read_one_shot_result_async().await;
+        (self.retype(), result)
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E> Hdc302xTyped<I2C, Delay, mode::Auto>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Read an auto-mode result, see [`Hdc302x::auto_read_async()`]
+    pub async fn auto_read_async(&mut self, target: AutoReadTarget) -> Result<RawDatum, Error<E>> {
+        self.driver.auto_read_async(target).await
+    }
+
+    /// Exit auto mode and return to sleep
+    pub async fn into_sleep_async(mut self) -> Result<Hdc302xTyped<I2C, Delay, mode::Sleep>, (Self, Error<E>)> {
+        match self.driver.auto_stop_async().await {
+            Ok(()) => Ok(self.retype()),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+#[blocking_and_async]
+impl<I2C, Delay, E, Mode: mode::Idle> Hdc302xTyped<I2C, Delay, Mode>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    Delay: embedded_hal_async::delay::DelayNs,
+{
+    /// Read the status bits, see [`Hdc302x::read_status_async()`]
+    pub async fn read_status_async(&mut self, clear: bool) -> Result<StatusBits, Error<E>> {
+        self.driver.read_status_async(clear).await
+    }
+
+    /// Read the serial number, see [`Hdc302x::read_serial_number_async()`]
+    pub async fn read_serial_number_async(&mut self) -> Result<SerialNumber, Error<E>> {
+        self.driver.read_serial_number_async().await
+    }
+
+    /// Set the heater, see [`Hdc302x::heater_async()`]
+    pub async fn heater_async(&mut self, heater_level: HeaterLevel) -> Result<(), Error<E>> {
+        self.driver.heater_async(heater_level).await
+    }
+}
@@ -0,0 +1,92 @@
+use crate::alert::*;
+use crate::offset::Offsets;
+use crate::types::*;
+
+use uom::si::f32::{Ratio, TemperatureInterval, ThermodynamicTemperature};
+use uom::si::{ratio, temperature_interval, thermodynamic_temperature};
+
+fn temperature(centigrade: f32) -> ThermodynamicTemperature {
+    ThermodynamicTemperature::new::<thermodynamic_temperature::degree_celsius>(centigrade)
+}
+
+fn relative_humidity(humidity_percent: f32) -> Ratio {
+    Ratio::new::<ratio::percent>(humidity_percent)
+}
+
+impl RawDatum {
+    /// Get temperature as a `uom` quantity
+    pub fn temperature(&self) -> Option<ThermodynamicTemperature> {
+        self.centigrade().map(temperature)
+    }
+    /// Get relative humidity as a `uom` ratio
+    pub fn relative_humidity(&self) -> Option<Ratio> {
+        self.humidity_percent().map(relative_humidity)
+    }
+}
+
+impl RawTempAndRelHumid {
+    /// Get temperature as a `uom` quantity
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        temperature(self.centigrade())
+    }
+    /// Get relative humidity as a `uom` ratio
+    pub fn relative_humidity(&self) -> Ratio {
+        relative_humidity(self.humidity_percent())
+    }
+}
+
+impl TempAndRelHumid {
+    /// Temperature as a `uom` quantity
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        temperature(self.centigrade)
+    }
+    /// Relative humidity as a `uom` ratio
+    pub fn relative_humidity(&self) -> Ratio {
+        relative_humidity(self.humidity_percent)
+    }
+}
+
+impl Temp {
+    /// Temperature as a `uom` quantity
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        temperature(self.centigrade)
+    }
+}
+
+impl AlertThreshold {
+    /// Temperature threshold as a `uom` quantity
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        temperature(self.centigrade)
+    }
+    /// Relative humidity threshold as a `uom` ratio
+    pub fn relative_humidity(&self) -> Ratio {
+        relative_humidity(self.humidity_percent)
+    }
+}
+
+impl AlertLimits {
+    /// Validate and quantize four (temperature, relative humidity) thresholds given as `uom`
+    /// quantities, see [`AlertLimits::new()`]
+    pub fn new_uom(
+        set_low: (ThermodynamicTemperature, Ratio),
+        set_high: (ThermodynamicTemperature, Ratio),
+        clear_low: (ThermodynamicTemperature, Ratio),
+        clear_high: (ThermodynamicTemperature, Ratio),
+    ) -> Result<Self, AlertLimitsError> {
+        let plain = |(temperature, humidity): (ThermodynamicTemperature, Ratio)| {
+            (temperature.get::<thermodynamic_temperature::degree_celsius>(), humidity.get::<ratio::percent>())
+        };
+        Self::new(plain(set_low), plain(set_high), plain(clear_low), plain(clear_high))
+    }
+}
+
+impl Offsets {
+    /// Temperature offset as a `uom` interval
+    pub fn temperature(&self) -> TemperatureInterval {
+        TemperatureInterval::new::<temperature_interval::degree_celsius>(self.centigrade)
+    }
+    /// Relative humidity offset as a `uom` ratio
+    pub fn relative_humidity(&self) -> Ratio {
+        relative_humidity(self.humidity_percent)
+    }
+}
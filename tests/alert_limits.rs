@@ -0,0 +1,32 @@
+//! Rejected alert thresholds keep their reason in the driver error
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimitsError, Error, Hdc302x, Hdc302xSim, I2cAddr};
+
+fn driver() -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn out_of_range_threshold_reports_the_reason() {
+    let mut hdc302x = driver();
+    let result = hdc302x.write_alert_set_high(200.0, 60.0);
+    assert!(matches!(result, Err(Error::InvalidAlertLimits(AlertLimitsError::OutOfRange))));
+    assert_eq!(result.unwrap_err().code(), 18);
+}
+
+#[test]
+fn clear_threshold_outside_the_set_threshold_reports_which_one() {
+    let mut hdc302x = driver();
+    hdc302x.write_alert_set_high(30.0, 60.0).unwrap();
+    hdc302x.write_alert_set_low(0.0, 20.0).unwrap();
+    let result = hdc302x.write_alert_clear_high(40.0, 70.0);
+    assert!(matches!(result, Err(Error::InvalidAlertLimits(AlertLimitsError::ClearHighOutsideSet))));
+    let result = hdc302x.write_alert_clear_low(-10.0, 10.0);
+    assert!(matches!(result, Err(Error::InvalidAlertLimits(AlertLimitsError::ClearLowOutsideSet))));
+    hdc302x.write_alert_clear_high(28.0, 55.0).unwrap();
+}
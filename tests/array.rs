@@ -0,0 +1,72 @@
+//! Fan-out operations over several sensors
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AutoReadTarget, Error, Hdc302x, Hdc302xArray, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum, SampleRate};
+
+const ADDRS: [I2cAddr; 3] = [I2cAddr::Addr00, I2cAddr::Addr01, I2cAddr::Addr10];
+
+fn sims() -> [Hdc302xSim; 3] {
+    ADDRS.map(Hdc302xSim::new)
+}
+
+fn array(sims: &mut [Hdc302xSim; 3]) -> Hdc302xArray<&mut Hdc302xSim, NoopDelay, 3> {
+    let [a, b, c] = sims;
+    Hdc302xArray::new([
+        Hdc302x::new(a, NoopDelay::new(), ADDRS[0]),
+        Hdc302x::new(b, NoopDelay::new(), ADDRS[1]),
+        Hdc302x::new(c, NoopDelay::new(), ADDRS[2]),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn rejects_duplicate_addresses() {
+    let mut a = Hdc302xSim::new(I2cAddr::Addr00);
+    let mut b = Hdc302xSim::new(I2cAddr::Addr00);
+    let sensors = [
+        Hdc302x::new(&mut a, NoopDelay::new(), I2cAddr::Addr00),
+        Hdc302x::new(&mut b, NoopDelay::new(), I2cAddr::Addr00),
+    ];
+    assert!(Hdc302xArray::new(sensors).is_none());
+}
+
+#[test]
+fn auto_mode_on_all() {
+    let mut sims = sims();
+    let mut sensors = array(&mut sims);
+    assert_eq!(sensors.addresses(), ADDRS);
+    assert!(sensors.auto_start_all(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).iter().all(Result::is_ok));
+    for result in sensors.auto_read_all(AutoReadTarget::LastTempAndRelHumid) {
+        assert!(matches!(result, Ok(RawDatum::TempAndRelHumid(_))));
+    }
+    assert!(sensors.auto_stop_all().iter().all(Result::is_ok));
+}
+
+#[test]
+fn one_shot_on_all() {
+    let mut sims = sims();
+    for sim in &mut sims {
+        sim.set_one_shot_busy_reads(2);
+    }
+    let mut sensors = array(&mut sims);
+    for result in sensors.one_shot_all(LowPowerMode::lowest_noise()) {
+        assert!(matches!(result, Ok(RawDatum::TempAndRelHumid(_))));
+    }
+    sensors.release();
+    assert!(sims.iter().all(|sim| sim.command_count() == 1));
+}
+
+#[test]
+fn failing_sensor_does_not_hide_the_others() {
+    let mut sims = sims();
+    sims[1].nack_next_writes(u8::MAX);
+    let mut sensors = array(&mut sims);
+    let results = sensors.one_shot_all(LowPowerMode::lowest_noise());
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(Error::I2c(_))));
+    assert!(results[2].is_ok());
+    assert!(sensors.sensor_mut(I2cAddr::Addr01).is_some());
+    assert!(sensors.sensor_mut(I2cAddr::Addr11).is_none());
+}
@@ -0,0 +1,134 @@
+//! Commands issued while the device samples in auto mode
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{
+    AlertLimits, AutoReadTarget, DeviceMode, Error, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode, RawDatum,
+    SampleRate, SimMode, StateEvent,
+};
+
+thread_local! {
+    static EVENTS: RefCell<Vec<StateEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record_event(event: &StateEvent) {
+    EVENTS.with_borrow_mut(|events| events.push(*event));
+}
+
+fn driver_in_auto_mode() -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.auto_start(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).unwrap();
+    hdc302x
+}
+
+#[test]
+fn reads_and_heater_do_not_disturb_auto_mode() {
+    let mut hdc302x = driver_in_auto_mode();
+    hdc302x.set_strict_auto_mode(true);
+    hdc302x.read_status(true).unwrap();
+    hdc302x.read_serial_number().unwrap();
+    hdc302x.read_manufacturer_id().unwrap();
+    hdc302x.read_alert_thresholds().unwrap();
+    hdc302x.heater(HeaterLevel::On25Percent).unwrap();
+    hdc302x.heater(HeaterLevel::Off).unwrap();
+
+    let (mut sim, delay) = hdc302x.destroy();
+    assert_eq!(sim.mode(), SimMode::Auto);
+    assert_eq!(sim.auto_disruptions(), 0);
+    sim.advance_auto(3);
+    assert_eq!(sim.auto_samples(), 3);
+
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.auto_start(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).unwrap();
+    let last = hdc302x.auto_read(AutoReadTarget::LastTempAndRelHumid).unwrap();
+    assert!(matches!(last, RawDatum::TempAndRelHumid(_)));
+}
+
+#[test]
+fn strict_mode_refuses_disruptive_commands() {
+    let mut hdc302x = driver_in_auto_mode();
+    hdc302x.set_strict_auto_mode(true);
+    assert!(matches!(hdc302x.write_alert_limits(&AlertLimits::COMFORT_ZONE), Err(Error::AutoModeActive)));
+    hdc302x.confirm_nv_write();
+    assert!(matches!(hdc302x.store_alert_thresholds_nv(), Err(Error::AutoModeActive)));
+    assert!(matches!(hdc302x.one_shot(LowPowerMode::lowest_noise()), Err(Error::AutoModeActive)));
+
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.mode(), SimMode::Auto);
+    assert_eq!(sim.auto_disruptions(), 0);
+}
+
+#[test]
+fn disruptive_commands_stop_auto_mode_without_strict_mode() {
+    let mut hdc302x = driver_in_auto_mode();
+    hdc302x.write_alert_limits(&AlertLimits::COMFORT_ZONE).unwrap();
+
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.mode(), SimMode::Sleep);
+    assert_eq!(sim.auto_disruptions(), 1);
+}
+
+#[test]
+fn reset_recovery_restarts_auto_mode() {
+    let mut hdc302x = driver_in_auto_mode();
+    hdc302x.write_alert_limits(&AlertLimits::FREEZER).unwrap();
+    hdc302x.auto_start(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).unwrap();
+    hdc302x.read_status(true).unwrap();
+    let config = hdc302x.save_config().unwrap();
+
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.power_cycle();
+    assert_eq!(sim.mode(), SimMode::Sleep);
+
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.set_reset_recovery(Some(config));
+    assert!(matches!(hdc302x.auto_read(AutoReadTarget::MaxTemp), Err(Error::DeviceWasReset)));
+    assert!(hdc302x.auto_read(AutoReadTarget::MaxTemp).is_ok());
+
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.mode(), SimMode::Auto);
+    assert_eq!(
+        sim.thresholds(),
+        [config.alert_set_low, config.alert_set_high, config.alert_clear_low, config.alert_clear_high]
+    );
+}
+
+#[test]
+fn observer_sees_recovery_transitions() {
+    let mut hdc302x = driver_in_auto_mode();
+    let config = hdc302x.save_config().unwrap();
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.power_cycle();
+
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.set_reset_recovery(Some(config));
+    hdc302x.set_observer(Some(record_event));
+    assert!(matches!(hdc302x.read_status(false), Err(Error::DeviceWasReset)));
+    hdc302x.auto_stop().unwrap();
+
+    let auto = StateEvent::AutoStarted { sample_rate: SampleRate::Auto1Hz, low_power_mode: LowPowerMode::lowest_noise() };
+    assert_eq!(EVENTS.take(), [StateEvent::Recovering, auto, StateEvent::Recovered, StateEvent::AutoStopped]);
+}
+
+#[test]
+fn init_detects_programmed_power_on_auto_mode() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    assert_eq!(hdc302x.init().unwrap(), DeviceMode::Sleep);
+    hdc302x.confirm_nv_write();
+    hdc302x.write_power_on_mode(SampleRate::Auto2Hz, LowPowerMode::LPM1).unwrap();
+
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.power_cycle();
+    assert_eq!(sim.mode(), SimMode::Auto);
+
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    assert_eq!(hdc302x.mode(), DeviceMode::Unknown);
+    assert_eq!(hdc302x.init().unwrap(), DeviceMode::Auto);
+    assert!(hdc302x.auto_read(AutoReadTarget::LastTempAndRelHumid).is_ok());
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.mode(), SimMode::Auto);
+    assert_eq!(sim.auto_disruptions(), 0);
+}
@@ -0,0 +1,126 @@
+//! Bounded queue between the sampling task and a slow consumer
+
+#![cfg(all(feature = "heapless", feature = "blocking"))]
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, OverflowPolicy, PushOutcome, RawTempAndRelHumid, SampleBridge};
+use heapless::mpmc::MpMcQueue;
+
+fn driver_with<D: DelayNs>(centigrade: f32, delay: D) -> Hdc302x<Hdc302xSim, D> {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(centigrade, 50.0);
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn drop_oldest_keeps_the_newest_samples_and_counts_drops() {
+    let queue = MpMcQueue::<u32, 4>::new();
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::DropOldest);
+    assert_eq!(bridge.policy(), OverflowPolicy::DropOldest);
+    for sample in 0..4 {
+        assert_eq!(bridge.try_push(sample), Ok(PushOutcome::Queued));
+    }
+    for sample in 4..7 {
+        assert_eq!(bridge.try_push(sample), Ok(PushOutcome::DroppedOldest));
+    }
+    assert_eq!(bridge.dropped(), 3);
+    let drained: Vec<_> = core::iter::from_fn(|| queue.dequeue()).collect();
+    assert_eq!(drained, [3, 4, 5, 6]);
+}
+
+#[test]
+fn drop_newest_keeps_the_oldest_samples() {
+    let queue = MpMcQueue::<u32, 2>::new();
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::DropNewest);
+    assert_eq!(bridge.try_push(1), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.try_push(2), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.try_push(3), Ok(PushOutcome::DroppedNewest));
+    assert_eq!(bridge.try_push(4), Ok(PushOutcome::DroppedNewest));
+    assert_eq!(bridge.dropped(), 2);
+    assert_eq!(queue.dequeue(), Some(1));
+    assert_eq!(queue.dequeue(), Some(2));
+    assert_eq!(queue.dequeue(), None);
+}
+
+#[test]
+fn block_hands_the_sample_back() {
+    let queue = MpMcQueue::<u32, 2>::new();
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::Block);
+    assert_eq!(bridge.try_push(1), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.try_push(2), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.try_push(3), Err((PushOutcome::Full, 3)));
+    assert_eq!(bridge.dropped(), 0);
+    assert_eq!(queue.dequeue(), Some(1));
+    assert_eq!(bridge.try_push(3), Ok(PushOutcome::Queued));
+}
+
+#[test]
+fn push_gives_up_after_its_timeout() {
+    let queue = MpMcQueue::<u32, 2>::new();
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::Block);
+    let mut delay = NoopDelay::new();
+    assert_eq!(bridge.push(1, &mut delay, Some(10)), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.push(2, &mut delay, Some(10)), Ok(PushOutcome::Queued));
+    assert_eq!(bridge.push(3, &mut delay, Some(10)), Err((PushOutcome::Full, 3)));
+    assert_eq!(bridge.dropped(), 0);
+}
+
+#[test]
+fn push_waits_for_the_consumer() {
+    let queue = MpMcQueue::<RawTempAndRelHumid, 2>::new();
+    let mut consumer = SlowConsumer { queue: &queue, waited_ns: 0, consumed: 0 };
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::Block);
+    let sample = RawTempAndRelHumid { temperature: 0, humidity: 0 };
+    while queue.enqueue(sample).is_ok() {}
+    assert_eq!(bridge.push(sample, &mut consumer, Some(10)), Ok(PushOutcome::Queued));
+    assert_eq!(consumer.consumed, 1);
+}
+
+/// Consumer that takes one sample from the queue every 5 ms of waiting
+struct SlowConsumer<'q> {
+    queue: &'q MpMcQueue<RawTempAndRelHumid, 2>,
+    waited_ns: u32,
+    consumed: u32,
+}
+impl DelayNs for SlowConsumer<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.waited_ns += ns;
+        while self.waited_ns >= 5_000_000 {
+            self.waited_ns -= 5_000_000;
+            if self.queue.dequeue().is_some() {
+                self.consumed += 1;
+            }
+        }
+    }
+}
+
+#[test]
+fn one_shot_into_waits_for_room_when_blocking() {
+    let queue = MpMcQueue::<RawTempAndRelHumid, 2>::new();
+    let consumer = SlowConsumer { queue: &queue, waited_ns: 0, consumed: 0 };
+    let mut hdc302x = driver_with(21.0, consumer);
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::Block);
+    // the one-shot conversion time alone may let the consumer take a sample, so fill until full
+    while queue.enqueue(RawTempAndRelHumid { temperature: 0, humidity: 0 }).is_ok() {}
+    assert_eq!(hdc302x.one_shot_into(LowPowerMode::lowest_noise(), &mut bridge).unwrap(), PushOutcome::Queued);
+    let (_, consumer) = hdc302x.destroy();
+    assert!(consumer.consumed >= 1);
+    assert_eq!(bridge.dropped(), 0);
+}
+
+#[test]
+fn one_shot_into_applies_drop_oldest() {
+    let queue = MpMcQueue::<RawTempAndRelHumid, 2>::new();
+    let mut bridge = SampleBridge::new(&queue, OverflowPolicy::DropOldest);
+    let mut hdc302x = driver_with(21.0, NoopDelay::new());
+    let low_power_mode = LowPowerMode::lowest_noise();
+    assert_eq!(hdc302x.one_shot_into(low_power_mode, &mut bridge).unwrap(), PushOutcome::Queued);
+    assert_eq!(hdc302x.one_shot_into(low_power_mode, &mut bridge).unwrap(), PushOutcome::Queued);
+    assert_eq!(hdc302x.one_shot_into(low_power_mode, &mut bridge).unwrap(), PushOutcome::DroppedOldest);
+    assert_eq!(bridge.dropped(), 1);
+    let sample = queue.dequeue().unwrap();
+    assert!((sample.centigrade() - 21.0).abs() < 0.01);
+}
@@ -0,0 +1,128 @@
+//! Retry behavior of the command-write and data-read phases of a command
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use hdc302x::{Error, Hdc302x, HeaterLevel, I2cAddr, LowPowerMode, RawDatum};
+
+const ADDR: u8 = 0x44;
+const STATUS_READ: [u8; 2] = [0xf3, 0x2d];
+const ONE_SHOT_LPM0: [u8; 2] = [0x24, 0x00];
+// 0xbeef followed by its CRC
+const WORD: [u8; 3] = [0xbe, 0xef, 0x92];
+
+fn driver(expectations: &[Transaction]) -> Hdc302x<Mock, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(Mock::new(expectations), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn nacked_command_write_is_retried() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, STATUS_READ.to_vec()).with_error(ErrorKind::Other),
+        Transaction::write(ADDR, STATUS_READ.to_vec()).with_error(ErrorKind::Other),
+        Transaction::write(ADDR, STATUS_READ.to_vec()),
+        Transaction::read(ADDR, WORD.to_vec()),
+    ]);
+    assert_eq!(hdc302x.read_status(false).unwrap().raw(), 0xbeef);
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn command_write_gives_up_without_reading() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, STATUS_READ.to_vec()).with_error(ErrorKind::Other),
+        Transaction::write(ADDR, STATUS_READ.to_vec()).with_error(ErrorKind::Other),
+        Transaction::write(ADDR, STATUS_READ.to_vec()).with_error(ErrorKind::Other),
+    ]);
+    assert!(matches!(hdc302x.read_status(false), Err(Error::I2c(ErrorKind::Other))));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn read_is_polled_until_the_result_is_ready() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, ONE_SHOT_LPM0.to_vec()),
+        Transaction::read(ADDR, vec![0; 6]).with_error(ErrorKind::Other),
+        Transaction::read(ADDR, vec![0; 6]).with_error(ErrorKind::Other),
+        Transaction::read(ADDR, [WORD, WORD].concat()),
+    ]);
+    let datum = hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert!(matches!(datum, RawDatum::TempAndRelHumid(sample) if sample.temperature == 0xbeef && sample.humidity == 0xbeef));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn lost_command_is_issued_again() {
+    let mut expectations = vec![Transaction::write(ADDR, STATUS_READ.to_vec())];
+    expectations.extend((0..50).map(|_| Transaction::read(ADDR, vec![0; 3]).with_error(ErrorKind::Other)));
+    expectations.push(Transaction::write(ADDR, STATUS_READ.to_vec()));
+    expectations.push(Transaction::read(ADDR, WORD.to_vec()));
+    let mut hdc302x = driver(&expectations);
+    assert_eq!(hdc302x.read_status(false).unwrap().raw(), 0xbeef);
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn read_times_out_after_reissuing() {
+    let mut expectations = Vec::new();
+    for _ in 0..2 {
+        expectations.push(Transaction::write(ADDR, STATUS_READ.to_vec()));
+        expectations.extend((0..50).map(|_| Transaction::read(ADDR, vec![0; 3]).with_error(ErrorKind::Other)));
+    }
+    let mut hdc302x = driver(&expectations);
+    assert!(matches!(hdc302x.read_status(false), Err(Error::Timeout)));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn read_timeout_is_configurable() {
+    let mut expectations = Vec::new();
+    for _ in 0..2 {
+        expectations.push(Transaction::write(ADDR, STATUS_READ.to_vec()));
+        expectations.extend((0..5).map(|_| Transaction::read(ADDR, vec![0; 3]).with_error(ErrorKind::Other)));
+    }
+    let mut hdc302x = driver(&expectations);
+    hdc302x.set_read_timeout_ms(5);
+    assert!(matches!(hdc302x.read_status(false), Err(Error::Timeout)));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn split_one_shot_returns_none_while_converting() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, ONE_SHOT_LPM0.to_vec()),
+        Transaction::read(ADDR, [WORD, WORD].concat()).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        Transaction::read(ADDR, [WORD, WORD].concat()),
+    ]);
+    hdc302x.start_one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert!(matches!(hdc302x.try_read_one_shot(), Ok(None)));
+    assert!(matches!(hdc302x.try_read_one_shot(), Ok(Some(RawDatum::TempAndRelHumid(_)))));
+    assert!(matches!(hdc302x.try_read_one_shot(), Err(Error::WrongMode(_))));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn fast_one_shot_reads_once_after_the_conversion_time() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, ONE_SHOT_LPM0.to_vec()),
+        Transaction::read(ADDR, [WORD, WORD].concat()),
+    ]);
+    let datum = hdc302x.one_shot_fast(LowPowerMode::lowest_noise()).unwrap();
+    assert!(matches!(datum, RawDatum::TempAndRelHumid(sample) if sample.temperature == 0xbeef));
+    hdc302x.destroy().0.done();
+}
+
+#[test]
+fn heater_config_is_written_with_crc() {
+    let mut hdc302x = driver(&[
+        Transaction::write(ADDR, vec![0x30, 0x66]),
+        Transaction::write(ADDR, vec![0x30, 0x6e, 0x00, 0x9f, 0x96]),
+        Transaction::write(ADDR, vec![0x30, 0x6d]),
+    ]);
+    hdc302x.heater(HeaterLevel::On25Percent).unwrap();
+    hdc302x.destroy().0.done();
+}
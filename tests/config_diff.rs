@@ -0,0 +1,75 @@
+//! Compact report of non-volatile configuration drift
+
+use hdc302x::{ConfigDiff, FieldMismatch, NvConfig, NvField};
+
+fn golden() -> NvConfig {
+    NvConfig {
+        alert_set_low: 0x1234,
+        alert_set_high: 0xcdef,
+        alert_clear_low: 0x2345,
+        alert_clear_high: 0xbcde,
+        offsets: 0x0000,
+        reset_state: 0x0000,
+    }
+}
+
+#[test]
+fn matching_configs_have_an_empty_diff() {
+    let diff = ConfigDiff::between(&golden(), &golden());
+    assert!(diff.is_empty());
+    assert_eq!(diff.len(), 0);
+    assert_eq!(diff.iter().count(), 0);
+    let mut buf = [0u8; ConfigDiff::MAX_BLOB_LEN];
+    assert_eq!(diff.to_bytes(&mut buf), Some(1));
+    assert_eq!(buf[0], 0);
+    assert_eq!(ConfigDiff::from_bytes(&buf[..1]), Some(diff));
+}
+
+#[test]
+fn between_to_bytes_from_bytes_round_trip() {
+    let actual = NvConfig { alert_set_high: 0xcd00, offsets: 0x8181, ..golden() };
+    let diff = ConfigDiff::between(&golden(), &actual);
+    assert_eq!(diff.len(), 2);
+    assert_eq!(diff.mask(), NvField::AlertSetHigh.bit() | NvField::Offsets.bit());
+    let mismatches: Vec<_> = diff.iter().collect();
+    assert_eq!(
+        mismatches,
+        [
+            FieldMismatch { field: NvField::AlertSetHigh, expected: 0xcdef, actual: 0xcd00 },
+            FieldMismatch { field: NvField::Offsets, expected: 0x0000, actual: 0x8181 },
+        ]
+    );
+
+    let mut buf = [0u8; ConfigDiff::MAX_BLOB_LEN];
+    let len = diff.to_bytes(&mut buf).unwrap();
+    assert_eq!(buf[..len], [0x12, 0xcd, 0xef, 0xcd, 0x00, 0x00, 0x00, 0x81, 0x81]);
+    assert_eq!(ConfigDiff::from_bytes(&buf[..len]), Some(diff));
+    assert_eq!(diff.to_bytes(&mut buf[..len - 1]), None);
+}
+
+#[test]
+fn every_field_fits_the_maximum_blob() {
+    let actual = NvConfig {
+        alert_set_low: 1,
+        alert_set_high: 2,
+        alert_clear_low: 3,
+        alert_clear_high: 4,
+        offsets: 5,
+        reset_state: 6,
+    };
+    let diff = ConfigDiff::between(&golden(), &actual);
+    assert_eq!(diff.len(), NvField::ALL.len());
+    let mut buf = [0u8; ConfigDiff::MAX_BLOB_LEN];
+    assert_eq!(diff.to_bytes(&mut buf), Some(ConfigDiff::MAX_BLOB_LEN));
+    assert_eq!(ConfigDiff::from_bytes(&buf), Some(diff));
+}
+
+#[test]
+fn rejects_malformed_blobs() {
+    assert_eq!(ConfigDiff::from_bytes(&[]), None);
+    // unknown field bit
+    assert_eq!(ConfigDiff::from_bytes(&[0x40]), None);
+    // one field set but no words, or too many words
+    assert_eq!(ConfigDiff::from_bytes(&[0x01]), None);
+    assert_eq!(ConfigDiff::from_bytes(&[0x00, 0, 0, 0, 0]), None);
+}
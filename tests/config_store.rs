@@ -0,0 +1,110 @@
+//! Mirroring the non-volatile configuration to MCU storage
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, ConfigStore, Error, Hdc302x, Hdc302xSim, I2cAddr, NvConfig, NvMirrorStatus};
+
+/// In-memory stand-in for a flash page
+#[derive(Default)]
+struct RamStore {
+    blob: Option<Vec<u8>>,
+    fail: bool,
+}
+impl ConfigStore for RamStore {
+    type Error = ();
+
+    fn load(&mut self, buf: &mut [u8]) -> Result<Option<usize>, ()> {
+        if self.fail {
+            return Err(());
+        }
+        Ok(self.blob.as_ref().map(|blob| {
+            buf[..blob.len()].copy_from_slice(blob);
+            blob.len()
+        }))
+    }
+
+    fn save(&mut self, blob: &[u8]) -> Result<(), ()> {
+        if self.fail {
+            return Err(());
+        }
+        self.blob = Some(blob.to_vec());
+        Ok(())
+    }
+}
+
+fn driver_with(sim: Hdc302xSim) -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn blob_v1_round_trips() {
+    let config = NvConfig {
+        alert_set_low: 0x0102,
+        alert_set_high: 0x0304,
+        alert_clear_low: 0x0506,
+        alert_clear_high: 0x0708,
+        offsets: 0x090a,
+        reset_state: 0x0b0c,
+    };
+    let blob = config.to_bytes();
+    assert_eq!(blob, [NvConfig::BLOB_VERSION, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+    assert_eq!(NvConfig::from_bytes(&blob), Some(config));
+}
+
+#[test]
+fn rejects_foreign_blobs() {
+    let blob = NvConfig::default().to_bytes();
+    assert_eq!(NvConfig::from_bytes(&blob[..NvConfig::BLOB_LEN - 1]), None);
+    let mut longer = blob.to_vec();
+    longer.push(0);
+    assert_eq!(NvConfig::from_bytes(&longer), None);
+    let mut other_version = blob;
+    other_version[0] = 2;
+    assert_eq!(NvConfig::from_bytes(&other_version), None);
+}
+
+#[test]
+fn mirror_then_check_detects_drift() {
+    let mut store = RamStore::default();
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    assert_eq!(hdc302x.check_nv_config(&mut store).unwrap(), NvMirrorStatus::NoMirror);
+
+    let mirrored = hdc302x.mirror_nv_config(&mut store).unwrap();
+    assert_eq!(store.blob.as_deref(), Some(&mirrored.to_bytes()[..]));
+    assert_eq!(hdc302x.check_nv_config(&mut store).unwrap(), NvMirrorStatus::Match);
+
+    hdc302x.confirm_nv_write();
+    hdc302x.write_temp_offset(1.0).unwrap();
+    let NvMirrorStatus::Mismatch { expected, actual } = hdc302x.check_nv_config(&mut store).unwrap() else {
+        panic!("expected a mismatch");
+    };
+    assert_eq!(expected, mirrored);
+    assert_ne!(actual.offsets, expected.offsets);
+    assert_eq!(actual.alert_set_low, expected.alert_set_low);
+}
+
+#[test]
+fn thresholds_match_again_once_a_reset_reloads_them() {
+    let mut store = RamStore::default();
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    hdc302x.mirror_nv_config(&mut store).unwrap();
+    hdc302x.write_alert_limits(&AlertLimits::FREEZER).unwrap();
+    assert!(matches!(hdc302x.check_nv_config(&mut store).unwrap(), NvMirrorStatus::Mismatch { .. }));
+    // a reset reloads the thresholds from non-volatile memory
+    hdc302x.software_reset().unwrap();
+    assert_eq!(hdc302x.check_nv_config(&mut store).unwrap(), NvMirrorStatus::Match);
+}
+
+#[test]
+fn store_errors_and_corrupt_blobs_are_reported() {
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    let mut store = RamStore { fail: true, ..Default::default() };
+    assert!(matches!(hdc302x.mirror_nv_config(&mut store), Err(Error::ConfigStore)));
+    assert!(matches!(hdc302x.check_nv_config(&mut store), Err(Error::ConfigStore)));
+
+    let mut store = RamStore { blob: Some(vec![NvConfig::BLOB_VERSION + 1; NvConfig::BLOB_LEN]), fail: false };
+    assert!(matches!(hdc302x.check_nv_config(&mut store), Err(Error::ConfigStore)));
+}
@@ -0,0 +1,104 @@
+//! Per-unit linear correction on top of the nominal conversion
+
+use hdc302x::{ConversionCoefficients, RawDatum, RawTempAndRelHumid};
+
+fn raw_temp(centigrade: f32) -> u16 {
+    ((centigrade + 45.0) * 65536.0 / 175.0 + 0.5) as u16
+}
+
+fn raw_rel_humid(percent: f32) -> u16 {
+    (percent * 65536.0 / 100.0 + 0.5) as u16
+}
+
+#[test]
+fn nominal_is_the_identity() {
+    let nominal = ConversionCoefficients::default();
+    assert_eq!(nominal, ConversionCoefficients::NOMINAL);
+    for raw in [0x0000, 0x0001, 0x4000, 0x8000, 0xfffe] {
+        assert_eq!(nominal.correct_raw_temp(raw), raw);
+        assert_eq!(nominal.correct_raw_rel_humid(raw), raw);
+    }
+}
+
+#[test]
+fn slope_and_offset() {
+    let coefficients = ConversionCoefficients {
+        temp_slope: 1.02,
+        temp_offset_centigrade: -0.5,
+        rel_humid_slope: 0.98,
+        rel_humid_offset_percent: 1.5,
+    };
+    // 25 °C * 1.02 - 0.5 = 25 °C
+    assert_eq!(coefficients.correct_raw_temp(raw_temp(25.0)), raw_temp(25.0));
+    // 10 °C * 1.02 - 0.5 = 9.7 °C
+    assert!(coefficients.correct_raw_temp(raw_temp(10.0)).abs_diff(raw_temp(9.7)) <= 1);
+    // 50 %RH * 0.98 + 1.5 = 50.5 %RH
+    assert!(coefficients.correct_raw_rel_humid(raw_rel_humid(50.0)).abs_diff(raw_rel_humid(50.5)) <= 1);
+}
+
+#[test]
+fn saturates_at_the_raw_range() {
+    let coefficients = ConversionCoefficients {
+        temp_slope: 1.0,
+        temp_offset_centigrade: 20.0,
+        rel_humid_slope: 1.0,
+        rel_humid_offset_percent: -20.0,
+    };
+    assert_eq!(coefficients.correct_raw_temp(raw_temp(120.0)), u16::MAX);
+    assert_eq!(coefficients.correct_raw_temp(0), raw_temp(-25.0));
+    assert_eq!(coefficients.correct_raw_rel_humid(raw_rel_humid(10.0)), 0);
+    let coefficients = ConversionCoefficients { temp_offset_centigrade: -20.0, rel_humid_offset_percent: 20.0, ..coefficients };
+    assert_eq!(coefficients.correct_raw_temp(raw_temp(-40.0)), 0);
+    assert_eq!(coefficients.correct_raw_rel_humid(raw_rel_humid(90.0)), u16::MAX);
+}
+
+#[test]
+fn corrects_every_datum_variant() {
+    let coefficients = ConversionCoefficients {
+        temp_slope: 1.0,
+        temp_offset_centigrade: 1.0,
+        rel_humid_slope: 1.0,
+        rel_humid_offset_percent: 2.0,
+    };
+    let t = raw_temp(20.0);
+    let rh = raw_rel_humid(40.0);
+    let t_corrected = coefficients.correct_raw_temp(t);
+    let rh_corrected = coefficients.correct_raw_rel_humid(rh);
+    assert!(t_corrected.abs_diff(raw_temp(21.0)) <= 1);
+    assert!(rh_corrected.abs_diff(raw_rel_humid(42.0)) <= 1);
+
+    assert!(matches!(
+        coefficients.correct(RawDatum::TempAndRelHumid(RawTempAndRelHumid { temperature: t, humidity: rh })),
+        RawDatum::TempAndRelHumid(RawTempAndRelHumid { temperature, humidity })
+            if temperature == t_corrected && humidity == rh_corrected
+    ));
+    assert!(matches!(coefficients.correct(RawDatum::MinTemp(t)), RawDatum::MinTemp(raw) if raw == t_corrected));
+    assert!(matches!(coefficients.correct(RawDatum::MaxTemp(t)), RawDatum::MaxTemp(raw) if raw == t_corrected));
+    assert!(matches!(coefficients.correct(RawDatum::MinRelHumid(rh)), RawDatum::MinRelHumid(raw) if raw == rh_corrected));
+    assert!(matches!(coefficients.correct(RawDatum::MaxRelHumid(rh)), RawDatum::MaxRelHumid(raw) if raw == rh_corrected));
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn driver_applies_the_coefficients() {
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode};
+
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(20.0, 40.0);
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    let coefficients = ConversionCoefficients {
+        temp_slope: 1.0,
+        temp_offset_centigrade: 1.0,
+        rel_humid_slope: 1.0,
+        rel_humid_offset_percent: 2.0,
+    };
+    hdc302x.set_conversion_coefficients(coefficients);
+    assert_eq!(hdc302x.conversion_coefficients(), coefficients);
+    let RawDatum::TempAndRelHumid(sample) = hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap() else {
+        panic!("expected a sample");
+    };
+    assert!((sample.centigrade() - 21.0).abs() < 0.01);
+    assert!((sample.humidity_percent() - 42.0).abs() < 0.01);
+}
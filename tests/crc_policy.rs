@@ -0,0 +1,44 @@
+//! What a CRC mismatch on a response does under each `CrcPolicy`
+
+#![cfg(all(feature = "blocking", feature = "crc"))]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use hdc302x::{CrcPolicy, Error, Hdc302x, I2cAddr};
+
+const ADDR: u8 = 0x44;
+const STATUS_READ: [u8; 2] = [0xf3, 0x2d];
+// 0xbeef followed by a wrong CRC (0x92 is right)
+const CORRUPT_WORD: [u8; 3] = [0xbe, 0xef, 0x00];
+
+fn read_corrupt_status(policy: CrcPolicy) -> (Result<u16, Error<embedded_hal::i2c::ErrorKind>>, u32) {
+    let expectations = [Transaction::write(ADDR, STATUS_READ.to_vec()), Transaction::read(ADDR, CORRUPT_WORD.to_vec())];
+    let mut hdc302x = Hdc302x::new(Mock::new(&expectations), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x.set_crc_policy(policy);
+    let result = hdc302x.read_status(false).map(|status| status.raw());
+    let crc_errors = hdc302x.crc_error_count();
+    hdc302x.destroy().0.done();
+    (result, crc_errors)
+}
+
+#[test]
+fn fail_policy_rejects_and_counts() {
+    let (result, crc_errors) = read_corrupt_status(CrcPolicy::Fail);
+    assert!(matches!(result, Err(Error::CrcMismatch)));
+    assert_eq!(crc_errors, 1);
+}
+
+#[test]
+fn warn_only_policy_accepts_and_counts() {
+    let (result, crc_errors) = read_corrupt_status(CrcPolicy::WarnOnly);
+    assert_eq!(result.unwrap(), 0xbeef);
+    assert_eq!(crc_errors, 1);
+}
+
+#[test]
+fn ignore_policy_accepts_without_counting() {
+    let (result, crc_errors) = read_corrupt_status(CrcPolicy::Ignore);
+    assert_eq!(result.unwrap(), 0xbeef);
+    assert_eq!(crc_errors, 0);
+}
@@ -0,0 +1,48 @@
+//! Redundancy checking between two sensors
+
+use hdc302x::{AccuracyBounds, ClimateSample, CrossCheck, RawTempAndRelHumid};
+
+struct Reference {
+    centigrade: f32,
+    humidity_percent: f32,
+}
+impl ClimateSample for Reference {
+    fn centigrade(&self) -> f32 {
+        self.centigrade
+    }
+    fn humidity_percent(&self) -> f32 {
+        self.humidity_percent
+    }
+}
+
+// 25 °C, 50 %RH
+const SAMPLE: RawTempAndRelHumid = RawTempAndRelHumid { temperature: 0x6666, humidity: 0x8000 };
+
+#[test]
+fn agreeing_pair_raises_nothing() {
+    let mut check = CrossCheck::hdc302x_pair();
+    let other = RawTempAndRelHumid { temperature: SAMPLE.temperature + 100, humidity: SAMPLE.humidity - 500 };
+    assert_eq!(check.compare(&SAMPLE, &other), None);
+    let stats = check.stats();
+    assert_eq!((stats.comparisons, stats.divergences), (1, 0));
+    assert!(stats.mean_centigrade_delta < 0.0 && stats.mean_humidity_delta_percent > 0.0);
+}
+
+#[test]
+fn divergence_beyond_combined_accuracy_is_raised() {
+    let mut check = CrossCheck::new(AccuracyBounds::HDC302X, AccuracyBounds { centigrade: 0.1, humidity_percent: 1.0 });
+    let reference = Reference { centigrade: SAMPLE.centigrade(), humidity_percent: SAMPLE.humidity_percent() - 4.0 };
+    let divergence = check.compare(&SAMPLE, &reference).unwrap();
+    assert!(divergence.humidity && !divergence.temperature);
+    assert!((divergence.humidity_delta_percent - 4.0).abs() < 1e-3);
+
+    let reference = Reference { centigrade: SAMPLE.centigrade() + 0.3, humidity_percent: SAMPLE.humidity_percent() };
+    assert_eq!(check.compare(&SAMPLE, &reference), None);
+    let stats = check.stats();
+    assert_eq!((stats.comparisons, stats.divergences), (2, 1));
+    assert!((stats.max_abs_humidity_delta_percent - 4.0).abs() < 1e-3);
+    assert!((stats.mean_humidity_delta_percent - 2.0).abs() < 1e-3);
+
+    check.reset();
+    assert_eq!(check.stats().comparisons, 0);
+}
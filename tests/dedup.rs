@@ -0,0 +1,82 @@
+//! Stale auto-mode samples after an MCU sleep
+
+use hdc302x::{FlaggedSample, MeasurementFlags, RawTempAndRelHumid, ResumeState, SampleDedup};
+
+const PERIOD_MS: u32 = 1_000;
+
+fn flagged(temperature: u16, humidity: u16) -> FlaggedSample {
+    FlaggedSample { sample: RawTempAndRelHumid { temperature, humidity }, flags: MeasurementFlags::default() }
+}
+
+fn state_before_sleep() -> ResumeState {
+    ResumeState { last_sample: RawTempAndRelHumid { temperature: 0x6000, humidity: 0x7000 }, fetched_at_ms: 10_000 }
+}
+
+#[test]
+fn blob_round_trips() {
+    let state = ResumeState {
+        last_sample: RawTempAndRelHumid { temperature: 0x1234, humidity: 0xabcd },
+        fetched_at_ms: 0x0102_0304_0506_0708,
+    };
+    let blob = state.to_bytes();
+    assert_eq!(blob, [ResumeState::BLOB_VERSION, 0x12, 0x34, 0xab, 0xcd, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(ResumeState::from_bytes(&blob), Some(state));
+}
+
+#[test]
+fn rejects_foreign_blobs() {
+    let blob = state_before_sleep().to_bytes();
+    assert_eq!(ResumeState::from_bytes(&blob[..ResumeState::BLOB_LEN - 1]), None);
+    let mut other_version = blob;
+    other_version[0] = ResumeState::BLOB_VERSION + 1;
+    assert_eq!(ResumeState::from_bytes(&other_version), None);
+}
+
+#[test]
+fn cold_boot_flags_nothing() {
+    let mut dedup = SampleDedup::new(PERIOD_MS);
+    assert_eq!(dedup.save(), None);
+    let mut sample = flagged(0x6000, 0x7000);
+    dedup.check(&mut sample, 10_100);
+    assert!(!sample.flags.stale);
+    assert_eq!(dedup.save(), Some(ResumeState { last_sample: sample.sample, fetched_at_ms: 10_100 }));
+}
+
+#[test]
+fn identical_sample_soon_after_wake_up_is_stale() {
+    let mut dedup = SampleDedup::resume(PERIOD_MS, state_before_sleep());
+    assert_eq!(dedup.save(), Some(state_before_sleep()));
+    let mut sample = flagged(0x6000, 0x7000);
+    dedup.check(&mut sample, 11_999);
+    assert!(sample.flags.stale);
+
+    // only the first sample after wake-up is compared
+    let mut sample = flagged(0x6000, 0x7000);
+    dedup.check(&mut sample, 12_100);
+    assert!(!sample.flags.stale);
+}
+
+#[test]
+fn new_or_late_samples_are_not_stale() {
+    let mut sample = flagged(0x6001, 0x7000);
+    SampleDedup::resume(PERIOD_MS, state_before_sleep()).check(&mut sample, 10_500);
+    assert!(!sample.flags.stale);
+
+    // two periods later the device has measured again, an identical value is a real one
+    let mut sample = flagged(0x6000, 0x7000);
+    SampleDedup::resume(PERIOD_MS, state_before_sleep()).check(&mut sample, 12_000);
+    assert!(!sample.flags.stale);
+}
+
+#[test]
+fn saved_state_survives_a_sleep_cycle() {
+    let mut dedup = SampleDedup::new(PERIOD_MS);
+    let mut sample = flagged(0x5555, 0x4444);
+    dedup.check(&mut sample, 50_000);
+    let blob = dedup.save().unwrap().to_bytes();
+
+    let mut dedup = SampleDedup::resume(PERIOD_MS, ResumeState::from_bytes(&blob).unwrap());
+    let mut sample = flagged(0x5555, 0x4444);
+    dedup.check(&mut sample, 50_300);
+    assert!(sample.flags.stale);
+}
@@ -0,0 +1,47 @@
+//! Heater bursts interleaved with measurement windows, and heater checks
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{DefrostCycle, DefrostSchedule, DefrostStep, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode, ManualClock};
+
+#[test]
+fn burst_waits_for_valid_samples_and_flags_settling() {
+    let clock = ManualClock::new(0);
+    let schedule = DefrostSchedule {
+        measure_ms: 1_000,
+        heater_ms: 500,
+        heater_level: HeaterLevel::On25Percent,
+        settle_ms: 2_000,
+        min_valid_samples: 2,
+    };
+    let mut cycle = DefrostCycle::new(&clock, schedule, LowPowerMode::lowest_noise());
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::Sample { valid: true, .. })));
+    clock.advance_ms(1_000);
+    // the window is over but holds only one valid sample
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::Sample { valid: true, .. })));
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::HeaterOn)));
+    clock.advance_ms(100);
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::Heating)));
+    clock.advance_ms(400);
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::HeaterOff)));
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::Sample { valid: false, .. })));
+    clock.advance_ms(2_000);
+    assert!(matches!(cycle.step(&mut hdc302x), Ok(DefrostStep::Sample { valid: true, .. })));
+
+    assert_eq!((cycle.valid_samples(), cycle.total_samples()), (3, 4));
+}
+
+#[test]
+fn heater_delta_leaves_the_heater_off() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    let delta = hdc302x.measure_heater_delta(HeaterLevel::On100Percent, 1_000, LowPowerMode::lowest_noise()).unwrap();
+    // the simulated device does not heat up
+    assert_eq!(delta.before, delta.during);
+    assert_eq!(delta.centigrade_delta(), 0.0);
+    let (sim, _) = hdc302x.destroy();
+    assert!(!sim.heater_on());
+    assert_ne!(sim.heater_config(), 0);
+}
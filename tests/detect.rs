@@ -0,0 +1,43 @@
+//! Scanning the bus for sensors
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal::i2c::{Error as _, ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{DetectedAddrs, Hdc302x, Hdc302xSim, I2cAddr};
+
+/// Several simulated devices on one bus
+struct Bus {
+    sims: Vec<(I2cAddr, Hdc302xSim)>,
+}
+impl ErrorType for Bus {
+    type Error = ErrorKind;
+}
+impl I2c for Bus {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        match self.sims.iter_mut().find(|(addr, _)| *addr as u8 == address) {
+            Some((_, sim)) => sim.transaction(address, operations).map_err(|error| error.kind()),
+            None => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+fn detect(addrs: &[I2cAddr]) -> DetectedAddrs {
+    let mut bus = Bus { sims: addrs.iter().map(|addr| (*addr, Hdc302xSim::new(*addr))).collect() };
+    Hdc302x::detect(&mut bus, &mut NoopDelay::new()).unwrap()
+}
+
+#[test]
+fn finds_strapped_addresses() {
+    let detected = detect(&[I2cAddr::Addr01, I2cAddr::Addr11]);
+    assert_eq!(detected.count(), 2);
+    assert!(detected.contains(I2cAddr::Addr01));
+    assert!(!detected.contains(I2cAddr::Addr00));
+    assert_eq!(detected.iter().collect::<Vec<_>>(), [I2cAddr::Addr01, I2cAddr::Addr11]);
+}
+
+#[test]
+fn empty_bus() {
+    assert_eq!(detect(&[]), DetectedAddrs::default());
+    assert_eq!(detect(&I2cAddr::ALL).count(), 4);
+}
@@ -0,0 +1,74 @@
+//! Relative humidity alert thresholds tracking a dew point band
+
+#![cfg(any(feature = "math", feature = "micromath"))]
+
+use hdc302x::{DewPointBand, DewPointBandController};
+
+/// Relative humidity step of the device's 7-bit alert thresholds
+const ALERT_RH_STEP_PERCENT: f32 = 100.0 * 512.0 / 65536.0;
+
+fn band() -> DewPointBand {
+    DewPointBand { low_centigrade: 2.0, high_centigrade: 12.0, hysteresis_centigrade: 1.0 }
+}
+
+#[test]
+fn thresholds_are_ordered_and_quantized() {
+    let controller = DewPointBandController::new(band());
+    let thresholds = controller.thresholds_at(20.0);
+    let all = [thresholds.set_low, thresholds.clear_low, thresholds.clear_high, thresholds.set_high];
+    for pair in all.windows(2) {
+        assert!(pair[0] < pair[1], "{thresholds:?}");
+    }
+    for percent in all {
+        let steps = percent / ALERT_RH_STEP_PERCENT;
+        assert!((steps - steps.round()).abs() < 1e-3, "{percent} is not on the alert grid");
+    }
+    // a 12 °C dew point at 20 °C is about 60 %RH, 2 °C about 30 %RH
+    assert!((thresholds.set_high - 60.0).abs() < 1.5);
+    assert!((thresholds.set_low - 30.0).abs() < 1.5);
+}
+
+#[test]
+fn warmer_air_lowers_the_thresholds() {
+    let controller = DewPointBandController::new(band());
+    let cool = controller.thresholds_at(15.0);
+    let warm = controller.thresholds_at(25.0);
+    assert!(warm.set_high < cool.set_high);
+    assert!(warm.set_low < cool.set_low);
+}
+
+#[test]
+fn rewrites_only_when_the_quantized_thresholds_change() {
+    let mut controller = DewPointBandController::new(band());
+    assert_eq!(controller.update(20.0), Some(controller.thresholds_at(20.0)));
+    assert_eq!(controller.update(20.0), None);
+    // far below one alert step
+    assert_eq!(controller.update(20.01), None);
+    assert_eq!(controller.update(25.0), Some(controller.thresholds_at(25.0)));
+    assert_eq!(controller.update(25.0), None);
+}
+
+#[test]
+fn invalidate_and_set_band_force_a_rewrite() {
+    let mut controller = DewPointBandController::new(band());
+    controller.update(20.0).unwrap();
+    controller.invalidate();
+    assert!(controller.update(20.0).is_some());
+
+    let narrow = controller.thresholds_at(20.0);
+    let wider = DewPointBand { high_centigrade: 15.0, ..band() };
+    controller.set_band(wider);
+    assert_eq!(controller.band(), wider);
+    let thresholds = controller.update(20.0).unwrap();
+    assert!(thresholds.set_high > narrow.set_high);
+    assert_eq!(thresholds.set_low, narrow.set_low);
+    assert_eq!(controller.update(20.0), None);
+}
+
+#[test]
+fn thresholds_saturate_at_the_humidity_range() {
+    let band = DewPointBand { low_centigrade: -30.0, high_centigrade: 30.0, hysteresis_centigrade: 1.0 };
+    let thresholds = DewPointBandController::new(band).thresholds_at(20.0);
+    assert!(thresholds.set_low >= 0.0);
+    assert!(thresholds.set_high <= 100.0);
+}
@@ -0,0 +1,25 @@
+//! RH tracking alert as an offset drift detector
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertDirection, DriftWatch, Hdc302x, Hdc302xSim, I2cAddr, IntegrityLimits, LowPowerMode};
+
+#[test]
+fn drift_outside_the_reference_band_fails_the_integrity_check() {
+    let watch = DriftWatch { reference_percent: 50.0, tolerance_percent: 3.0, hysteresis_percent: 1.0 };
+    let limits = IntegrityLimits { rh_alert_is_drift: true, ..Default::default() };
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(22.0, 50.5);
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.write_alert_limits(&watch.alert_limits().unwrap()).unwrap();
+    assert!(!hdc302x.integrity_check(&limits).unwrap().humidity_drift);
+
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.set_environment(22.0, 56.0);
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert_eq!(watch.check(&hdc302x.read_status(false).unwrap()), Some(AlertDirection::High));
+    assert!(hdc302x.integrity_check(&limits).unwrap().humidity_drift);
+    assert_eq!(watch.suggested_offset_percent(56.0), -6.0);
+}
@@ -0,0 +1,63 @@
+//! Guided condensation removal
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{DryingOutcome, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode};
+
+/// Delay that dries the simulated sensor by 5 %RH per second while its heater is on
+struct DryingDelay<'a> {
+    sim: &'a RefCell<Hdc302xSim>,
+    humidity_percent: f32,
+}
+impl DelayNs for DryingDelay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        let mut sim = self.sim.borrow_mut();
+        if sim.heater_on() {
+            self.humidity_percent -= 5.0 * ns as f32 / 1e9;
+            sim.set_environment(25.0, self.humidity_percent);
+        }
+    }
+}
+
+fn wet_sim(humidity_percent: f32) -> RefCell<Hdc302xSim> {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(25.0, humidity_percent);
+    RefCell::new(sim)
+}
+
+#[test]
+fn heats_until_dry() {
+    let sim = wet_sim(98.0);
+    let delay = DryingDelay { sim: &sim, humidity_percent: 98.0 };
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), delay, I2cAddr::Addr00);
+    let report = hdc302x.dry_sensor(HeaterLevel::On100Percent, 80.0, 60_000, LowPowerMode::lowest_noise()).unwrap();
+    assert_eq!(report.outcome, DryingOutcome::Dried);
+    assert!(report.last.humidity_percent() < 80.0);
+    assert!((3_000..=5_000).contains(&report.heated_ms), "heated for {} ms", report.heated_ms);
+    assert!(!sim.borrow().heater_on());
+}
+
+#[test]
+fn gives_up_after_the_timeout() {
+    let sim = wet_sim(98.0);
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), NoopDelay::new(), I2cAddr::Addr00);
+    let report = hdc302x.dry_sensor(HeaterLevel::On50Percent, 80.0, 2_500, LowPowerMode::lowest_noise()).unwrap();
+    assert_eq!(report.outcome, DryingOutcome::TimedOut);
+    assert_eq!(report.heated_ms, 2_500);
+    assert!(!sim.borrow().heater_on());
+}
+
+#[test]
+fn dry_sensor_is_left_alone() {
+    let sim = wet_sim(40.0);
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), NoopDelay::new(), I2cAddr::Addr00);
+    let report = hdc302x.dry_sensor(HeaterLevel::On50Percent, 80.0, 2_500, LowPowerMode::lowest_noise()).unwrap();
+    assert_eq!(report.outcome, DryingOutcome::AlreadyDry);
+    assert_eq!(report.heated_ms, 0);
+    assert_eq!(sim.borrow().heater_config(), 0);
+}
@@ -0,0 +1,76 @@
+//! Heater pulsed at a duty cycle
+
+#![cfg(feature = "blocking")]
+
+use std::cell::{Cell, RefCell};
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
+use hdc302x::{Hdc302x, Hdc302xSim, HeaterDutyCycle, HeaterLevel, I2cAddr, LowPowerMode};
+
+/// Delay that warms the simulated die by `rate` °C per second while the heater is on and cools it
+/// back to 25 °C while off
+struct ThermalDelay<'a> {
+    sim: &'a RefCell<Hdc302xSim>,
+    centigrade: f32,
+    rate: f32,
+}
+impl DelayNs for ThermalDelay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        let mut sim = self.sim.borrow_mut();
+        let seconds = ns as f32 / 1e9;
+        self.centigrade = match sim.heater_on() {
+            true => self.centigrade + self.rate * seconds,
+            false => (self.centigrade - seconds).max(25.0),
+        };
+        sim.set_environment(self.centigrade, 50.0);
+    }
+}
+
+const SCHEDULE: HeaterDutyCycle = HeaterDutyCycle {
+    heater_level: HeaterLevel::On50Percent,
+    on_ms: 1_000,
+    off_ms: 3_000,
+    max_rise_centigrade: 3.0,
+};
+
+fn run(rate: f32, cycles: u32, cancel: impl FnMut() -> bool) -> (hdc302x::DutyCycleReport, bool) {
+    let sim = RefCell::new(Hdc302xSim::new(I2cAddr::Addr00));
+    let delay = ThermalDelay { sim: &sim, centigrade: 25.0, rate };
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), delay, I2cAddr::Addr00);
+    let report = SCHEDULE.run(&mut hdc302x, cycles, LowPowerMode::lowest_noise(), cancel).unwrap();
+    let heater_on = sim.borrow().heater_on();
+    (report, heater_on)
+}
+
+#[test]
+fn pulses_at_the_duty_cycle() {
+    assert_eq!(SCHEDULE.duty(), 0.25);
+    let (report, heater_on) = run(1.0, 4, || false);
+    assert_eq!(report.cycles, 4);
+    assert_eq!(report.skipped_cycles, 0);
+    assert_eq!(report.heated_ms, 4_000);
+    assert!(!report.cancelled);
+    assert!(!heater_on);
+}
+
+#[test]
+fn skips_cycles_when_too_warm() {
+    let (report, heater_on) = run(4.0, 4, || false);
+    assert_eq!(report.cycles, 4);
+    assert!(report.skipped_cycles > 0);
+    assert!(report.max_rise_centigrade > SCHEDULE.max_rise_centigrade);
+    assert!(!heater_on);
+}
+
+#[test]
+fn cancel_stops_between_cycles() {
+    let checks = Cell::new(0);
+    let (report, heater_on) = run(1.0, 10, || {
+        checks.set(checks.get() + 1);
+        checks.get() > 2
+    });
+    assert!(report.cancelled);
+    assert_eq!(report.cycles, 2);
+    assert!(!heater_on);
+}
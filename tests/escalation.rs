@@ -0,0 +1,98 @@
+//! Staged handling of persistent and repeated alerts
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{
+    AlertLimits, Escalation, EscalationPolicy, EscalationStage, EscalationTrigger, Hdc302x, Hdc302xSim, HeaterLevel,
+    I2cAddr, LowPowerMode, ManualClock,
+};
+
+thread_local! {
+    static NOTIFIED: RefCell<Vec<EscalationStage>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record_stage(stage: EscalationStage) {
+    NOTIFIED.with_borrow_mut(|stages| stages.push(stage));
+}
+
+const POLICY: EscalationPolicy = EscalationPolicy {
+    log: EscalationTrigger::IMMEDIATELY,
+    notify: EscalationTrigger::after_ms_or_occurrences(1_000, 3),
+    heater: EscalationTrigger::after_ms(5_000),
+    maintenance: EscalationTrigger::after_ms_or_occurrences(10_000, 5),
+    heater_level: HeaterLevel::On25Percent,
+};
+
+#[test]
+fn persistent_condition_escalates_in_order() {
+    let clock = ManualClock::new(0);
+    let mut escalation = Escalation::new(&clock, POLICY);
+    escalation.set_hook(Some(record_stage));
+    assert_eq!(escalation.update(false), EscalationStage::Idle);
+    assert_eq!(escalation.update(true), EscalationStage::Log);
+    clock.advance_ms(1_000);
+    assert_eq!(escalation.update(true), EscalationStage::Notify);
+    // a late update enters every skipped stage
+    clock.advance_ms(20_000);
+    assert_eq!(escalation.update(true), EscalationStage::Maintenance);
+    assert!(escalation.maintenance_requested());
+    NOTIFIED.with_borrow(|stages| {
+        assert_eq!(stages.as_slice(), [EscalationStage::Notify, EscalationStage::Heater, EscalationStage::Maintenance]);
+    });
+
+    assert_eq!(escalation.update(false), EscalationStage::Idle);
+    assert!(escalation.maintenance_requested());
+    escalation.acknowledge();
+    assert!(!escalation.maintenance_requested());
+    assert_eq!(escalation.occurrences(), 0);
+}
+
+#[test]
+fn repeated_condition_escalates_by_count() {
+    let clock = ManualClock::new(0);
+    let mut escalation = Escalation::new(&clock, POLICY);
+    for _ in 0..2 {
+        assert_eq!(escalation.update(true), EscalationStage::Log);
+        escalation.update(false);
+    }
+    assert_eq!(escalation.update(true), EscalationStage::Notify);
+    assert_eq!(escalation.occurrences(), 3);
+}
+
+#[test]
+fn never_ends_the_escalation() {
+    let clock = ManualClock::new(0);
+    let policy = EscalationPolicy { heater: EscalationTrigger::NEVER, ..POLICY };
+    let mut escalation = Escalation::new(&clock, policy);
+    escalation.update(true);
+    clock.advance_ms(60_000);
+    assert_eq!(escalation.update(true), EscalationStage::Notify);
+    assert!(!escalation.maintenance_requested());
+}
+
+#[test]
+fn poll_runs_the_heater_while_escalated() {
+    let clock = ManualClock::new(0);
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(25.0, 95.0);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.write_alert_limits(&AlertLimits::COMFORT_ZONE).unwrap();
+    hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+
+    let mut escalation = Escalation::new(&clock, POLICY);
+    assert_eq!(escalation.poll(&mut hdc302x).unwrap(), EscalationStage::Log);
+    clock.advance_ms(5_000);
+    assert_eq!(escalation.poll(&mut hdc302x).unwrap(), EscalationStage::Heater);
+    hdc302x.destroy();
+    assert!(sim.heater_on());
+
+    sim.set_environment(25.0, 45.0);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert_eq!(escalation.poll(&mut hdc302x).unwrap(), EscalationStage::Idle);
+    hdc302x.destroy();
+    assert!(!sim.heater_on());
+}
@@ -0,0 +1,68 @@
+//! Operating time and high-humidity exposure as a drift estimate
+
+use hdc302x::{DriftModel, ExposureDose, RawTempAndRelHumid};
+
+const HOUR_MS: u64 = 3_600_000;
+
+fn at_humidity(percent: f32) -> RawTempAndRelHumid {
+    RawTempAndRelHumid { temperature: 0x6000, humidity: (percent * 65536.0 / 100.0) as u16 }
+}
+
+#[test]
+fn records_total_and_high_humidity_time() {
+    let model = DriftModel::default();
+    let mut dose = ExposureDose::new();
+    dose.record(&at_humidity(50.0), 10 * HOUR_MS, &model);
+    dose.record(&at_humidity(90.0), 5 * HOUR_MS, &model);
+    // at (just under) the threshold is not high humidity
+    dose.record(&at_humidity(80.0), HOUR_MS, &model);
+    assert_eq!(dose.total_hours(), 16.0);
+    assert_eq!(dose.high_humidity_hours(), 5.0);
+}
+
+#[test]
+fn one_dry_year_drifts_the_base_rate() {
+    let model = DriftModel::default();
+    let mut dose = ExposureDose::new();
+    dose.record(&at_humidity(40.0), 8766 * HOUR_MS, &model);
+    let estimate = dose.estimated_drift(&model);
+    assert!((estimate.drift_percent - 0.19).abs() < 1e-4);
+    assert!(!estimate.recalibrate);
+}
+
+#[test]
+fn high_humidity_exposure_triggers_recalibration() {
+    let model = DriftModel::default();
+    let mut dose = ExposureDose::new();
+    dose.record(&at_humidity(95.0), 1_000 * HOUR_MS, &model);
+    let estimate = dose.estimated_drift(&model);
+    assert!(!estimate.recalibrate, "{estimate:?}");
+
+    dose.record(&at_humidity(95.0), 1_000 * HOUR_MS, &model);
+    let estimate = dose.estimated_drift(&model);
+    // 2000 h at high humidity add 1 %RH, plus the base rate for the same time
+    assert!((estimate.drift_percent - (1.0 + 2_000.0 / 8766.0 * 0.19)).abs() < 1e-4);
+    assert!(estimate.recalibrate);
+}
+
+#[test]
+fn blob_round_trips() {
+    let model = DriftModel::default();
+    let mut dose = ExposureDose::new();
+    dose.record(&at_humidity(90.0), 0x0102_0304, &model);
+    dose.record(&at_humidity(10.0), 0x0100_0000, &model);
+    let blob = dose.to_bytes();
+    assert_eq!(blob[0], ExposureDose::BLOB_VERSION);
+    assert_eq!(blob[1..9], 0x0202_0304u64.to_be_bytes());
+    assert_eq!(blob[9..17], 0x0102_0304u64.to_be_bytes());
+    assert_eq!(ExposureDose::from_bytes(&blob), Some(dose));
+}
+
+#[test]
+fn rejects_foreign_blobs() {
+    let blob = ExposureDose::new().to_bytes();
+    assert_eq!(ExposureDose::from_bytes(&blob[..ExposureDose::BLOB_LEN - 1]), None);
+    let mut other_version = blob;
+    other_version[0] = ExposureDose::BLOB_VERSION + 1;
+    assert_eq!(ExposureDose::from_bytes(&other_version), None);
+}
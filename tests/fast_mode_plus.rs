@@ -0,0 +1,116 @@
+//! Timing margins at 1 MHz fast-mode-plus
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+use hdc302x::{BusSpeed, Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Event {
+    Transaction,
+    Delay { ns: u64 },
+}
+
+type Log = Rc<RefCell<Vec<Event>>>;
+
+/// Simulated device that logs every transaction
+struct LoggingBus {
+    sim: Hdc302xSim,
+    log: Log,
+}
+impl ErrorType for LoggingBus {
+    type Error = <Hdc302xSim as ErrorType>::Error;
+}
+impl I2c for LoggingBus {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push(Event::Transaction);
+        self.sim.transaction(address, operations)
+    }
+}
+
+/// Delay that only logs how long it was asked to wait
+struct LoggingDelay {
+    log: Log,
+}
+impl DelayNs for LoggingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.log.borrow_mut().push(Event::Delay { ns: u64::from(ns) });
+    }
+}
+
+fn driver(bus_hz: u32) -> (Hdc302x<LoggingBus, LoggingDelay>, Log) {
+    driver_with_sim(bus_hz, Hdc302xSim::new(I2cAddr::Addr00))
+}
+
+fn driver_with_sim(bus_hz: u32, sim: Hdc302xSim) -> (Hdc302x<LoggingBus, LoggingDelay>, Log) {
+    let log = Log::default();
+    let bus = LoggingBus { sim, log: log.clone() };
+    let delay = LoggingDelay { log: log.clone() };
+    (Hdc302x::new_with_bus_hz(bus, delay, I2cAddr::Addr00, bus_hz), log)
+}
+
+/// Shortest total delay between two consecutive transactions
+fn min_gap_ns(log: &Log) -> u64 {
+    let mut min = u64::MAX;
+    let mut since_last: Option<u64> = None;
+    for event in log.borrow().iter() {
+        match event {
+            Event::Transaction => {
+                if let Some(gap) = since_last {
+                    min = min.min(gap);
+                }
+                since_last = Some(0);
+            }
+            Event::Delay { ns } => {
+                if let Some(gap) = since_last.as_mut() {
+                    *gap += ns;
+                }
+            }
+        }
+    }
+    min
+}
+
+#[test]
+fn fast_mode_plus_keeps_the_bus_free_time() {
+    let (mut hdc302x, log) = driver(1_000_000);
+    assert_eq!(hdc302x.min_transaction_gap_us(), BusSpeed::FastPlus.bus_free_us());
+    hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    hdc302x.read_status(false).unwrap();
+    assert!(log.borrow().iter().filter(|event| **event == Event::Transaction).count() >= 4);
+    assert!(min_gap_ns(&log) >= 1_000);
+}
+
+#[test]
+fn configured_gap_applies_to_retries_and_polls() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.nack_next_writes(1);
+    sim.set_one_shot_busy_reads(3);
+    let (mut hdc302x, log) = driver_with_sim(1_000_000, sim);
+    hdc302x.set_min_transaction_gap_us(20);
+    hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert!(min_gap_ns(&log) >= 20_000);
+}
+
+#[test]
+fn command_response_gap_includes_settle_time() {
+    let (mut hdc302x, log) = driver(1_000_000);
+    log.borrow_mut().clear();
+    hdc302x.read_status(false).unwrap();
+    let settle_and_gap = u64::from(BusSpeed::FastPlus.settle_us() + BusSpeed::FastPlus.bus_free_us()) * 1_000;
+    assert!(min_gap_ns(&log) >= settle_and_gap);
+}
+
+#[test]
+fn plain_constructor_adds_no_gap() {
+    let log = Log::default();
+    let bus = LoggingBus { sim: Hdc302xSim::new(I2cAddr::Addr00), log: log.clone() };
+    let mut hdc302x = Hdc302x::new(bus, LoggingDelay { log: log.clone() }, I2cAddr::Addr00);
+    assert_eq!(hdc302x.min_transaction_gap_us(), 0);
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(min_gap_ns(&log), 0);
+}
@@ -0,0 +1,83 @@
+#![cfg(all(feature = "ffi", feature = "sim"))]
+
+use core::ffi::c_void;
+
+use hdc302x::ffi::*;
+use hdc302x::*;
+
+struct Bus {
+    sim: Hdc302xSim,
+    delayed_us: u64,
+}
+
+extern "C" fn write(ctx: *mut c_void, addr: u8, data: *const u8, len: usize) -> i32 {
+    let bus = unsafe { &mut *ctx.cast::<Bus>() };
+    let data = unsafe { core::slice::from_raw_parts(data, len) };
+    match embedded_hal::i2c::I2c::write(&mut bus.sim, addr, data) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+extern "C" fn read(ctx: *mut c_void, addr: u8, data: *mut u8, len: usize) -> i32 {
+    let bus = unsafe { &mut *ctx.cast::<Bus>() };
+    let data = unsafe { core::slice::from_raw_parts_mut(data, len) };
+    match embedded_hal::i2c::I2c::read(&mut bus.sim, addr, data) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+extern "C" fn delay_us(ctx: *mut c_void, us: u32) {
+    let bus = unsafe { &mut *ctx.cast::<Bus>() };
+    bus.delayed_us += u64::from(us);
+}
+
+fn ffi_bus(bus: &mut Bus) -> Hdc302xFfiBus {
+    Hdc302xFfiBus { ctx: (bus as *mut Bus).cast(), write, read, delay_us }
+}
+
+#[test]
+fn c_abi_keeps_driver_state_between_calls() {
+    let mut bus = Bus { sim: Hdc302xSim::new(I2cAddr::Addr00), delayed_us: 0 };
+    bus.sim.set_environment(21.5, 40.0);
+    let mut dev = core::mem::MaybeUninit::<Hdc302xFfi>::uninit();
+    let dev = dev.as_mut_ptr();
+
+    assert_eq!(unsafe { hdc302x_init(dev, ffi_bus(&mut bus), 0x44) }, 0);
+    assert!(bus.delayed_us >= u64::from(STARTUP_TIME_MS) * 1000);
+
+    // the start-up grace was spent once, during init, so every sample waits the same
+    let (mut centigrade, mut humidity) = (0.0f32, 0.0f32);
+    let mut waits = [0; 2];
+    for wait in &mut waits {
+        bus.delayed_us = 0;
+        assert_eq!(unsafe { hdc302x_measure(dev, &mut centigrade, &mut humidity) }, 0);
+        assert!((centigrade - 21.5).abs() < 0.1);
+        assert!((humidity - 40.0).abs() < 0.1);
+        *wait = bus.delayed_us;
+    }
+    assert_eq!(waits[0], waits[1]);
+
+    let before = bus.sim.thresholds();
+    assert_eq!(unsafe { hdc302x_set_alerts(dev, 0.0, 20.0, 40.0, 80.0, 2.0, 22.0, 38.0, 78.0) }, 0);
+    assert_ne!(bus.sim.thresholds(), before);
+    let mut status = 0u16;
+    assert_eq!(unsafe { hdc302x_read_status(dev, &mut status) }, 0);
+}
+
+#[test]
+fn c_abi_rejects_invalid_arguments() {
+    let mut bus = Bus { sim: Hdc302xSim::new(I2cAddr::Addr00), delayed_us: 0 };
+    let mut dev = core::mem::MaybeUninit::<Hdc302xFfi>::uninit();
+    let dev = dev.as_mut_ptr();
+    assert_eq!(unsafe { hdc302x_init(dev, ffi_bus(&mut bus), 0x40) }, HDC302X_FFI_INVALID_ARGUMENT);
+    assert_eq!(unsafe { hdc302x_init(core::ptr::null_mut(), ffi_bus(&mut bus), 0x44) }, HDC302X_FFI_INVALID_ARGUMENT);
+    let mut status = 0u16;
+    assert_eq!(unsafe { hdc302x_read_status(core::ptr::null_mut(), &mut status) }, HDC302X_FFI_INVALID_ARGUMENT);
+
+    assert_eq!(unsafe { hdc302x_init(dev, ffi_bus(&mut bus), 0x44) }, 0);
+    assert_eq!(unsafe { hdc302x_read_status(dev, core::ptr::null_mut()) }, HDC302X_FFI_INVALID_ARGUMENT);
+    // set-high below set-low
+    assert_eq!(unsafe { hdc302x_set_alerts(dev, 40.0, 20.0, 0.0, 80.0, 2.0, 22.0, 38.0, 78.0) }, HDC302X_FFI_INVALID_ARGUMENT);
+}
@@ -0,0 +1,30 @@
+//! Exact Q16.16 conversions of raw samples
+
+#![cfg(feature = "fixed")]
+
+use fixed::types::I16F16;
+use hdc302x::{centigrade_fixed, fahrenheit_fixed, humidity_percent_fixed, FixedTempAndRelHumid, RawTempAndRelHumid};
+
+#[test]
+fn fixed_conversions_are_exact() {
+    assert_eq!(centigrade_fixed(0x0000), I16F16::from_num(-45));
+    assert_eq!(centigrade_fixed(0x8000), I16F16::from_num(42.5));
+    assert_eq!(fahrenheit_fixed(0x0000), I16F16::from_num(-49));
+    assert_eq!(fahrenheit_fixed(0x8000), I16F16::from_num(108.5));
+    assert_eq!(humidity_percent_fixed(0x8000), I16F16::from_num(50));
+    // the datasheet formulas, exact in f64
+    for raw in (0..=u16::MAX).step_by(89).chain([u16::MAX]) {
+        let scaled = f64::from(raw) / 65536.0;
+        assert_eq!(centigrade_fixed(raw), I16F16::from_num(-45.0 + 175.0 * scaled));
+        assert_eq!(fahrenheit_fixed(raw), I16F16::from_num(-49.0 + 315.0 * scaled));
+        assert_eq!(humidity_percent_fixed(raw), I16F16::from_num(100.0 * scaled));
+    }
+}
+
+#[test]
+fn fixed_sample_from_raw() {
+    let sample = FixedTempAndRelHumid::from(&RawTempAndRelHumid { temperature: 0x8000, humidity: 0x4000 });
+    assert_eq!(sample.centigrade, I16F16::from_num(42.5));
+    assert_eq!(sample.fahrenheit, I16F16::from_num(108.5));
+    assert_eq!(sample.humidity_percent, I16F16::from_num(25));
+}
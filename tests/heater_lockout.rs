@@ -0,0 +1,79 @@
+//! Supply voltage checks before turning on the heater
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Error, Hdc302x, Hdc302xSim, HeaterLevel, HeaterLockout, HeaterPower, I2cAddr};
+
+const LEVELS: [HeaterLevel; 4] = [
+    HeaterLevel::On25Percent,
+    HeaterLevel::On50Percent,
+    HeaterLevel::On100Percent,
+    HeaterLevel::Custom(HeaterPower::FULL),
+];
+
+#[test]
+fn refuses_below_the_minimum() {
+    let lockout = HeaterLockout::default();
+    for level in LEVELS {
+        assert_eq!(lockout.allowed_level(level, 2_399), None);
+        assert_eq!(lockout.allowed_level(level, 0), None);
+    }
+}
+
+#[test]
+fn derates_to_quarter_power_in_the_band() {
+    let lockout = HeaterLockout::default();
+    for millivolts in [2_400, 2_699] {
+        assert_eq!(lockout.allowed_level(HeaterLevel::On50Percent, millivolts), Some(HeaterLevel::On25Percent));
+        assert_eq!(lockout.allowed_level(HeaterLevel::On100Percent, millivolts), Some(HeaterLevel::On25Percent));
+        assert_eq!(lockout.allowed_level(HeaterLevel::On25Percent, millivolts), Some(HeaterLevel::On25Percent));
+    }
+    for level in LEVELS {
+        assert_eq!(lockout.allowed_level(level, 2_700), Some(level));
+    }
+}
+
+#[test]
+fn custom_levels_within_quarter_power_are_kept() {
+    let lockout = HeaterLockout::default();
+    let within = HeaterLevel::Custom(HeaterPower::from_bits(0x0011).unwrap());
+    assert_eq!(lockout.allowed_level(within, 2_500), Some(within));
+    let quarter = HeaterLevel::Custom(HeaterPower::QUARTER);
+    assert_eq!(lockout.allowed_level(quarter, 2_500), Some(quarter));
+    // a single element outside the 25% selection is derated even though it draws little
+    let outside = HeaterLevel::Custom(HeaterPower::from_bits(0x0100).unwrap());
+    assert_eq!(lockout.allowed_level(outside, 2_500), Some(HeaterLevel::On25Percent));
+}
+
+#[test]
+fn off_is_always_allowed() {
+    let lockout = HeaterLockout::default();
+    for millivolts in [0, 2_500, 3_300] {
+        assert_eq!(lockout.allowed_level(HeaterLevel::Off, millivolts), Some(HeaterLevel::Off));
+    }
+}
+
+#[test]
+fn driver_refuses_derates_and_applies() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    let lockout = HeaterLockout::default();
+
+    let result = hdc302x.heater_with_supply(HeaterLevel::On100Percent, &lockout, &mut || 2_000);
+    assert!(matches!(result, Err(Error::SupplyTooLow)));
+    assert_eq!(Error::<()>::SupplyTooLow.code(), 10);
+
+    let applied = hdc302x.heater_with_supply(HeaterLevel::On100Percent, &lockout, &mut || 2_500).unwrap();
+    assert_eq!(applied, HeaterLevel::On25Percent);
+    assert_eq!(hdc302x.read_heater_state().unwrap().config, HeaterPower::QUARTER.bits());
+
+    let applied = hdc302x.heater_with_supply(HeaterLevel::On100Percent, &lockout, &mut || 3_300).unwrap();
+    assert_eq!(applied, HeaterLevel::On100Percent);
+
+    let applied = hdc302x.heater_with_supply(HeaterLevel::Off, &lockout, &mut || 0).unwrap();
+    assert_eq!(applied, HeaterLevel::Off);
+    hdc302x.destroy();
+    assert!(!sim.heater_on());
+}
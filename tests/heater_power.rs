@@ -0,0 +1,63 @@
+//! Custom heater power levels
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, HeaterLevel, HeaterLockout, HeaterPower, I2cAddr};
+
+#[test]
+fn validates_bit_patterns() {
+    assert_eq!(HeaterPower::from_bits(0), None);
+    assert_eq!(HeaterPower::from_bits(0x4000), None);
+    assert_eq!(HeaterPower::from_bits(0x0f0f).map(|power| power.steps()), Some(8));
+    assert_eq!(HeaterPower::from_steps(0), None);
+    assert_eq!(HeaterPower::from_steps(15), None);
+    assert_eq!(HeaterPower::from_steps(14), Some(HeaterPower::FULL));
+    assert_eq!(HeaterPower::from_steps(3).map(|power| power.bits()), Some(0x0007));
+}
+
+#[test]
+fn custom_level_is_written_to_the_device() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    let power = HeaterPower::from_steps(5).unwrap();
+    hdc302x.heater(HeaterLevel::Custom(power)).unwrap();
+    hdc302x.destroy();
+    assert!(sim.heater_on());
+    assert_eq!(sim.heater_config(), 0x001f);
+}
+
+#[test]
+fn derating_keeps_low_custom_levels() {
+    let lockout = HeaterLockout::default();
+    let low = HeaterLevel::Custom(HeaterPower::from_steps(3).unwrap());
+    let high = HeaterLevel::Custom(HeaterPower::from_steps(12).unwrap());
+    assert_eq!(lockout.allowed_level(low, 2_500), Some(low));
+    assert_eq!(lockout.allowed_level(high, 2_500), Some(HeaterLevel::On25Percent));
+    assert_eq!(lockout.allowed_level(high, 3_300), Some(high));
+}
+
+#[test]
+fn state_reads_back_and_cross_checks() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    let state = hdc302x.read_heater_state().unwrap();
+    assert!(!state.enabled);
+    assert_eq!(state.level(), Some(HeaterLevel::Off));
+    assert!(!state.is_consistent(), "the driver does not know the heater state after creation");
+
+    hdc302x.heater(HeaterLevel::On50Percent).unwrap();
+    let state = hdc302x.read_heater_state().unwrap();
+    assert_eq!(state.config, HeaterPower::HALF.bits());
+    assert_eq!(state.level(), Some(HeaterLevel::On50Percent));
+    assert!(state.is_consistent());
+
+    let power = HeaterPower::from_bits(0x0101).unwrap();
+    hdc302x.heater(HeaterLevel::Custom(power)).unwrap();
+    let state = hdc302x.read_heater_state().unwrap();
+    assert_eq!(state.power(), Some(power));
+    assert!(state.is_consistent());
+
+    hdc302x.heater(HeaterLevel::Off).unwrap();
+    assert!(hdc302x.read_heater_state().unwrap().is_consistent());
+}
@@ -0,0 +1,26 @@
+//! Float-free conversions of raw samples, checked against the floating point ones
+
+use hdc302x::{RawDatum, RawTempAndRelHumid};
+
+#[test]
+fn integer_conversions_match_float_conversions() {
+    for raw in (0..=u16::MAX).step_by(97).chain([u16::MAX]) {
+        let sample = RawTempAndRelHumid { temperature: raw, humidity: raw };
+        assert!((sample.centigrade_hundredths() as f32 - sample.centigrade() * 100.0).abs() <= 0.5 + 1e-3);
+        assert!((sample.fahrenheit_hundredths() as f32 - sample.fahrenheit() * 100.0).abs() <= 0.5 + 1e-3);
+        assert!((sample.humidity_permille() as f32 - sample.humidity_percent() * 10.0).abs() <= 0.5 + 1e-3);
+    }
+}
+
+#[test]
+fn integer_conversion_endpoints() {
+    let low = RawTempAndRelHumid { temperature: 0x0000, humidity: 0x0000 };
+    assert_eq!((low.centigrade_hundredths(), low.fahrenheit_hundredths(), low.humidity_permille()), (-4_500, -4_900, 0));
+    let mid = RawTempAndRelHumid { temperature: 0x8000, humidity: 0x8000 };
+    assert_eq!((mid.centigrade_hundredths(), mid.fahrenheit_hundredths(), mid.humidity_permille()), (4_250, 10_850, 500));
+    let high = RawTempAndRelHumid { temperature: 0xffff, humidity: 0xffff };
+    assert_eq!((high.centigrade_hundredths(), high.fahrenheit_hundredths(), high.humidity_permille()), (13_000, 26_600, 1_000));
+    assert_eq!(RawDatum::MinTemp(0x8000).centigrade_hundredths(), Some(4_250));
+    assert_eq!(RawDatum::MaxRelHumid(0x8000).humidity_permille(), Some(500));
+    assert_eq!(RawDatum::MaxRelHumid(0x8000).fahrenheit_hundredths(), None);
+}
@@ -0,0 +1,107 @@
+//! Periodic integrity check
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+
+use embedded_hal_bus::i2c::RefCellDevice;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, IntegrityLimits, IntegrityReport, SerialNumber};
+
+fn healthy_sim() -> RefCell<Hdc302xSim> {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(22.0, 45.0);
+    sim.set_serial_words([0x0102, 0x0304, 0x0506]);
+    RefCell::new(sim)
+}
+
+fn driver(sim: &RefCell<Hdc302xSim>) -> Hdc302x<RefCellDevice<'_, Hdc302xSim>, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(sim), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn healthy_device_passes() {
+    let sim = healthy_sim();
+    let mut hdc302x = driver(&sim);
+    let serial = hdc302x.read_serial_number().unwrap();
+    let limits = IntegrityLimits { expected_serial: Some(serial), ..Default::default() };
+    let report = hdc302x.integrity_check(&limits).unwrap();
+    assert!(report.passed(), "{report:?}");
+    assert_eq!(report.bits(), 0);
+    assert_eq!(report.crc_errors, 0);
+}
+
+#[test]
+fn swapped_device_changes_the_serial() {
+    let sim = healthy_sim();
+    let mut hdc302x = driver(&sim);
+    let limits = IntegrityLimits { expected_serial: Some(SerialNumber([0; 6])), ..Default::default() };
+    let report = hdc302x.integrity_check(&limits).unwrap();
+    assert!(report.serial_changed);
+    assert_eq!(report.bits(), 1 << 0);
+}
+
+#[test]
+fn device_checksum_failure_is_reported() {
+    let sim = healthy_sim();
+    let mut hdc302x = driver(&sim);
+    sim.borrow_mut().corrupt_next_data_write();
+    // the status flags the most recent data write
+    let _ = hdc302x.write_alert_set_high(30.0, 80.0);
+    let report = hdc302x.integrity_check(&IntegrityLimits::default()).unwrap();
+    assert!(report.device_checksum_failure);
+    assert_eq!(report.bits(), 1 << 1);
+}
+
+#[test]
+fn implausible_samples_are_reported() {
+    let sim = healthy_sim();
+    let mut hdc302x = driver(&sim);
+    let limits = IntegrityLimits { min_centigrade: 0.0, max_centigrade: 15.0, ..Default::default() };
+    let report = hdc302x.integrity_check(&limits).unwrap();
+    assert!(report.temperature_implausible);
+    assert_eq!(report.bits(), 1 << 2);
+
+    let limits = IntegrityLimits { min_humidity_percent: 50.0, ..Default::default() };
+    let report = hdc302x.integrity_check(&limits).unwrap();
+    assert!(report.humidity_implausible);
+    assert_eq!(report.bits(), 1 << 3);
+}
+
+#[test]
+fn report_bits_pack_every_check() {
+    let none = IntegrityReport {
+        serial_changed: false,
+        device_checksum_failure: false,
+        temperature_implausible: false,
+        humidity_implausible: false,
+        crc_errors_exceeded: false,
+        crc_errors: 7,
+        humidity_drift: false,
+    };
+    assert!(none.passed());
+    let cases = [
+        (IntegrityReport { serial_changed: true, ..none }, 0x01),
+        (IntegrityReport { device_checksum_failure: true, ..none }, 0x02),
+        (IntegrityReport { temperature_implausible: true, ..none }, 0x04),
+        (IntegrityReport { humidity_implausible: true, ..none }, 0x08),
+        (IntegrityReport { crc_errors_exceeded: true, ..none }, 0x10),
+        (IntegrityReport { humidity_drift: true, ..none }, 0x20),
+    ];
+    for (report, bits) in cases {
+        assert_eq!(report.bits(), bits);
+        assert!(!report.passed());
+    }
+    let all = IntegrityReport {
+        serial_changed: true,
+        device_checksum_failure: true,
+        temperature_implausible: true,
+        humidity_implausible: true,
+        crc_errors_exceeded: true,
+        crc_errors: 7,
+        humidity_drift: true,
+    };
+    assert_eq!(all.bits(), 0x3f);
+}
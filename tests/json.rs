@@ -0,0 +1,21 @@
+//! JSON encoding of samples
+
+#![cfg(feature = "json")]
+
+use hdc302x::{JsonSample, SerialNumber, TempAndRelHumid};
+
+#[test]
+fn encodes_fixed_field_order_and_bounded_length() {
+    let sample = TempAndRelHumid { centigrade: -4.125, fahrenheit: 24.575, humidity_percent: 45.25 };
+    let serial = SerialNumber([0xab, 0x89, 0x67, 0x45, 0x23, 0x01]);
+    assert_eq!(
+        JsonSample::encode(&sample, serial, 1000).as_str(),
+        r#"{"serial":"0123456789AB","timestamp_ms":1000,"centigrade":-4.13,"humidity_percent":45.25}"#
+    );
+
+    let extreme = TempAndRelHumid { centigrade: -1.0e9, fahrenheit: 0.0, humidity_percent: -1.0e9 };
+    let json = JsonSample::encode(&extreme, serial, u64::MAX);
+    assert_eq!(json.as_bytes().len(), 113);
+    assert_eq!(json.as_bytes().len(), JsonSample::MAX_LEN);
+    assert!(json.as_str().ends_with(r#""centigrade":-99999.99,"humidity_percent":-99999.99}"#));
+}
@@ -0,0 +1,27 @@
+//! Kelvin and millikelvin accessors of the temperature types
+
+use hdc302x::{RawDatum, RawTempAndRelHumid, Temp, TempAndRelHumid};
+
+#[test]
+fn raw_millikelvin_is_exact_and_rounded() {
+    // raw 0 is -45 °C, raw 0x8000 is 42.5 °C
+    assert_eq!(RawTempAndRelHumid { temperature: 0x0000, humidity: 0 }.millikelvin(), 228_150);
+    assert_eq!(RawTempAndRelHumid { temperature: 0x8000, humidity: 0 }.millikelvin(), 315_650);
+    // 175000 / 65536 = 2.67 mK per step
+    assert_eq!(RawTempAndRelHumid { temperature: 0x0001, humidity: 0 }.millikelvin(), 228_153);
+    assert_eq!(RawTempAndRelHumid { temperature: 0xffff, humidity: 0 }.millikelvin(), 403_147);
+    assert_eq!(RawDatum::MaxTemp(0x8000).millikelvin(), Some(315_650));
+    assert_eq!(RawDatum::MinRelHumid(0x8000).millikelvin(), None);
+    assert_eq!(RawDatum::MinTemp(0x8000).kelvin(), Some(315.65));
+}
+
+#[test]
+fn converted_types_agree_with_raw() {
+    let raw = RawTempAndRelHumid { temperature: 0x6666, humidity: 0x8000 };
+    let sample = TempAndRelHumid::from(&raw);
+    assert_eq!(sample.millikelvin(), raw.millikelvin());
+    assert!((sample.kelvin() - raw.kelvin()).abs() < 1e-3);
+    let temp = Temp::from(0x6666);
+    assert_eq!(temp.millikelvin(), raw.millikelvin());
+    assert!((temp.kelvin() - sample.centigrade - 273.15).abs() < 1e-3);
+}
@@ -0,0 +1,39 @@
+//! Instance labels in log output
+
+#![cfg(all(feature = "blocking", feature = "log"))]
+
+use std::sync::Mutex;
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr};
+
+static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Capture;
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &log::Record) {
+        LINES.lock().unwrap().push(record.args().to_string());
+    }
+    fn flush(&self) {}
+}
+static CAPTURE: Capture = Capture;
+
+#[test]
+fn log_lines_name_the_instance() {
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr01);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr01);
+    assert_eq!(hdc302x.label(), "0x45");
+    hdc302x.set_label("intake");
+    assert_eq!(hdc302x.label(), "intake");
+    hdc302x.read_status(false).unwrap();
+
+    let lines = LINES.lock().unwrap();
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|line| line.starts_with("hdc302x[intake]")), "{lines:?}");
+}
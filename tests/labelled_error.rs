@@ -0,0 +1,27 @@
+//! Instance labels in errors
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Error, Hdc302x, Hdc302xSim, I2cAddr};
+
+#[test]
+fn errors_name_the_instance() {
+    // nothing answers at the driver's address
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr01);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x.set_label("intake");
+
+    let error = hdc302x.labelled(|hdc302x| hdc302x.read_status(false)).unwrap_err();
+    assert_eq!(error.label, "intake");
+    assert!(matches!(error.error, Error::I2c(_)));
+    assert!(error.to_string().starts_with("hdc302x[intake]: I2c("), "{error}");
+}
+
+#[test]
+fn successful_calls_pass_the_result_through() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    assert!(hdc302x.labelled(|hdc302x| hdc302x.read_status(false)).is_ok());
+    assert_eq!(Error::<()>::Timeout.labelled("exhaust").to_string(), "hdc302x[exhaust]: Timeout");
+}
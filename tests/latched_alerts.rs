@@ -0,0 +1,96 @@
+//! Alert bits latched across status reads
+
+use hdc302x::{AlertClearPolicy, LatchedAlerts, StatusBits};
+
+const ANY_ALERT: u16 = 1 << 15;
+const HEATER: u16 = 1 << 13;
+const T_TRACKING: u16 = 1 << 10;
+const T_HIGH: u16 = 1 << 7;
+const RH_TRACKING: u16 = 1 << 11;
+const RH_LOW: u16 = 1 << 8;
+const RESET: u16 = 1 << 4;
+
+#[test]
+fn update_keeps_only_alert_bits() {
+    let mut latch = LatchedAlerts::new();
+    assert!(!latch.pending());
+    latch.update(StatusBits::from(ANY_ALERT | T_TRACKING | T_HIGH | HEATER | RESET));
+    assert_eq!(latch.current().raw(), ANY_ALERT | T_TRACKING | T_HIGH);
+    assert!(!latch.current().heater_enabled);
+    assert!(!latch.current().reset_since_clear);
+    assert!(latch.pending());
+}
+
+#[test]
+fn history_latches_alerts_that_cleared_between_polls() {
+    let mut latch = LatchedAlerts::new();
+    latch.update(StatusBits::from(ANY_ALERT | T_TRACKING | T_HIGH));
+    latch.update(StatusBits::from(ANY_ALERT | RH_TRACKING | RH_LOW));
+    latch.update(StatusBits::from(0));
+
+    assert_eq!(latch.current().raw(), 0);
+    let history = latch.history();
+    assert!(history.t_high_tracking_alert);
+    assert!(history.rh_low_tracking_alert);
+    assert!(history.at_least_one_alert);
+    assert_eq!(latch.cleared_since_acknowledge(), history);
+    assert!(latch.pending());
+}
+
+#[test]
+fn acknowledge_keeps_still_active_alerts() {
+    let mut latch = LatchedAlerts::new();
+    latch.update(StatusBits::from(ANY_ALERT | T_TRACKING | T_HIGH));
+    latch.update(StatusBits::from(ANY_ALERT | RH_TRACKING | RH_LOW));
+    latch.acknowledge();
+    assert_eq!(latch.history().raw(), ANY_ALERT | RH_TRACKING | RH_LOW);
+    assert_eq!(latch.cleared_since_acknowledge().raw(), 0);
+    assert!(latch.pending());
+
+    latch.update(StatusBits::from(0));
+    assert_eq!(latch.cleared_since_acknowledge().raw(), ANY_ALERT | RH_TRACKING | RH_LOW);
+    latch.acknowledge();
+    assert!(!latch.pending());
+}
+
+#[test]
+fn deliver_follows_the_clear_policy() {
+    let mut latch = LatchedAlerts::new();
+    latch.update(StatusBits::from(ANY_ALERT | T_TRACKING | T_HIGH));
+    latch.update(StatusBits::from(0));
+    assert!(latch.deliver().t_high_tracking_alert);
+    assert!(latch.pending(), "acknowledge policy keeps the history until acknowledged");
+
+    let mut latch = LatchedAlerts::with_policy(AlertClearPolicy::OnDelivery);
+    latch.update(StatusBits::from(ANY_ALERT | T_TRACKING | T_HIGH));
+    latch.update(StatusBits::from(0));
+    assert!(latch.deliver().t_high_tracking_alert);
+    assert!(!latch.pending());
+    assert_eq!(latch.deliver().raw(), 0);
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn latches_a_short_alert_from_the_device() {
+    use std::cell::RefCell;
+
+    use embedded_hal_bus::i2c::RefCellDevice;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use hdc302x::{AlertLimits, Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode};
+
+    let sim = RefCell::new(Hdc302xSim::new(I2cAddr::Addr00));
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    let limits = AlertLimits::new((0.0, 10.0), (30.0, 90.0), (2.0, 12.0), (28.0, 88.0)).unwrap();
+    hdc302x.write_alert_limits(&limits).unwrap();
+    let mut latch = LatchedAlerts::new();
+
+    for centigrade in [35.0, 20.0] {
+        sim.borrow_mut().set_environment(centigrade, 50.0);
+        hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+        latch.update(hdc302x.read_status(false).unwrap());
+    }
+    assert!(!latch.current().t_high_tracking_alert);
+    assert!(latch.history().t_high_tracking_alert);
+    assert!(latch.cleared_since_acknowledge().t_high_tracking_alert);
+}
@@ -0,0 +1,32 @@
+//! Decoding and migrating logged sample frames
+
+use hdc302x::{LogFrame, MeasurementFlags, MigrateError, RawTempAndRelHumid};
+
+const SAMPLE: RawTempAndRelHumid = RawTempAndRelHumid { temperature: 0x6666, humidity: 0x8000 };
+
+#[test]
+fn log_of_current_frames_migrates_unchanged() {
+    let mut log = [0u8; 2 * LogFrame::LEN + 4];
+    for seq in 0..2 {
+        let frame = LogFrame::encode(seq, &SAMPLE, MeasurementFlags::default());
+        log[seq as usize * LogFrame::LEN..][..LogFrame::LEN].copy_from_slice(frame.as_bytes());
+    }
+    // erased flash after the last frame
+    log[2 * LogFrame::LEN..].fill(0xff);
+
+    let mut seqs = Vec::new();
+    let result = LogFrame::migrate_log(&log, |frame| seqs.push(LogFrame::decode(&frame.0).unwrap().0));
+    assert_eq!(result, Err((2 * LogFrame::LEN, MigrateError::NotAFrame)));
+    assert_eq!(seqs, [0, 1]);
+    assert_eq!(LogFrame::migrate_log(&log[..2 * LogFrame::LEN], |_| ()), Ok(2 * LogFrame::LEN));
+}
+
+#[test]
+fn unknown_or_damaged_frames_are_refused() {
+    let mut frame = LogFrame::encode(7, &SAMPLE, MeasurementFlags::default()).0;
+    assert_eq!(LogFrame::migrate(&frame[..10]), Err(MigrateError::Truncated));
+    frame[6] ^= 1;
+    assert_eq!(LogFrame::migrate(&frame), Err(MigrateError::Corrupt));
+    frame[1] = LogFrame::VERSION + 1;
+    assert_eq!(LogFrame::migrate(&frame), Err(MigrateError::UnsupportedVersion(LogFrame::VERSION + 1)));
+}
@@ -0,0 +1,62 @@
+//! Quality flags of flagged one-shot samples
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LogFrame, LowPowerMode, MeasurementFlags};
+
+#[test]
+fn samples_taken_while_heating_are_flagged() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+    let clean = hdc302x.one_shot_flagged(LowPowerMode::lowest_noise()).unwrap();
+    assert!(!clean.flags.heater_on);
+
+    hdc302x.heater(HeaterLevel::On25Percent).unwrap();
+    let heated = hdc302x.one_shot_flagged(LowPowerMode::lowest_noise()).unwrap();
+    assert!(heated.flags.heater_on);
+    assert!(!heated.flags.is_clean());
+    assert!(!heated.flags.crc_retried);
+}
+
+#[test]
+fn new_flags_survive_log_frames() {
+    let flags = MeasurementFlags { heater_on: true, crc_retried: true, ..Default::default() };
+    let sample = hdc302x::RawTempAndRelHumid { temperature: 0x6666, humidity: 0x8000 };
+    let frame = LogFrame::encode(7, &sample, flags);
+    assert_eq!(LogFrame::decode(&frame.0), Some((7, sample, flags)));
+    assert!(MeasurementFlags { crc_retried: true, ..Default::default() }.is_clean());
+}
+
+#[cfg(feature = "crc")]
+mod crc {
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    const ADDR: u8 = 0x44;
+    const STATUS_READ: [u8; 2] = [0xf3, 0x2d];
+    const ONE_SHOT_LPM0: [u8; 2] = [0x24, 0x00];
+    // status 0x0000, then temperature 0x6666 and humidity 0x8000, each followed by its CRC
+    const STATUS: [u8; 3] = [0x00, 0x00, 0x81];
+    const SAMPLE: [u8; 6] = [0x66, 0x66, 0x93, 0x80, 0x00, 0xa2];
+    const CORRUPT_SAMPLE: [u8; 6] = [0x66, 0x67, 0x93, 0x80, 0x00, 0xa2];
+
+    #[test]
+    fn crc_mismatch_is_measured_again() {
+        let mut i2c = Mock::new(&[
+            Transaction::write(ADDR, STATUS_READ.to_vec()),
+            Transaction::read(ADDR, STATUS.to_vec()),
+            Transaction::write(ADDR, ONE_SHOT_LPM0.to_vec()),
+            Transaction::read(ADDR, CORRUPT_SAMPLE.to_vec()),
+            Transaction::write(ADDR, ONE_SHOT_LPM0.to_vec()),
+            Transaction::read(ADDR, SAMPLE.to_vec()),
+        ]);
+        let mut hdc302x = Hdc302x::new(&mut i2c, NoopDelay::new(), I2cAddr::Addr00);
+        hdc302x.set_startup_grace_ms(0);
+        let sample = hdc302x.one_shot_flagged(LowPowerMode::LPM0).unwrap();
+        assert!(sample.flags.crc_retried);
+        assert_eq!(sample.sample.temperature, 0x6666);
+        hdc302x.destroy();
+        i2c.done();
+    }
+}
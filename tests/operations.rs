@@ -0,0 +1,91 @@
+//! Driver-borrowing tokens for multi-step operations
+
+#![cfg(feature = "blocking")]
+
+use std::cell::RefCell;
+
+use embedded_hal_bus::i2c::RefCellDevice;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, DryingOutcome, Error, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode, OperationProgress, ProvisioningPlan, SampleRate};
+
+fn driver_with(sim: Hdc302xSim) -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn provisioning_runs_the_planned_steps_in_order() {
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    let plan = ProvisioningPlan {
+        alert_limits: Some(AlertLimits::GREENHOUSE),
+        store_alert_thresholds: true,
+        rh_offset_percent: Some(-2.0),
+        power_on_mode: Some((SampleRate::Auto1Hz, LowPowerMode::LPM0)),
+        ..ProvisioningPlan::default()
+    };
+    let mut provisioning = hdc302x.begin_provisioning(plan).unwrap();
+    assert_eq!(provisioning.step().unwrap(), OperationProgress::InProgress { done: 1, total: 5 });
+    let config = provisioning.finish().unwrap();
+    let limits = AlertLimits::GREENHOUSE;
+    assert_eq!(
+        [config.alert_set_low, config.alert_set_high, config.alert_clear_low, config.alert_clear_high],
+        [limits.set_low().raw, limits.set_high().raw, limits.clear_low().raw, limits.clear_high().raw]
+    );
+    assert_eq!(hdc302x.nv_write_count(), 3);
+    assert_eq!(hdc302x.read_power_on_mode().unwrap(), Some((SampleRate::Auto1Hz, LowPowerMode::LPM0)));
+    assert!((hdc302x.read_offsets().unwrap().humidity_percent + 2.0).abs() < 0.2);
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.nv_thresholds(), sim.thresholds());
+}
+
+#[test]
+fn provisioning_rejects_an_out_of_range_offset_without_confirming_writes() {
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    let plan = ProvisioningPlan { temp_offset_centigrade: Some(50.0), ..ProvisioningPlan::default() };
+    assert!(matches!(hdc302x.begin_provisioning(plan), Err(Error::InvalidInputData)));
+    assert!(matches!(hdc302x.write_rh_offset(1.0), Err(Error::NvWriteRefused)));
+    assert_eq!(hdc302x.nv_write_count(), 0);
+}
+
+#[test]
+fn recondition_heats_step_by_step_and_turns_the_heater_off() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(25.0, 95.0);
+    let mut hdc302x = driver_with(sim);
+    let mut recondition = hdc302x.begin_recondition(HeaterLevel::On50Percent, 80.0, 2_500, LowPowerMode::lowest_noise());
+    assert_eq!(recondition.step().unwrap(), OperationProgress::InProgress { done: 1, total: 4 });
+    assert_eq!(recondition.step().unwrap(), OperationProgress::InProgress { done: 2, total: 4 });
+    let report = recondition.finish().unwrap();
+    assert_eq!(report.outcome, DryingOutcome::TimedOut);
+    assert_eq!(report.heated_ms, 2_500);
+    let (sim, _) = hdc302x.destroy();
+    assert!(!sim.heater_on());
+}
+
+#[test]
+fn recondition_abort_turns_the_heater_off() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(25.0, 95.0);
+    let mut hdc302x = driver_with(sim);
+    let mut recondition = hdc302x.begin_recondition(HeaterLevel::On50Percent, 80.0, 2_500, LowPowerMode::lowest_noise());
+    recondition.step().unwrap();
+    recondition.abort().unwrap();
+    let (sim, _) = hdc302x.destroy();
+    assert!(!sim.heater_on());
+}
+
+#[test]
+fn recondition_returns_the_sample_error_and_turns_the_heater_off() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(25.0, 95.0);
+    let sim = RefCell::new(sim);
+    let mut hdc302x = Hdc302x::new(RefCellDevice::new(&sim), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    let mut recondition = hdc302x.begin_recondition(HeaterLevel::On50Percent, 80.0, 2_500, LowPowerMode::lowest_noise());
+    recondition.step().unwrap();
+    assert!(sim.borrow().heater_on());
+    sim.borrow_mut().truncate_next_read(2);
+    assert!(matches!(recondition.step(), Err(Error::ShortRead { .. })));
+    assert!(!sim.borrow().heater_on());
+}
@@ -0,0 +1,102 @@
+//! Fixed-period sampling driven by a clock
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, ManualClock, Pacer};
+
+fn driver_with<D: DelayNs>(delay: D) -> Hdc302x<Hdc302xSim, D> {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), delay, I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+/// Delay that moves the virtual clock forward by the time waited
+struct ClockDelay<'a>(&'a ManualClock);
+impl DelayNs for ClockDelay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.advance_ms(u64::from(ns.div_ceil(1_000_000)));
+    }
+}
+
+#[test]
+fn first_sample_is_due_immediately() {
+    let clock = ManualClock::new(1_000);
+    let mut pacer = Pacer::new(&clock, 100);
+    assert_eq!(pacer.period_ms(), 100);
+    assert!(pacer.is_due());
+    assert_eq!(pacer.until_due_ms(), 0);
+    pacer.mark_sampled();
+    assert_eq!(pacer.until_due_ms(), 100);
+}
+
+#[test]
+fn slightly_late_samples_keep_the_phase() {
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 100);
+    pacer.mark_sampled();
+    // sampled 30 ms late, the next sample is still due at 200
+    clock.set_ms(130);
+    assert!(pacer.is_due());
+    pacer.mark_sampled();
+    assert_eq!(pacer.until_due_ms(), 70);
+    // just under a period late still keeps the phase
+    clock.set_ms(299);
+    pacer.mark_sampled();
+    assert_eq!(pacer.until_due_ms(), 1);
+}
+
+#[test]
+fn samples_more_than_a_period_late_reschedule() {
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 100);
+    pacer.mark_sampled();
+    // due at 100, sampled at 250: the missed slot at 200 is skipped, not caught up
+    clock.set_ms(250);
+    pacer.mark_sampled();
+    assert_eq!(pacer.until_due_ms(), 100);
+    clock.advance_ms(100);
+    assert!(pacer.is_due());
+}
+
+#[test]
+fn period_change_applies_after_the_next_sample() {
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 100);
+    pacer.mark_sampled();
+    pacer.set_period_ms(500);
+    assert_eq!(pacer.until_due_ms(), 100);
+    clock.set_ms(100);
+    pacer.mark_sampled();
+    assert_eq!(pacer.until_due_ms(), 500);
+}
+
+#[test]
+fn one_shot_if_due_skips_until_due() {
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 1_000);
+    let mut hdc302x = driver_with(NoopDelay::new());
+    let low_power_mode = LowPowerMode::lowest_noise();
+    assert!(hdc302x.one_shot_if_due(low_power_mode, &mut pacer).unwrap().is_some());
+    clock.advance_ms(999);
+    assert!(hdc302x.one_shot_if_due(low_power_mode, &mut pacer).unwrap().is_none());
+    clock.advance_ms(1);
+    assert!(hdc302x.one_shot_if_due(low_power_mode, &mut pacer).unwrap().is_some());
+    assert_eq!(pacer.until_due_ms(), 1_000);
+}
+
+#[test]
+fn one_shot_paced_waits_for_the_next_slot() {
+    let clock = ManualClock::new(0);
+    let mut pacer = Pacer::new(&clock, 1_000);
+    let mut hdc302x = driver_with(ClockDelay(&clock));
+    let low_power_mode = LowPowerMode::lowest_noise();
+    for slot in 0..3 {
+        hdc302x.one_shot_paced(low_power_mode, &mut pacer).unwrap();
+        // the sample was taken at the start of its slot, plus the conversion time
+        let now = pacer.now_ms();
+        assert!(now >= slot * 1_000 && now < slot * 1_000 + 100, "sampled at {now} ms");
+    }
+    assert!(pacer.until_due_ms() > 900);
+}
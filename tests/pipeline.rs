@@ -0,0 +1,101 @@
+//! Composable sample processing stages
+
+use std::cell::RefCell;
+
+use hdc302x::{Ema, Filter, Map, Median, Pipeline, Sink, Stage, TempAndRelHumid, ThresholdFlags, Thresholds, TimeWeightedMean};
+
+fn sample(centigrade: f32, humidity_percent: f32) -> TempAndRelHumid {
+    TempAndRelHumid { centigrade, fahrenheit: centigrade * 9.0 / 5.0 + 32.0, humidity_percent }
+}
+
+#[test]
+fn median_waits_for_a_full_window_and_rejects_spikes() {
+    let mut median = Median::<3>::new();
+    assert!(median.process(sample(20.0, 40.0)).is_none());
+    assert!(median.process(sample(21.0, 90.0)).is_none());
+    let out = median.process(sample(85.0, 41.0)).unwrap();
+    assert_eq!((out.centigrade, out.humidity_percent), (21.0, 41.0));
+    assert_eq!(out.fahrenheit, 21.0 * 9.0 / 5.0 + 32.0);
+    // the window slides: 21, 85, 22
+    let out = median.process(sample(22.0, 42.0)).unwrap();
+    assert_eq!((out.centigrade, out.humidity_percent), (22.0, 42.0));
+}
+
+#[test]
+fn even_median_averages_the_middle_pair() {
+    let mut median = Median::<4>::default();
+    for centigrade in [10.0, 40.0, 20.0] {
+        assert!(median.process(sample(centigrade, 50.0)).is_none());
+    }
+    assert_eq!(median.process(sample(30.0, 50.0)).unwrap().centigrade, 25.0);
+}
+
+#[test]
+fn ema_starts_at_the_first_sample_and_converges() {
+    let mut ema = Ema::new(0.5);
+    assert_eq!(ema.process(sample(20.0, 40.0)).unwrap().centigrade, 20.0);
+    let out = ema.process(sample(30.0, 60.0)).unwrap();
+    assert_eq!((out.centigrade, out.humidity_percent), (25.0, 50.0));
+    assert_eq!(out.fahrenheit, 77.0);
+    let out = ema.process(sample(30.0, 60.0)).unwrap();
+    assert_eq!((out.centigrade, out.humidity_percent), (27.5, 55.0));
+
+    let mut follow = Ema::new(1.0);
+    follow.process(sample(20.0, 40.0));
+    assert_eq!(follow.process(sample(30.0, 60.0)).unwrap().centigrade, 30.0);
+}
+
+#[test]
+fn stages_chain_into_a_pipeline() {
+    let seen = RefCell::new(Vec::new());
+    let mut pipeline = Pipeline::new(Median::<3>::new())
+        .then(Ema::new(0.5))
+        .then(Filter(|sample: &TempAndRelHumid| sample.humidity_percent < 50.0))
+        .then(Map(|sample: TempAndRelHumid| sample.centigrade))
+        .then(Sink(|centigrade: f32| seen.borrow_mut().push(centigrade)));
+    assert!(pipeline.push(sample(20.0, 40.0)).is_none());
+    assert!(pipeline.push(sample(20.0, 40.0)).is_none());
+    assert_eq!(pipeline.push(sample(20.0, 40.0)), Some(()));
+    // the median passes 20 °C, the average moves half way
+    assert_eq!(pipeline.push(sample(40.0, 40.0)), Some(()));
+    assert_eq!(pipeline.push(sample(40.0, 40.0)), Some(()));
+    // one humid sample is removed by the median, two push the average over the filter limit
+    assert_eq!(pipeline.push(sample(40.0, 95.0)), Some(()));
+    assert!(pipeline.push(sample(40.0, 95.0)).is_none());
+    assert_eq!(*seen.borrow(), [20.0, 20.0, 30.0, 35.0]);
+}
+
+#[test]
+fn thresholds_flag_each_limit() {
+    let mut thresholds = Thresholds { min_centigrade: 0.0, max_centigrade: 30.0, min_humidity_percent: 20.0, max_humidity_percent: 80.0 };
+    let (_, flags) = thresholds.process(sample(20.0, 50.0)).unwrap();
+    assert!(!flags.any());
+    let (_, flags) = thresholds.process(sample(35.0, 10.0)).unwrap();
+    assert_eq!(flags, ThresholdFlags { temp_high: true, rh_low: true, ..Default::default() });
+    assert!(flags.any());
+}
+
+#[test]
+fn time_weighted_mean_integrates_between_samples() {
+    let mut mean = TimeWeightedMean::new();
+    assert!(mean.process((1_000, sample(20.0, 40.0))).is_none());
+    // 20 → 30 over 1 s averages 25, then 30 for 3 s
+    assert_eq!(mean.process((2_000, sample(30.0, 40.0))).unwrap().centigrade, 25.0);
+    let out = mean.process((5_000, sample(30.0, 40.0))).unwrap();
+    assert_eq!(out.centigrade, (25.0 + 3.0 * 30.0) / 4.0);
+    assert_eq!(mean.span_ms(), 4_000);
+    // a repeated timestamp adds no weight
+    assert_eq!(mean.process((5_000, sample(90.0, 40.0))).unwrap().centigrade, out.centigrade);
+    mean.reset();
+    assert!(mean.mean().is_none());
+}
+
+#[cfg(any(feature = "math", feature = "micromath"))]
+#[test]
+fn dew_point_stage_attaches_the_dew_point() {
+    use hdc302x::DewPoint;
+
+    let out = DewPoint.process(sample(20.0, 50.0)).unwrap();
+    assert_eq!(out.sample.centigrade, 20.0);
+    assert!((out.dew_point_centigrade - 9.3).abs() < 0.1);
+}
@@ -0,0 +1,37 @@
+//! Confirming an HDC302x answers before using it
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+use hdc302x::{Error, Hdc302x, Hdc302xSim, I2cAddr};
+
+const ADDR: u8 = 0x44;
+const MANUFACTURER_ID: [u8; 2] = [0x37, 0x81];
+// 0x1234 followed by its CRC
+const OTHER_ID: [u8; 3] = [0x12, 0x34, 0x37];
+
+#[test]
+fn finds_the_device() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let hdc302x = Hdc302x::probe(&mut sim, NoopDelay::new(), I2cAddr::Addr00).unwrap();
+    hdc302x.destroy();
+    let (_, serial_number) = Hdc302x::probe_with_serial(&mut sim, NoopDelay::new(), I2cAddr::Addr00).unwrap();
+    assert_eq!(serial_number.fields().bytes_5_4, 0x1234);
+}
+
+#[test]
+fn wrong_address_straps() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    let result = Hdc302x::probe(&mut sim, NoopDelay::new(), I2cAddr::Addr11);
+    assert!(matches!(result, Err(Error::DeviceNotFound)));
+    assert_eq!(Error::<()>::DeviceNotFound.code(), 17);
+}
+
+#[test]
+fn other_device_at_the_address() {
+    let mut i2c = Mock::new(&[Transaction::write(ADDR, MANUFACTURER_ID.to_vec()), Transaction::read(ADDR, OTHER_ID.to_vec())]);
+    let result = Hdc302x::probe(&mut i2c, NoopDelay::new(), I2cAddr::Addr00);
+    assert!(matches!(result, Err(Error::DeviceNotFound)));
+    i2c.done();
+}
@@ -0,0 +1,106 @@
+//! Psychrometric derivations against reference values
+
+#![cfg(any(feature = "math", feature = "micromath"))]
+
+use hdc302x::{absolute_humidity_g_per_m3, dew_point_centigrade, frost_point_centigrade, heat_index, humidex, mixing_ratio_g_per_kg, specific_humidity_g_per_kg, vapor_pressure_deficit_kpa, Psychrometrics, ReferenceTemperature, TempAndRelHumid};
+
+fn sample(centigrade: f32, humidity_percent: f32) -> TempAndRelHumid {
+    TempAndRelHumid { centigrade, fahrenheit: centigrade * 1.8 + 32.0, humidity_percent }
+}
+
+fn assert_near(actual: f32, expected: f32, tolerance: f32) {
+    assert!((actual - expected).abs() <= tolerance, "{actual} is not within {tolerance} of {expected}");
+}
+
+#[test]
+fn dew_point() {
+    assert_near(dew_point_centigrade(25.0, 50.0), 13.9, 0.1);
+    assert_near(dew_point_centigrade(20.0, 100.0), 20.0, 0.01);
+    assert_near(dew_point_centigrade(0.0, 80.0), -3.0, 0.1);
+    assert_near(sample(30.0, 70.0).dew_point_centigrade(), 23.9, 0.1);
+    assert!(dew_point_centigrade(25.0, 0.0) < -100.0);
+}
+
+#[test]
+fn frost_point() {
+    // above freezing the frost point is the dew point
+    assert_eq!(frost_point_centigrade(25.0, 50.0), dew_point_centigrade(25.0, 50.0));
+    // below freezing saturation over ice is reached before saturation over water
+    assert_near(frost_point_centigrade(-10.0, 80.0), -11.4, 0.1);
+    assert!(frost_point_centigrade(-10.0, 80.0) > dew_point_centigrade(-10.0, 80.0));
+    assert_near(sample(5.0, 30.0).frost_point_centigrade(), -10.0, 0.3);
+}
+
+#[test]
+fn absolute_humidity() {
+    assert_near(absolute_humidity_g_per_m3(25.0, 50.0, None), 11.5, 0.1);
+    assert_near(absolute_humidity_g_per_m3(0.0, 100.0, None), 4.85, 0.05);
+    assert_eq!(absolute_humidity_g_per_m3(25.0, 0.0, None), 0.0);
+    // moist air holds slightly more vapor than pure water vapor over a flat surface
+    let corrected = sample(25.0, 50.0).absolute_humidity_g_per_m3(Some(1013.25));
+    assert_near(corrected / absolute_humidity_g_per_m3(25.0, 50.0, None), 1.004, 0.001);
+}
+
+#[test]
+fn heat_index_follows_nws_table() {
+    // NWS heat index chart: 90 °F at 70 %RH feels like 106 °F
+    let hot = heat_index((90.0 - 32.0) / 1.8, 70.0);
+    assert_near(hot.fahrenheit, 106.0, 1.0);
+    assert_near(hot.centigrade, (hot.fahrenheit - 32.0) / 1.8, 1e-4);
+    // mild conditions use the simple formula, close to the air temperature
+    assert_near(heat_index(20.0, 50.0).centigrade, 19.6, 0.3);
+    // dry heat is adjusted down, humid warmth up
+    assert!(heat_index((100.0 - 32.0) / 1.8, 5.0).fahrenheit < 95.0);
+    assert_near(sample((85.0 - 32.0) / 1.8, 90.0).heat_index().fahrenheit, 102.0, 1.0);
+}
+
+#[test]
+fn vapor_pressure_deficit() {
+    assert_near(vapor_pressure_deficit_kpa(25.0, 50.0), 1.58, 0.02);
+    assert_near(vapor_pressure_deficit_kpa(25.0, 100.0), 0.0, 1e-6);
+    assert_near(sample(20.0, 0.0).vapor_pressure_deficit_kpa(), 2.34, 0.02);
+}
+
+#[test]
+fn vapor_pressure_deficit_uses_the_reference_temperature() {
+    let mut psychrometrics = Psychrometrics::with_temperature_source(ReferenceTemperature(20.0));
+    let vpd = psychrometrics.vapor_pressure_deficit_kpa(&sample(25.0, 50.0));
+    assert_near(vpd, vapor_pressure_deficit_kpa(20.0, 50.0), 1e-6);
+    assert_near(Psychrometrics::new().vapor_pressure_deficit_kpa(&sample(25.0, 50.0)), 1.58, 0.02);
+}
+
+#[test]
+fn humidex_follows_environment_canada_table() {
+    // 30 °C with a 15 °C dew point: humidex 34
+    let humidity = 100.0 * humidity_ratio_for_dew_point(15.0, 30.0);
+    assert_near(humidex(30.0, humidity), 34.0, 0.5);
+    assert_near(sample(30.0, humidity).humidex(), humidex(30.0, humidity), 1e-6);
+    // dry air feels cooler than the air temperature
+    assert!(humidex(20.0, 10.0) < 20.0);
+}
+
+// relative humidity ratio at `centigrade` for the given dew point (Magnus formula)
+fn humidity_ratio_for_dew_point(dew_point: f32, centigrade: f32) -> f32 {
+    let gamma = |t: f32| 17.62 * t / (243.12 + t);
+    (gamma(dew_point) - gamma(centigrade)).exp()
+}
+
+#[test]
+fn mixing_ratio_and_specific_humidity() {
+    // 25 °C, 50 %RH at sea level: about 9.9 g/kg
+    assert_near(mixing_ratio_g_per_kg(25.0, 50.0, None), 9.9, 0.1);
+    assert_near(specific_humidity_g_per_kg(25.0, 50.0, None), 9.8, 0.1);
+    assert!(specific_humidity_g_per_kg(25.0, 50.0, None) < mixing_ratio_g_per_kg(25.0, 50.0, None));
+    // the same air holds more vapor per kilogram at altitude
+    let high = sample(25.0, 50.0);
+    assert!(high.mixing_ratio_g_per_kg(Some(700.0)) > high.mixing_ratio_g_per_kg(Some(1013.25)));
+    assert_eq!(mixing_ratio_g_per_kg(25.0, 0.0, Some(900.0)), 0.0);
+}
+
+#[test]
+fn mixing_ratio_and_specific_humidity_use_the_reference_temperature() {
+    let mut psychrometrics = Psychrometrics::with_temperature_source(ReferenceTemperature(20.0));
+    let reading = sample(25.0, 50.0);
+    assert_near(psychrometrics.mixing_ratio_g_per_kg(&reading, Some(950.0)), mixing_ratio_g_per_kg(20.0, 50.0, Some(950.0)), 1e-6);
+    assert_near(psychrometrics.specific_humidity_g_per_kg(&reading, None), specific_humidity_g_per_kg(20.0, 50.0, None), 1e-6);
+}
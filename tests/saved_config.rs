@@ -0,0 +1,65 @@
+//! Capturing the device configuration and pushing it back after a reset
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, DeviceMode, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode, SampleRate, SimMode};
+
+fn driver_with(sim: Hdc302xSim) -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn apply_restores_a_saved_config_after_reset() {
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    hdc302x.write_alert_limits(&AlertLimits::FREEZER).unwrap();
+    hdc302x.confirm_nv_write();
+    hdc302x.write_temp_offset(1.0).unwrap();
+    hdc302x.heater(HeaterLevel::On25Percent).unwrap();
+    hdc302x.auto_start(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).unwrap();
+    let config = hdc302x.save_config().unwrap();
+    assert_eq!(config.heater, Some(HeaterLevel::On25Percent));
+    assert_eq!(config.auto_mode, Some((SampleRate::Auto1Hz, LowPowerMode::lowest_noise())));
+    assert_eq!(hdc302x.mode(), DeviceMode::Auto);
+    hdc302x.auto_stop().unwrap();
+    // the offset drifts away from the saved one, e.g. a field recalibration gone wrong
+    hdc302x.confirm_nv_write();
+    hdc302x.write_temp_offset(-2.0).unwrap();
+
+    let (mut sim, delay) = hdc302x.destroy();
+    sim.power_cycle();
+    let thresholds = [config.alert_set_low, config.alert_set_high, config.alert_clear_low, config.alert_clear_high];
+    assert_ne!(sim.thresholds(), thresholds);
+    assert_ne!(sim.nv_offset(), config.offsets);
+    assert!(!sim.heater_on());
+    assert_eq!(sim.mode(), SimMode::Sleep);
+
+    let mut hdc302x = Hdc302x::new(sim, delay, I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x.read_status(true).unwrap();
+    hdc302x.confirm_nv_write();
+    hdc302x.apply_config(&config).unwrap();
+    assert_eq!(hdc302x.mode(), DeviceMode::Auto);
+    assert_eq!(hdc302x.save_config().unwrap(), config);
+
+    let (sim, _) = hdc302x.destroy();
+    assert_eq!(sim.thresholds(), thresholds);
+    assert_eq!(sim.nv_offset(), config.offsets);
+    assert!(sim.heater_on());
+    assert_eq!(sim.mode(), SimMode::Auto);
+}
+
+#[test]
+fn apply_skips_unchanged_offsets() {
+    let mut hdc302x = driver_with(Hdc302xSim::new(I2cAddr::Addr00));
+    let config = hdc302x.save_config().unwrap();
+    assert_eq!(config.heater, None);
+    assert_eq!(config.auto_mode, None);
+    let nv_writes = hdc302x.nv_write_count();
+    // no confirm_nv_write(): the offsets match, so no NV write is needed
+    hdc302x.apply_config(&config).unwrap();
+    assert_eq!(hdc302x.nv_write_count(), nv_writes);
+    assert_eq!(hdc302x.mode(), DeviceMode::Unknown);
+}
@@ -0,0 +1,56 @@
+//! serde round trips of data and configuration types
+
+#![cfg(feature = "serde")]
+
+use hdc302x::{AlertLimits, HeaterLevel, HeaterPower, LowPowerMode, RawTempAndRelHumid, SampleRate, SavedConfig, SerialNumber, StatusBits, TempAndRelHumid};
+use serde_json_core::{from_str, to_slice};
+
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    let mut buf = [0u8; 256];
+    let len = to_slice(value, &mut buf).unwrap();
+    from_str(core::str::from_utf8(&buf[..len]).unwrap()).unwrap().0
+}
+
+#[test]
+fn data_types_round_trip() {
+    let sample = TempAndRelHumid::from(&RawTempAndRelHumid { temperature: 0x6666, humidity: 0x8000 });
+    let back = round_trip(&sample);
+    assert_eq!((back.centigrade, back.fahrenheit, back.humidity_percent), (sample.centigrade, sample.fahrenheit, sample.humidity_percent));
+    let status = StatusBits::from(0xa010);
+    assert_eq!(round_trip(&status), status);
+    let serial = SerialNumber([1, 2, 3, 4, 5, 6]);
+    assert_eq!(round_trip(&serial), serial);
+}
+
+#[test]
+fn status_bits_serialize_as_the_register_word() {
+    let mut buf = [0u8; 16];
+    let len = to_slice(&StatusBits::from(0x2010), &mut buf).unwrap();
+    assert_eq!(&buf[..len], b"8208");
+}
+
+#[test]
+fn config_types_round_trip() {
+    assert_eq!(round_trip(&AlertLimits::GREENHOUSE), AlertLimits::GREENHOUSE);
+    let config = SavedConfig {
+        alert_set_low: 0x1234,
+        alert_set_high: 0xcdcc,
+        alert_clear_low: 0x1439,
+        alert_clear_high: 0xc9c7,
+        offsets: 0x0000,
+        heater: HeaterPower::from_bits(0x0f).map(HeaterLevel::Custom),
+        auto_mode: Some((SampleRate::Auto1Hz, LowPowerMode::LPM1)),
+    };
+    assert_eq!(round_trip(&config), config);
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    // set low above set high
+    let inverted = r#"{"set_low":65535,"set_high":0,"clear_low":0,"clear_high":0}"#;
+    assert!(from_str::<AlertLimits>(inverted).is_err());
+    // bit outside the heater element mask
+    assert!(from_str::<HeaterPower>("16384").is_err());
+    assert!(from_str::<HeaterPower>("0").is_err());
+    assert_eq!(from_str::<HeaterPower>("1023").unwrap().0, HeaterPower::HALF);
+}
@@ -0,0 +1,44 @@
+//! Serial number decoding
+
+use hdc302x::{SerialByteOrder, SerialNumber, SerialNumberFields};
+
+/// Bytes 5 to 0 are 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc
+fn serial() -> SerialNumber {
+    SerialNumber([0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12])
+}
+
+#[test]
+fn fields_are_big_endian_by_default() {
+    let fields = SerialNumberFields { bytes_5_4: 0x1234, bytes_3_2: 0x5678, bytes_1_0: 0x9abc };
+    assert_eq!(serial().fields(), fields);
+    assert_eq!(serial().fields_in(SerialByteOrder::BigEndian), fields);
+}
+
+#[test]
+fn little_endian_swaps_the_bytes_of_each_word() {
+    let fields = serial().fields_in(SerialByteOrder::LittleEndian);
+    assert_eq!(fields, SerialNumberFields { bytes_5_4: 0x3412, bytes_3_2: 0x7856, bytes_1_0: 0xbc9a });
+}
+
+#[test]
+fn fields_agree_with_the_other_representations() {
+    let serial = serial();
+    let fields = serial.fields();
+    let from_fields = (u64::from(fields.bytes_5_4) << 32) | (u64::from(fields.bytes_3_2) << 16) | u64::from(fields.bytes_1_0);
+    assert_eq!(serial.as_u64(), from_fields);
+    assert_eq!(serial.to_be_bytes(), [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]);
+    assert_eq!(serial.to_string(), "123456789ABC");
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn fields_match_the_words_read_from_the_device() {
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr};
+
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_serial_words([0x1234, 0x5678, 0x9abc]);
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    assert_eq!(hdc302x.read_serial_number().unwrap(), serial());
+}
@@ -0,0 +1,99 @@
+//! Sharing the bus with other devices through `embedded-hal-bus` and `embassy-embedded-hal`
+//!
+//! The probing delays check that the driver does not hold the bus while it waits, e.g. between
+//! polls for a one-shot result.
+
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum};
+
+fn busy_sim() -> Hdc302xSim {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_one_shot_busy_reads(3);
+    sim
+}
+
+#[cfg(feature = "blocking")]
+mod blocking {
+    use super::*;
+    use std::cell::RefCell;
+
+    use embedded_hal::delay::DelayNs;
+    use embedded_hal_bus::i2c::RefCellDevice;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+
+    /// Delay that checks the bus is free, as another device's driver would need it
+    struct ProbingDelay<'a> {
+        bus: &'a RefCell<Hdc302xSim>,
+        probes: &'a RefCell<u32>,
+    }
+    impl DelayNs for ProbingDelay<'_> {
+        fn delay_ns(&mut self, _ns: u32) {
+            assert!(self.bus.try_borrow_mut().is_ok(), "bus held across a delay");
+            *self.probes.borrow_mut() += 1;
+        }
+    }
+
+    #[test]
+    fn refcell_device_is_free_during_delays() {
+        let bus = RefCell::new(busy_sim());
+        let probes = RefCell::new(0);
+        let delay = ProbingDelay { bus: &bus, probes: &probes };
+        let mut hdc302x = Hdc302x::new(RefCellDevice::new(&bus), delay, I2cAddr::Addr00);
+        assert!(matches!(hdc302x.one_shot(LowPowerMode::lowest_noise()), Ok(RawDatum::TempAndRelHumid(_))));
+        assert!(*probes.borrow() > 0);
+        assert_eq!(bus.borrow().command_count(), 1);
+    }
+
+    #[test]
+    fn borrowed_bus_is_accepted() {
+        let mut sim = busy_sim();
+        let mut hdc302x = Hdc302x::new(&mut sim, NoopDelay::new(), I2cAddr::Addr00);
+        hdc302x.read_status(false).unwrap();
+        hdc302x.destroy();
+        assert_eq!(sim.command_count(), 1);
+    }
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embassy_sync::mutex::Mutex;
+    use embedded_hal_async::delay::DelayNs;
+
+    struct ProbingDelay<'a> {
+        bus: &'a Mutex<NoopRawMutex, Hdc302xSim>,
+        probes: u32,
+    }
+    impl DelayNs for ProbingDelay<'_> {
+        async fn delay_ns(&mut self, _ns: u32) {
+            assert!(self.bus.try_lock().is_ok(), "bus held across a delay");
+            self.probes += 1;
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn mutex_device_is_free_during_delays() {
+        let bus = Mutex::<NoopRawMutex, _>::new(busy_sim());
+        let delay = ProbingDelay { bus: &bus, probes: 0 };
+        let mut hdc302x = Hdc302x::new(I2cDevice::new(&bus), delay, I2cAddr::Addr00);
+        let datum = block_on(hdc302x.one_shot_async(LowPowerMode::lowest_noise()));
+        assert!(matches!(datum, Ok(RawDatum::TempAndRelHumid(_))));
+        let (_, delay) = hdc302x.destroy();
+        assert!(delay.probes > 0);
+    }
+}
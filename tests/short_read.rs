@@ -0,0 +1,31 @@
+//! Responses cut short by the bus
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Error, Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum};
+
+fn driver_with(sim: Hdc302xSim) -> Hdc302x<Hdc302xSim, NoopDelay> {
+    Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00)
+}
+
+#[test]
+fn truncated_sample_is_reported_not_decoded() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.truncate_next_read(3);
+    let mut hdc302x = driver_with(sim);
+    let result = hdc302x.one_shot(LowPowerMode::lowest_noise());
+    assert!(matches!(result, Err(Error::ShortRead { expected: 6, got: 3 })));
+    assert_eq!(hdc302x.crc_error_count(), 0);
+
+    let sample = hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap();
+    assert!(matches!(sample, RawDatum::TempAndRelHumid(_)));
+}
+
+#[test]
+fn empty_read_is_reported() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.truncate_next_read(0);
+    let mut hdc302x = driver_with(sim);
+    assert!(matches!(hdc302x.read_status(false), Err(Error::ShortRead { expected: 3, got: 0 })));
+}
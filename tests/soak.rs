@@ -0,0 +1,171 @@
+//! Randomized soak test of the driver against the simulated device: mode transitions, resets,
+//! fault injection, alert flows and non-volatile writes.
+//!
+//! ```sh
+//! HDC302X_SOAK_ITERATIONS=10000000 HDC302X_SOAK_SEED=42 cargo test --release --features soak --test soak
+//! ```
+
+#![cfg(feature = "soak")]
+
+use std::cell::RefCell;
+use std::env;
+use std::rc::Rc;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{
+    AlertLimits, AutoReadTarget, Error, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr, LowPowerMode, RawDatum, SampleRate,
+    SimMode, SimNack,
+};
+
+/// Bus shared between the driver and the test, so faults can be injected mid-session
+#[derive(Clone)]
+struct SharedSim(Rc<RefCell<Hdc302xSim>>);
+impl ErrorType for SharedSim {
+    type Error = SimNack;
+}
+impl I2c for SharedSim {
+    fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), SimNack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+/// xorshift64*, good enough to pick operations reproducibly from a seed
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * (self.below(1 << 24) as f32 / (1 << 24) as f32)
+    }
+}
+
+const RATES: [SampleRate; 6] = [
+    SampleRate::OneShot,
+    SampleRate::Auto500mHz,
+    SampleRate::Auto1Hz,
+    SampleRate::Auto2Hz,
+    SampleRate::Auto4Hz,
+    SampleRate::Auto10Hz,
+];
+const MODES: [LowPowerMode; 4] = [LowPowerMode::LPM0, LowPowerMode::LPM1, LowPowerMode::LPM2, LowPowerMode::LPM3];
+const HEATER: [HeaterLevel; 4] = [
+    HeaterLevel::Off,
+    HeaterLevel::On25Percent,
+    HeaterLevel::On50Percent,
+    HeaterLevel::On100Percent,
+];
+const TARGETS: [AutoReadTarget; 5] = [
+    AutoReadTarget::LastTempAndRelHumid,
+    AutoReadTarget::MinTemp,
+    AutoReadTarget::MaxTemp,
+    AutoReadTarget::MinRelHumid,
+    AutoReadTarget::MaxRelHumid,
+];
+const PRESETS: [AlertLimits; 3] = [AlertLimits::COMFORT_ZONE, AlertLimits::FREEZER, AlertLimits::GREENHOUSE];
+
+fn env_u64(name: &str, default: u64) -> u64 {
+    env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+/// Errors the injected faults and mode changes may legitimately cause
+fn expected_error(error: &Error<SimNack>) -> bool {
+    matches!(
+        error,
+        Error::I2c(_)
+            | Error::ShortRead { .. }
+            | Error::AutoModeActive
+            | Error::AutoModeRequired
+            | Error::NvWriteRefused
+            | Error::Timeout
+    )
+}
+
+#[test]
+fn soak() {
+    let iterations = env_u64("HDC302X_SOAK_ITERATIONS", 1_000_000);
+    let seed = env_u64("HDC302X_SOAK_SEED", 0x5eed_3020);
+    let mut rng = Rng(seed | 1);
+
+    let sim = Rc::new(RefCell::new(Hdc302xSim::new(I2cAddr::Addr00)));
+    let mut hdc302x = Hdc302x::new(SharedSim(Rc::clone(&sim)), NoopDelay::new(), I2cAddr::Addr00);
+    let mut environment = (25.0, 50.0);
+
+    for iteration in 0..iterations {
+        let context = |what: &str| format!("seed {seed:#x}, iteration {iteration}: {what}");
+        let result = match rng.below(16) {
+            // faults make a command fail, never return a wrong sample
+            0 => hdc302x.one_shot(rng.pick(&MODES)).map(|datum| {
+                let RawDatum::TempAndRelHumid(sample) = datum else {
+                    panic!("{}", context(&format!("one_shot returned {datum:?}")));
+                };
+                assert!((sample.centigrade() - environment.0).abs() < 0.01, "{}", context("temperature"));
+                assert!((sample.humidity_percent() - environment.1).abs() < 0.01, "{}", context("humidity"));
+            }),
+            1 => {
+                let sample_rate = rng.pick(&RATES);
+                hdc302x.auto_start(sample_rate, rng.pick(&MODES)).map(|()| {
+                    if sample_rate != SampleRate::OneShot {
+                        assert_eq!(sim.borrow().mode(), SimMode::Auto, "{}", context("auto_start"));
+                    }
+                })
+            }
+            2 => hdc302x
+                .auto_stop()
+                .map(|()| assert_eq!(sim.borrow().mode(), SimMode::Sleep, "{}", context("auto_stop"))),
+            3 => hdc302x.auto_read(rng.pick(&TARGETS)).map(|_| ()),
+            4 => {
+                sim.borrow_mut().advance_auto(rng.below(5) as u32);
+                Ok(())
+            }
+            5 => {
+                let level = rng.pick(&HEATER);
+                hdc302x
+                    .heater(level)
+                    .map(|()| assert_eq!(sim.borrow().heater_on(), level != HeaterLevel::Off, "{}", context("heater")))
+            }
+            6 => hdc302x.read_status(rng.below(2) == 0).map(|_| ()),
+            7 => {
+                environment = (rng.range(-40.0, 125.0), rng.range(0.0, 100.0));
+                sim.borrow_mut().set_environment(environment.0, environment.1);
+                Ok(())
+            }
+            8 => hdc302x.write_alert_limits(&rng.pick(&PRESETS)),
+            9 => hdc302x.read_alert_report().map(|_| ()),
+            10 => {
+                sim.borrow_mut().power_cycle();
+                Ok(())
+            }
+            11 => hdc302x.software_reset(),
+            12 => {
+                sim.borrow_mut().nack_next_writes(1 + rng.below(3) as u8);
+                Ok(())
+            }
+            13 => {
+                // cut at a word boundary: a cut inside a word is only caught when the CRC over the
+                // bytes left in the buffer fails, which misses 1 in 256
+                sim.borrow_mut().truncate_next_read(3 * rng.below(2) as usize);
+                Ok(())
+            }
+            14 => hdc302x.one_shot_flagged(rng.pick(&MODES)).map(|_| ()),
+            _ => {
+                hdc302x.confirm_nv_write();
+                hdc302x.write_rh_offset(rng.range(-20.0, 20.0)).map(|_| ())
+            }
+        };
+        if let Err(error) = &result {
+            assert!(expected_error(error), "{}", context(&format!("unexpected error {error:?}")));
+        }
+    }
+    assert!(hdc302x.nv_write_count() <= hdc302x::NV_WRITE_SESSION_LIMIT);
+}
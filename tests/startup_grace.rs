@@ -0,0 +1,58 @@
+//! Start-up grace before the first command after creation or a software reset
+
+#![cfg(feature = "blocking")]
+
+use std::cell::Cell;
+
+use embedded_hal::delay::DelayNs;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, STARTUP_TIME_MS};
+
+/// Delay that only adds up the time it was asked to wait
+struct TotalDelay<'a>(&'a Cell<u64>);
+impl DelayNs for TotalDelay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.set(self.0.get() + u64::from(ns));
+    }
+}
+
+#[test]
+fn grace_is_waited_once_before_the_first_command() {
+    let total = Cell::new(0);
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), TotalDelay(&total), I2cAddr::Addr00);
+    assert_eq!(hdc302x.ready_after(), STARTUP_TIME_MS);
+    assert_eq!(total.get(), 0, "creating the driver does not wait");
+
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(total.get(), u64::from(STARTUP_TIME_MS) * 1_000_000);
+    assert_eq!(hdc302x.ready_after(), 0);
+
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(total.get(), u64::from(STARTUP_TIME_MS) * 1_000_000);
+}
+
+#[test]
+fn custom_grace_is_rearmed_by_a_software_reset() {
+    let total = Cell::new(0);
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), TotalDelay(&total), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(12);
+    assert_eq!(hdc302x.ready_after(), 12);
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(total.get(), 12_000_000);
+
+    hdc302x.software_reset().unwrap();
+    assert_eq!(hdc302x.ready_after(), 12);
+    total.set(0);
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(total.get(), 12_000_000);
+    assert_eq!(hdc302x.ready_after(), 0);
+}
+
+#[test]
+fn zero_grace_does_not_wait() {
+    let total = Cell::new(0);
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), TotalDelay(&total), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    assert_eq!(hdc302x.ready_after(), 0);
+    hdc302x.read_status(false).unwrap();
+    assert_eq!(total.get(), 0);
+}
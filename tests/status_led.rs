@@ -0,0 +1,85 @@
+//! Sensor state shown on an LED
+
+use std::cell::Cell;
+use std::convert::Infallible;
+
+use embedded_hal::digital::{ErrorType, OutputPin};
+use hdc302x::{Error, LedPattern, LedPatterns, LedState, StatusBits, StatusLed};
+
+/// Pin whose level the test can watch while the LED driver owns it
+struct Pin<'a>(&'a Cell<bool>);
+impl ErrorType for Pin<'_> {
+    type Error = Infallible;
+}
+impl OutputPin for Pin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.set(false);
+        Ok(())
+    }
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+/// LED level at each of `times_ms`
+fn levels(led: &mut StatusLed<Pin<'_>>, level: &Cell<bool>, times_ms: &[u64]) -> Vec<bool> {
+    times_ms
+        .iter()
+        .map(|&now_ms| {
+            led.update(now_ms).unwrap();
+            level.get()
+        })
+        .collect()
+}
+
+#[test]
+fn default_patterns() {
+    let level = Cell::new(false);
+    let mut led = StatusLed::new(Pin(&level));
+    assert_eq!(led.state(), LedState::Ok);
+    // 50 ms heartbeat every 2 s, counted from the first update
+    assert_eq!(levels(&mut led, &level, &[1_000, 1_049, 1_050, 2_999, 3_000]), [true, true, false, false, true]);
+
+    led.set_state(LedState::Alert);
+    assert_eq!(levels(&mut led, &level, &[5_000, 5_124, 5_125, 5_250]), [true, true, false, true]);
+
+    led.set_state(LedState::SensorMissing);
+    assert_eq!(levels(&mut led, &level, &[6_000, 6_500, 9_999]), [true, true, true]);
+
+    led.set_state(LedState::Recovering);
+    assert_eq!(levels(&mut led, &level, &[7_000, 7_499, 7_500, 8_000]), [true, true, false, true]);
+}
+
+#[test]
+fn pattern_restarts_only_on_a_state_change() {
+    let level = Cell::new(false);
+    let mut led = StatusLed::new(Pin(&level));
+    led.set_state(LedState::Recovering);
+    assert_eq!(levels(&mut led, &level, &[0, 600]), [true, false]);
+    // setting the same state keeps the phase
+    led.set_state(LedState::Recovering);
+    assert_eq!(levels(&mut led, &level, &[700]), [false]);
+}
+
+#[test]
+fn custom_and_steady_patterns() {
+    let patterns = LedPatterns { ok: LedPattern::OFF, alert: LedPattern { on_ms: 1, period_ms: 3 }, ..Default::default() };
+    let level = Cell::new(true);
+    let mut led = StatusLed::with_patterns(Pin(&level), patterns);
+    assert_eq!(levels(&mut led, &level, &[0, 1_000]), [false, false]);
+    led.set_state(LedState::Alert);
+    assert_eq!(levels(&mut led, &level, &[10, 11, 12, 13]), [true, false, false, true]);
+    let Pin(released) = led.release();
+    assert!(std::ptr::eq(released, &level));
+}
+
+#[test]
+fn state_from_a_status_read() {
+    let ok: Result<StatusBits, Error<()>> = Ok(StatusBits::from(0));
+    assert_eq!(LedState::from_status(&ok), LedState::Ok);
+    let alert: Result<StatusBits, Error<()>> = Ok(StatusBits::from(1 << 15));
+    assert_eq!(LedState::from_status(&alert), LedState::Alert);
+    let missing: Result<StatusBits, Error<()>> = Err(Error::I2c(()));
+    assert_eq!(LedState::from_status(&missing), LedState::SensorMissing);
+}
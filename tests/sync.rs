@@ -0,0 +1,90 @@
+//! Thread-safe driver handle and background sampler for std applications
+
+#![cfg(all(feature = "std", feature = "blocking"))]
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use embedded_hal_bus::i2c::MutexDevice;
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum, SyncHdc302x};
+
+type SyncSim = SyncHdc302x<MutexDevice<'static, Hdc302xSim>, NoopDelay>;
+
+/// The simulator stays reachable by the test while the sampler thread owns the driver
+fn sync_driver(centigrade: f32, humidity_percent: f32) -> (SyncSim, &'static Mutex<Hdc302xSim>) {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.set_environment(centigrade, humidity_percent);
+    let sim: &'static Mutex<Hdc302xSim> = Box::leak(Box::new(Mutex::new(sim)));
+    let mut hdc302x = Hdc302x::new(MutexDevice::new(sim), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    (SyncHdc302x::new(hdc302x), sim)
+}
+
+fn wait_for(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn handle_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SyncSim>();
+}
+
+#[test]
+fn clones_share_one_driver_across_threads() {
+    let (hdc302x, sim) = sync_driver(22.0, 45.0);
+    let commands = sim.lock().unwrap().command_count();
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let hdc302x = hdc302x.clone();
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    let RawDatum::TempAndRelHumid(sample) = hdc302x.one_shot(LowPowerMode::lowest_noise()).unwrap() else {
+                        panic!("expected a sample");
+                    };
+                    assert!((sample.centigrade() - 22.0).abs() < 0.01);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(sim.lock().unwrap().command_count(), commands + 40);
+}
+
+#[test]
+fn sampler_publishes_the_latest_sample() {
+    let (hdc302x, _) = sync_driver(18.5, 60.0);
+    assert_eq!(hdc302x.latest(), None);
+    let sampler = hdc302x.spawn_sampler(Duration::from_millis(1), LowPowerMode::lowest_noise());
+    wait_for(|| hdc302x.latest().is_some());
+    sampler.stop();
+
+    let sample = hdc302x.latest().unwrap();
+    assert!((sample.centigrade() - 18.5).abs() < 0.01);
+    assert!((sample.humidity_percent() - 60.0).abs() < 0.01);
+    assert_eq!(hdc302x.sample_errors(), 0);
+    // the driver is usable again once the sampler is stopped
+    assert!(hdc302x.one_shot(LowPowerMode::lowest_noise()).is_ok());
+}
+
+#[test]
+fn sampler_counts_errors_and_stops_on_drop() {
+    let (hdc302x, sim) = sync_driver(18.5, 60.0);
+    sim.lock().unwrap().nack_next_writes(u8::MAX);
+    let sampler = hdc302x.spawn_sampler(Duration::from_millis(1), LowPowerMode::lowest_noise());
+    wait_for(|| hdc302x.sample_errors() >= 2);
+    drop(sampler);
+
+    let errors = hdc302x.sample_errors();
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(hdc302x.sample_errors(), errors, "the sampler thread was stopped");
+    assert_eq!(hdc302x.latest(), None);
+}
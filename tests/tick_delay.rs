@@ -0,0 +1,65 @@
+//! Busy-wait delay over a free-running tick counter
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal::delay::DelayNs;
+use hdc302x::{Hdc302x, Hdc302xSim, I2cAddr, TickDelay, TickSource};
+
+/// Counter advancing by one tick on every read
+struct FakeTicks {
+    now: u32,
+    reads: u32,
+    hz: u32,
+}
+impl FakeTicks {
+    fn starting_at(now: u32, hz: u32) -> Self {
+        Self { now, reads: 0, hz }
+    }
+}
+impl TickSource for FakeTicks {
+    fn ticks(&mut self) -> u32 {
+        self.reads += 1;
+        self.now = self.now.wrapping_add(1);
+        self.now
+    }
+    fn tick_hz(&self) -> u32 {
+        self.hz
+    }
+}
+
+#[test]
+fn waits_at_least_the_requested_time() {
+    let mut delay = TickDelay::new(FakeTicks::starting_at(0, 1_000_000));
+    delay.delay_ms(2);
+    let ticks = delay.release().now;
+    assert!(ticks >= 2_000, "waited {ticks} ticks");
+    assert!(ticks <= 2_003, "waited {ticks} ticks");
+}
+
+#[test]
+fn rounds_short_delays_up_to_a_tick() {
+    let mut delay = TickDelay::new(FakeTicks::starting_at(0, 1_000));
+    delay.delay_ns(1);
+    assert!(delay.release().now >= 2);
+
+    let mut delay = TickDelay::new(FakeTicks::starting_at(0, 1_000));
+    delay.delay_ns(0);
+    assert_eq!(delay.release().reads, 0);
+}
+
+#[test]
+fn survives_counter_wrap_around() {
+    let mut delay = TickDelay::new(FakeTicks::starting_at(u32::MAX - 10, 1_000));
+    delay.delay_ms(100);
+    let ticks = delay.release().now.wrapping_sub(u32::MAX - 10);
+    assert!((100..=103).contains(&ticks), "waited {ticks} ticks");
+}
+
+#[test]
+fn drives_the_sensor() {
+    let mut ticks = FakeTicks::starting_at(0, 32_768);
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), TickDelay::new(&mut ticks), I2cAddr::Addr00);
+    hdc302x.one_shot(hdc302x::LowPowerMode::lowest_noise()).unwrap();
+    hdc302x.destroy();
+    assert!(ticks.now > 0);
+}
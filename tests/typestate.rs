@@ -0,0 +1,42 @@
+//! Mode transitions through the typestate wrapper
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AutoReadTarget, DeviceMode, Error, Hdc302xTyped, Hdc302xSim, I2cAddr, LowPowerMode, RawDatum, SampleRate, SimMode};
+
+#[test]
+fn one_shot_then_auto_then_sleep() {
+    let hdc302x = Hdc302xTyped::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    let pending = hdc302x.start_one_shot(LowPowerMode::lowest_noise()).map_err(|(_, error)| error).unwrap();
+    let (hdc302x, sample) = pending.read_one_shot();
+    assert!(matches!(sample, Ok(RawDatum::TempAndRelHumid(_))));
+
+    let mut hdc302x = hdc302x.into_auto(SampleRate::Auto1Hz, LowPowerMode::lowest_noise()).map_err(|(_, error)| error).unwrap();
+    assert!(hdc302x.auto_read(AutoReadTarget::LastTempAndRelHumid).is_ok());
+    let hdc302x = hdc302x.into_sleep().map_err(|(_, error)| error).unwrap();
+
+    let (sim, _) = hdc302x.into_inner().destroy();
+    assert_eq!(sim.mode(), SimMode::Sleep);
+}
+
+#[test]
+fn one_shot_rate_is_not_an_auto_mode() {
+    let hdc302x = Hdc302xTyped::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    let Err((mut hdc302x, error)) = hdc302x.into_auto(SampleRate::OneShot, LowPowerMode::lowest_noise()) else {
+        panic!("one-shot rate accepted as auto mode");
+    };
+    assert!(matches!(error, Error::InvalidInputData));
+    assert!(hdc302x.one_shot(LowPowerMode::lowest_noise()).is_ok());
+}
+
+#[test]
+fn plain_driver_refuses_commands_while_result_pending() {
+    let hdc302x = Hdc302xTyped::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    let pending = hdc302x.start_one_shot(LowPowerMode::lowest_noise()).map_err(|(_, error)| error).unwrap();
+    let mut hdc302x = pending.into_inner();
+    assert_eq!(hdc302x.mode(), DeviceMode::MeasurementPending);
+    assert!(matches!(hdc302x.read_status(false), Err(Error::WrongMode(DeviceMode::MeasurementPending))));
+    hdc302x.software_reset().unwrap();
+    assert_eq!(hdc302x.mode(), DeviceMode::Unknown);
+}
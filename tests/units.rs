@@ -0,0 +1,47 @@
+//! `uom` quantities for samples, alert thresholds and offsets
+
+#![cfg(all(feature = "blocking", feature = "uom"))]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{AlertLimits, Hdc302x, Hdc302xSim, I2cAddr, RawDatum, RawTempAndRelHumid, TempAndRelHumid};
+use uom::si::f32::{Ratio, TemperatureInterval, ThermodynamicTemperature};
+use uom::si::{ratio::percent, temperature_interval, thermodynamic_temperature::{degree_celsius, kelvin}};
+
+fn celsius(value: f32) -> ThermodynamicTemperature {
+    ThermodynamicTemperature::new::<degree_celsius>(value)
+}
+
+#[test]
+fn samples_convert_to_quantities() {
+    let raw = RawTempAndRelHumid { temperature: 0x8000, humidity: 0x8000 };
+    assert!((raw.temperature().get::<kelvin>() - 315.65).abs() < 1e-3);
+    assert!((raw.relative_humidity().get::<percent>() - 50.0).abs() < 1e-4);
+    let sample = TempAndRelHumid::from(&raw);
+    assert!((sample.temperature().get::<degree_celsius>() - 42.5).abs() < 1e-3);
+    assert_eq!(RawDatum::MaxRelHumid(0x8000).temperature(), None);
+    assert!(RawDatum::MaxRelHumid(0x8000).relative_humidity().is_some());
+}
+
+#[test]
+fn alert_limits_from_quantities_match_plain_values() {
+    let rh = Ratio::new::<percent>;
+    let limits = AlertLimits::new_uom((celsius(18.0), rh(25.0)), (celsius(28.0), rh(65.0)), (celsius(20.0), rh(30.0)), (celsius(26.0), rh(60.0)));
+    assert_eq!(limits, Ok(AlertLimits::COMFORT_ZONE));
+    let set_high = AlertLimits::COMFORT_ZONE.set_high();
+    assert_eq!(set_high.temperature().get::<degree_celsius>(), celsius(set_high.centigrade).get::<degree_celsius>());
+}
+
+#[test]
+fn offsets_round_trip_as_quantities() {
+    let mut hdc302x = Hdc302x::new(Hdc302xSim::new(I2cAddr::Addr00), NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x.confirm_nv_write();
+    let programmed = hdc302x.write_temp_offset_uom(TemperatureInterval::new::<temperature_interval::kelvin>(1.0)).unwrap();
+    assert!((programmed.get::<temperature_interval::degree_celsius>() - 1.0).abs() < 0.2);
+    hdc302x.confirm_nv_write();
+    let programmed = hdc302x.write_rh_offset_uom(Ratio::new::<percent>(-2.0)).unwrap();
+    assert!((programmed.get::<percent>() + 2.0).abs() < 0.2);
+    let offsets = hdc302x.read_offsets().unwrap();
+    assert_eq!(offsets.relative_humidity(), programmed);
+    assert_eq!(offsets.temperature().get::<temperature_interval::degree_celsius>(), offsets.centigrade);
+}
@@ -0,0 +1,37 @@
+//! Status read-back after data writes
+
+#![cfg(feature = "blocking")]
+
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use hdc302x::{Error, Hdc302x, Hdc302xSim, HeaterLevel, I2cAddr};
+
+fn driver_with(sim: Hdc302xSim) -> Hdc302x<Hdc302xSim, NoopDelay> {
+    let mut hdc302x = Hdc302x::new(sim, NoopDelay::new(), I2cAddr::Addr00);
+    hdc302x.set_startup_grace_ms(0);
+    hdc302x
+}
+
+#[test]
+fn rejected_write_is_reported_when_verifying() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.corrupt_next_data_write();
+    let mut hdc302x = driver_with(sim);
+    hdc302x.set_write_verification(true);
+    let result = hdc302x.write_alert_set_high(30.0, 60.0);
+    assert!(matches!(result, Err(Error::DeviceRejectedWrite)));
+    assert_eq!(Error::<()>::DeviceRejectedWrite.code(), 16);
+
+    hdc302x.write_alert_set_high(30.0, 60.0).unwrap();
+    hdc302x.heater(HeaterLevel::On25Percent).unwrap();
+    let (sim, _) = hdc302x.destroy();
+    assert_ne!(sim.heater_config(), 0);
+}
+
+#[test]
+fn rejected_write_goes_unnoticed_without_verification() {
+    let mut sim = Hdc302xSim::new(I2cAddr::Addr00);
+    sim.corrupt_next_data_write();
+    let mut hdc302x = driver_with(sim);
+    hdc302x.write_alert_set_high(30.0, 60.0).unwrap();
+    assert!(hdc302x.read_status(false).unwrap().checksum_failure);
+}